@@ -0,0 +1,45 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use aredis::command::{Append, Command, Get};
+use bytes::BytesMut;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+fn encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode");
+    let gets: Vec<_> = (0..1000)
+        .map(|i| Get::new(format!("key:{}", i).into_bytes()))
+        .collect();
+    let appends: Vec<_> = (0..1000)
+        .map(|i| Append::new(format!("key:{}", i).into_bytes(), vec![b'x'; 100]))
+        .collect();
+    let mut out = BytesMut::new();
+    group.throughput(Throughput::Elements(1000));
+    group.bench_function("get", |b| {
+        b.iter(|| {
+            out.clear();
+            gets.iter().for_each(|cmd| cmd.encode(&mut out));
+        })
+    });
+    group.bench_function("append", |b| {
+        b.iter(|| {
+            out.clear();
+            appends.iter().for_each(|cmd| cmd.encode(&mut out));
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, encode);
+criterion_main!(benches);
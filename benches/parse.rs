@@ -0,0 +1,146 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use aredis::{Client, Model};
+use bytes::{BufMut, Bytes, BytesMut};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use futures::{
+    executor::block_on,
+    io::{AsyncRead, AsyncWrite},
+};
+
+fn bulk(out: &mut BytesMut, value: &[u8]) {
+    out.put_slice(format!("${}\r\n", value.len()).as_bytes());
+    out.put_slice(value);
+    out.put_slice(b"\r\n");
+}
+
+/// What MGET of `n` keys holding `size`-byte values replies.
+fn mget_reply(n: usize, size: usize) -> Bytes {
+    let mut out = BytesMut::new();
+    out.put_slice(format!("*{}\r\n", n).as_bytes());
+    for _ in 0..n {
+        bulk(&mut out, &vec![b'x'; size]);
+    }
+    out.freeze()
+}
+
+/// What HGETALL of a hash with `n` fields replies under RESP3.
+fn hgetall_reply(n: usize) -> Bytes {
+    let mut out = BytesMut::new();
+    out.put_slice(format!("%{}\r\n", n).as_bytes());
+    for i in 0..n {
+        bulk(&mut out, format!("field:{}", i).as_bytes());
+        bulk(&mut out, format!("value:{}", i).as_bytes());
+    }
+    out.freeze()
+}
+
+/// Replays `script` at most `piece` bytes a read, as a socket delivers a large
+/// reply, and discards whatever is written.
+struct Pieces {
+    script: Bytes,
+    piece: usize,
+}
+
+impl AsyncRead for Pieces {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let n = buf.len().min(self.piece).min(self.script.len());
+        buf[..n].copy_from_slice(&self.script.split_to(n));
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl AsyncWrite for Pieces {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for (n, size) in [(100, 16), (1000, 16), (100, 16 * 1024)] {
+        let reply = mget_reply(n, size);
+        group.throughput(Throughput::Bytes(reply.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("mget", format!("{}x{}", n, size)),
+            &reply,
+            |b, reply| b.iter(|| Model::parse(reply, 0).unwrap().unwrap()),
+        );
+    }
+    for n in [100, 1000] {
+        let reply = hgetall_reply(n);
+        group.throughput(Throughput::Bytes(reply.len() as u64));
+        group.bench_with_input(BenchmarkId::new("hgetall", n), &reply, |b, reply| {
+            b.iter(|| Model::parse(reply, 0).unwrap().unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Receives a large reply through a client, 4 KiB a read.
+fn recv(c: &mut Criterion) {
+    let mut group = c.benchmark_group("recv");
+    for (n, size) in [(1000, 16), (100, 16 * 1024)] {
+        let reply = mget_reply(n, size);
+        let mut script = BytesMut::from(&b"+OK\r\n+OK\r\n"[..]);
+        script.put_slice(&reply);
+        let script = script.freeze();
+        group.throughput(Throughput::Bytes(reply.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("mget", format!("{}x{}", n, size)),
+            &script,
+            |b, script| {
+                b.iter_batched(
+                    || {
+                        let script = script.clone();
+                        block_on(Client::from_stream(Pieces {
+                            script,
+                            piece: 4096,
+                        }))
+                        .unwrap()
+                    },
+                    |mut client| block_on(client.call(vec!["MGET"])).unwrap(),
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse, recv);
+criterion_main!(benches);
@@ -0,0 +1,40 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use aredis::proxy::Proxy;
+
+/// Relays to the upstream given as the first argument, listening on the
+/// second, and prints every exchange. A third argument names a file to record
+/// the session to.
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let upstream = args.next().unwrap_or_else(|| "127.0.0.1:6379".to_string());
+    let listen = args.next().unwrap_or_else(|| "127.0.0.1:6380".to_string());
+    let mut proxy = Proxy::new(upstream).listen(listen).inspect(|exchange| {
+        println!(
+            "#{} {:?} -> {:?} in {:?}",
+            exchange.connection, exchange.request, exchange.reply, exchange.elapsed
+        )
+    });
+    if let Some(path) = args.next() {
+        proxy = proxy.record(path);
+    }
+    let proxy = proxy.start().await?;
+    println!("aredis-proxy listening on {}", proxy.addr());
+    tokio::signal::ctrl_c().await?;
+    proxy.flush().await;
+    Ok(())
+}
@@ -0,0 +1,29 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use aredis::testing::InMemoryServer;
+
+/// Serves an in-memory database, on the address given as the first argument
+/// or the default Redis port otherwise, until interrupted.
+#[tokio::main]
+async fn main() -> Result<()> {
+    let addr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:6379".to_string());
+    let server = InMemoryServer::bind(addr).await?;
+    println!("aredis-server listening on {}", server.addr());
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
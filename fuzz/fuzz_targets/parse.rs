@@ -0,0 +1,39 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![no_main]
+
+use aredis::{Limits, Model};
+use bytes::{Bytes, BytesMut};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let limits = Limits::default()
+        .max_bulk_len(1024 * 1024)
+        .max_aggregate_len(1024);
+    let buf = Bytes::copy_from_slice(data);
+    let mut pos = 0;
+    while let Ok(Some((model, next))) = Model::parse_with_limits(&buf, pos, &limits) {
+        assert!(next > pos && next <= buf.len());
+        pos = next;
+
+        // whatever parses must encode to something parsing back the same
+        let mut out = BytesMut::new();
+        model.encode(&mut out);
+        let encoded = out.freeze();
+        let (got, len) = Model::parse(&encoded, 0).unwrap().unwrap();
+        assert_eq!(len, encoded.len());
+        assert_eq!(format!("{:?}", got), format!("{:?}", model));
+    }
+});
@@ -0,0 +1,288 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A load generator in the manner of redis-benchmark.
+//!
+//! Each client runs on its own connection and issues commands in pipelines of
+//! the given depth until the requests are used up. Latency is measured per
+//! pipeline and counted for every command in it.
+
+use std::{
+    env,
+    error::Error,
+    process,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use aredis::{Client, Model};
+use hdrhistogram::Histogram;
+
+const USAGE: &str = "\
+Usage: aredis-bench [OPTIONS]
+
+Options:
+  -h <host>       Server hostname (default: 127.0.0.1)
+  -p <port>       Server port (default: 6379)
+  -c <clients>    Number of concurrent connections (default: 50)
+  -n <requests>   Total number of requests per test (default: 100000)
+  -P <depth>      Pipeline depth (default: 1, no pipelining)
+  -d <size>       Value size in bytes for SET (default: 3)
+  -r <keyspace>   Number of distinct keys (default: 10000)
+  -k <keys>       Keys per MGET (default: 10)
+  -t <tests>      Comma separated tests among set,get,incr,mget (default: all)
+  --help          Print this help";
+
+#[derive(Clone, Copy)]
+enum Test {
+    Set,
+    Get,
+    Incr,
+    MGet,
+}
+
+impl Test {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "set" => Ok(Test::Set),
+            "get" => Ok(Test::Get),
+            "incr" => Ok(Test::Incr),
+            "mget" => Ok(Test::MGet),
+            _ => Err(format!("unknown test {}", name)),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Test::Set => "SET",
+            Test::Get => "GET",
+            Test::Incr => "INCR",
+            Test::MGet => "MGET",
+        }
+    }
+}
+
+struct Options {
+    host: String,
+    port: u16,
+    clients: u64,
+    requests: u64,
+    pipeline: u64,
+    value_size: usize,
+    keyspace: u64,
+    mget_keys: u64,
+    tests: Vec<Test>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            host: "127.0.0.1".to_string(),
+            port: 6379,
+            clients: 50,
+            requests: 100000,
+            pipeline: 1,
+            value_size: 3,
+            keyspace: 10000,
+            mget_keys: 10,
+            tests: vec![Test::Set, Test::Get, Test::Incr, Test::MGet],
+        }
+    }
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options::default();
+        while let Some(arg) = args.next() {
+            let value = args.next();
+            let value = match (arg.as_str(), value) {
+                ("--help", _) => return Err(USAGE.to_string()),
+                (_, Some(value)) => value,
+                (_, None) => return Err(format!("{} needs a value\n\n{}", arg, USAGE)),
+            };
+            match arg.as_str() {
+                "-h" => options.host = value,
+                "-p" => options.port = parse_number(&arg, &value)?,
+                "-c" => options.clients = parse_number(&arg, &value)?,
+                "-n" => options.requests = parse_number(&arg, &value)?,
+                "-P" => options.pipeline = parse_number(&arg, &value)?,
+                "-d" => options.value_size = parse_number(&arg, &value)?,
+                "-r" => options.keyspace = parse_number(&arg, &value)?,
+                "-k" => options.mget_keys = parse_number(&arg, &value)?,
+                "-t" => {
+                    options.tests = value
+                        .split(',')
+                        .map(Test::parse)
+                        .collect::<Result<_, _>>()?
+                }
+                _ => return Err(format!("unknown option {}\n\n{}", arg, USAGE)),
+            }
+        }
+        if options.clients == 0
+            || options.pipeline == 0
+            || options.keyspace == 0
+            || options.mget_keys == 0
+        {
+            return Err("-c, -P, -r and -k must be positive".to_string());
+        }
+        Ok(options)
+    }
+
+    fn addr(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} expects a number, got {}", name, value))
+}
+
+#[tokio::main]
+async fn main() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => Arc::new(options),
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    };
+    for test in options.tests.iter() {
+        if let Err(e) = bench(options.clone(), *test).await {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn bench(options: Arc<Options>, test: Test) -> Result<(), Box<dyn Error>> {
+    let mut clients = Vec::with_capacity(options.clients as usize);
+    for _ in 0..options.clients {
+        clients.push(Client::connect(options.addr()).await?);
+    }
+    let issued = Arc::new(AtomicU64::new(0));
+    let start = Instant::now();
+    let mut tasks = Vec::with_capacity(clients.len());
+    for (seed, client) in clients.into_iter().enumerate() {
+        let options = options.clone();
+        let issued = issued.clone();
+        tasks.push(tokio::spawn(async move {
+            drive(client, &options, test, &issued, seed as u64 + 1).await
+        }));
+    }
+    let mut latencies = histogram();
+    let mut errors = 0;
+    for task in tasks.into_iter() {
+        let (histogram, failed) = task.await??;
+        latencies.add(histogram)?;
+        errors += failed;
+    }
+    report(test, start.elapsed(), &latencies, errors);
+    Ok(())
+}
+
+/// Issues pipelines of `test` until `issued` reaches the requested total and
+/// returns the latencies in microseconds along with the number of errors.
+async fn drive(
+    mut client: Client,
+    options: &Options,
+    test: Test,
+    issued: &AtomicU64,
+    seed: u64,
+) -> Result<(Histogram<u64>, u64), aredis::Error> {
+    let mut latencies = histogram();
+    let mut errors = 0;
+    let mut rng = Rng(seed);
+    let value = vec![b'x'; options.value_size];
+    loop {
+        let from = issued.fetch_add(options.pipeline, Ordering::Relaxed);
+        if from >= options.requests {
+            break;
+        }
+        let depth = options.pipeline.min(options.requests - from);
+        let commands: Vec<Vec<Vec<u8>>> = (0..depth)
+            .map(|_| command(test, options, &mut rng, &value))
+            .collect();
+        let start = Instant::now();
+        let replies = match depth {
+            1 => vec![client.call(commands.into_iter().next().unwrap()).await?],
+            _ => client.call_all(commands).await?,
+        };
+        let micros = start.elapsed().as_micros() as u64;
+        latencies.saturating_record_n(micros.max(1), depth);
+        errors += replies
+            .iter()
+            .filter(|reply| matches!(reply, Model::Error(_)))
+            .count() as u64;
+    }
+    Ok((latencies, errors))
+}
+
+/// Microseconds from 1 up to a minute, to three significant figures.
+fn histogram() -> Histogram<u64> {
+    Histogram::new_with_bounds(1, 60_000_000, 3).expect("the bounds are valid")
+}
+
+fn command(test: Test, options: &Options, rng: &mut Rng, value: &[u8]) -> Vec<Vec<u8>> {
+    let mut key = || format!("key:{:012}", rng.next() % options.keyspace).into_bytes();
+    match test {
+        Test::Set => vec![b"SET".to_vec(), key(), value.to_vec()],
+        Test::Get => vec![b"GET".to_vec(), key()],
+        Test::Incr => vec![
+            b"INCR".to_vec(),
+            format!("counter:{:012}", rng.next() % options.keyspace).into_bytes(),
+        ],
+        Test::MGet => {
+            let mut args = vec![b"MGET".to_vec()];
+            args.extend((0..options.mget_keys).map(|_| key()));
+            args
+        }
+    }
+}
+
+fn report(test: Test, elapsed: Duration, latencies: &Histogram<u64>, errors: u64) {
+    let requests = latencies.len();
+    let millis = |quantile: f64| latencies.value_at_quantile(quantile) as f64 / 1000.0;
+    println!(
+        "{}: {} requests in {:.2} s, {:.2} requests/s, {} errors",
+        test.name(),
+        requests,
+        elapsed.as_secs_f64(),
+        requests as f64 / elapsed.as_secs_f64(),
+        errors
+    );
+    println!(
+        "  latency (ms): p50 {:.3}, p99 {:.3}, p999 {:.3}, max {:.3}",
+        millis(0.5),
+        millis(0.99),
+        millis(0.999),
+        latencies.max() as f64 / 1000.0
+    );
+}
+
+/// A xorshift generator, enough to spread keys over the key space.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Renders replies the way redis-cli does, or as JSON.
+
+use aredis::Model;
+use serde_json::{Map, Number, Value};
+
+/// Renders `model` for a terminal, numbering the elements of aggregates and
+/// indenting nested ones under their number.
+pub fn pretty(model: &Model) -> String {
+    match model {
+        Model::Error(e) => format!("(error) {}", e),
+        Model::Status(s) => s.clone(),
+        Model::Integer(n) => format!("(integer) {}", n),
+        Model::Double(d) => format!("(double) {}", d),
+        Model::Nil => "(nil)".to_string(),
+        Model::String(s) => quote(s),
+        Model::Array(models) if models.is_empty() => "(empty array)".to_string(),
+        Model::Array(models) => numbered(models, ')'),
+        Model::Map(pairs) if pairs.is_empty() => "(empty hash)".to_string(),
+        Model::Map(pairs) => {
+            let width = pairs.len().to_string().len();
+            let mut out = vec![];
+            for (i, (key, value)) in pairs.iter().enumerate() {
+                let prefix = format!("{:>width$}# {} => ", i + 1, pretty(key));
+                out.push(indent(&prefix, &pretty(value)));
+            }
+            out.join("\n")
+        }
+        Model::Set(models) if models.is_empty() => "(empty set)".to_string(),
+        Model::Set(models) => numbered(models, '~'),
+        Model::Bool(b) => format!("({})", b),
+        Model::Verb(_, text) => String::from_utf8_lossy(text).into_owned(),
+        Model::Push(models) if models.is_empty() => "(empty push)".to_string(),
+        Model::Push(models) => numbered(models, '>'),
+        Model::BigNum(n) => format!("(big number) {}", n),
+    }
+}
+
+fn numbered(models: &[Model], marker: char) -> String {
+    let width = models.len().to_string().len();
+    let mut out = vec![];
+    for (i, model) in models.iter().enumerate() {
+        let prefix = format!("{:>width$}{} ", i + 1, marker);
+        out.push(indent(&prefix, &pretty(model)));
+    }
+    out.join("\n")
+}
+
+/// Puts `prefix` before the first line of `text` and aligns the rest with it.
+fn indent(prefix: &str, text: &str) -> String {
+    let pad = " ".repeat(prefix.chars().count());
+    let mut out = String::new();
+    for (i, line) in text.lines().enumerate() {
+        if i > 0 {
+            out.push('\n');
+            out.push_str(&pad);
+        } else {
+            out.push_str(prefix);
+        }
+        out.push_str(line);
+    }
+    if out.is_empty() {
+        out.push_str(prefix.trim_end());
+    }
+    out
+}
+
+/// Quotes a string, escaping anything but printable ASCII.
+fn quote(s: &[u8]) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for &b in s {
+        match b {
+            b'\\' => out.push_str("\\\\"),
+            b'"' => out.push_str("\\\""),
+            b'\n' => out.push_str("\\n"),
+            b'\r' => out.push_str("\\r"),
+            b'\t' => out.push_str("\\t"),
+            0x07 => out.push_str("\\a"),
+            0x08 => out.push_str("\\b"),
+            b' '..=b'~' => out.push(b as char),
+            _ => out.push_str(&format!("\\x{:02x}", b)),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Converts `model` to JSON. Maps keyed by strings become objects and other
+/// maps arrays of pairs; numbers JSON cannot hold exactly become strings.
+pub fn json(model: &Model) -> Value {
+    match model {
+        Model::Error(e) => {
+            let mut object = Map::new();
+            object.insert("error".to_string(), Value::String(e.clone()));
+            Value::Object(object)
+        }
+        Model::Status(s) => Value::String(s.clone()),
+        Model::Integer(n) => Value::Number((*n).into()),
+        Model::Double(d) => match Number::from_f64(*d) {
+            Some(n) => Value::Number(n),
+            None => Value::String(d.to_string()),
+        },
+        Model::Nil => Value::Null,
+        Model::String(s) => Value::String(String::from_utf8_lossy(s).into_owned()),
+        Model::Array(models) | Model::Set(models) | Model::Push(models) => {
+            Value::Array(models.iter().map(json).collect())
+        }
+        Model::Map(pairs) => {
+            let mut object = Map::new();
+            for (key, value) in pairs.iter() {
+                match json(key) {
+                    Value::String(key) => object.insert(key, json(value)),
+                    _ => {
+                        let pairs = pairs
+                            .iter()
+                            .map(|(k, v)| Value::Array(vec![json(k), json(v)]));
+                        return Value::Array(pairs.collect());
+                    }
+                };
+            }
+            Value::Object(object)
+        }
+        Model::Bool(b) => Value::Bool(*b),
+        Model::Verb(_, text) => Value::String(String::from_utf8_lossy(text).into_owned()),
+        Model::BigNum(n) => Value::String(n.to_string()),
+    }
+}
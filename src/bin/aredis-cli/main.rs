@@ -0,0 +1,314 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A command line interface in the manner of redis-cli.
+//!
+//! Runs the command given as arguments, the commands piped to stdin with
+//! `--pipe`, or else an interactive prompt.
+
+mod format;
+mod repl;
+
+use std::{
+    env,
+    error::Error,
+    io::{self, Read},
+    path::PathBuf,
+    process,
+};
+
+use aredis::{Client, Model};
+use bytes::Bytes;
+use percent_encoding::percent_decode_str;
+use rustyline::{error::ReadlineError, history::DefaultHistory, Editor};
+use url::Url;
+
+const USAGE: &str = "\
+Usage: aredis-cli [OPTIONS] [COMMAND [ARG...]]
+
+Options:
+  -u <url>        Server URL, redis://[[user]:password@]host[:port][/db]
+  -h <host>       Server hostname (default: 127.0.0.1)
+  -p <port>       Server port (default: 6379)
+  --user <user>   Username to authenticate with
+  -a <password>   Password to authenticate with
+  -n <db>         Database number
+  -3              Speak RESP3
+  --json          Print replies as JSON
+  --pipe          Send the commands read from stdin, one per line or as RESP
+  --help          Print this help";
+
+/// How many piped commands go out in one write.
+const PIPE_BATCH: usize = 1000;
+
+struct Options {
+    host: String,
+    port: u16,
+    user: Option<String>,
+    password: Option<String>,
+    db: u64,
+    resp3: bool,
+    json: bool,
+    pipe: bool,
+    command: Vec<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            host: "127.0.0.1".to_string(),
+            port: 6379,
+            user: None,
+            password: None,
+            db: 0,
+            resp3: false,
+            json: false,
+            pipe: false,
+            command: vec![],
+        }
+    }
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options::default();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+            match arg.as_str() {
+                "-u" => options.apply_url(&value("-u")?)?,
+                "-h" => options.host = value("-h")?,
+                "-p" => options.port = parse_number("-p", &value("-p")?)?,
+                "--user" => options.user = Some(value("--user")?),
+                "-a" => options.password = Some(value("-a")?),
+                "-n" => options.db = parse_number("-n", &value("-n")?)?,
+                "-3" => options.resp3 = true,
+                "--json" => options.json = true,
+                "--pipe" => options.pipe = true,
+                "--help" => return Err(USAGE.to_string()),
+                _ if arg.starts_with('-') && options.command.is_empty() => {
+                    return Err(format!("unknown option {}\n\n{}", arg, USAGE))
+                }
+                _ => {
+                    options.command.push(arg);
+                    options.command.extend(args);
+                    break;
+                }
+            }
+        }
+        Ok(options)
+    }
+
+    fn apply_url(&mut self, url: &str) -> Result<(), String> {
+        let url = Url::parse(url).map_err(|e| format!("invalid URL {}: {}", url, e))?;
+        if url.scheme() != "redis" {
+            return Err(format!("unsupported URL scheme {}", url.scheme()));
+        }
+        if let Some(host) = url.host_str() {
+            self.host = host.trim_matches(|c| c == '[' || c == ']').to_string();
+        }
+        if let Some(port) = url.port() {
+            self.port = port;
+        }
+        // the URL keeps credentials percent-encoded, as in p%40ss for p@ss
+        let decode = |s: &str| percent_decode_str(s).decode_utf8_lossy().into_owned();
+        if !url.username().is_empty() {
+            self.user = Some(decode(url.username()));
+        }
+        if let Some(password) = url.password() {
+            self.password = Some(decode(password));
+        }
+        match url.path().trim_start_matches('/') {
+            "" => {}
+            db => self.db = parse_number("the URL database", db)?,
+        }
+        Ok(())
+    }
+
+    fn prompt(&self) -> String {
+        match self.db {
+            0 => format!("{}:{}> ", self.host, self.port),
+            db => format!("{}:{}[{}]> ", self.host, self.port, db),
+        }
+    }
+
+    fn print(&self, model: &Model) {
+        match self.json {
+            true => println!("{}", format::json(model)),
+            false => println!("{}", format::pretty(model)),
+        }
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} expects a number, got {}", name, value))
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    };
+    match run(options).await {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Returns whether every command succeeded.
+async fn run(mut options: Options) -> Result<bool, Box<dyn Error>> {
+    let mut client = connect(&options).await?;
+    if options.pipe {
+        return pipe(&mut client).await;
+    }
+    if !options.command.is_empty() {
+        let args = std::mem::take(&mut options.command);
+        let reply = client.call(args).await?;
+        options.print(&reply);
+        return Ok(!matches!(reply, Model::Error(_)));
+    }
+    interact(&mut client, &mut options).await?;
+    Ok(true)
+}
+
+async fn connect(options: &Options) -> Result<Client, Box<dyn Error>> {
+    let mut client = Client::connect(format!("{}:{}", options.host, options.port)).await?;
+    let mut setup = vec![];
+    if let Some(password) = options.password.as_ref() {
+        let mut auth = vec!["AUTH".to_string()];
+        auth.extend(options.user.clone());
+        auth.push(password.clone());
+        setup.push(auth);
+    }
+    if options.resp3 {
+        setup.push(vec!["HELLO".to_string(), "3".to_string()]);
+    }
+    if options.db != 0 {
+        setup.push(vec!["SELECT".to_string(), options.db.to_string()]);
+    }
+    for args in setup.into_iter() {
+        if let Model::Error(e) = client.call(args).await? {
+            return Err(e.into());
+        }
+    }
+    Ok(client)
+}
+
+/// Sends the commands from stdin in batches and reports how many failed.
+async fn pipe(client: &mut Client) -> Result<bool, Box<dyn Error>> {
+    let mut input = vec![];
+    io::stdin().read_to_end(&mut input)?;
+    let commands = match input.first() {
+        Some(b'*') => parse_resp(Bytes::from(input))?,
+        _ => parse_lines(&String::from_utf8(input)?)?,
+    };
+    let (mut errors, mut replies) = (0, 0);
+    let mut commands = commands.into_iter().peekable();
+    while commands.peek().is_some() {
+        let batch: Vec<Vec<Vec<u8>>> = commands.by_ref().take(PIPE_BATCH).collect();
+        for reply in client.call_all(batch).await?.iter() {
+            if let Model::Error(e) = reply {
+                eprintln!("(error) {}", e);
+                errors += 1;
+            }
+            replies += 1;
+        }
+    }
+    println!("errors: {}, replies: {}", errors, replies);
+    Ok(errors == 0)
+}
+
+fn parse_lines(input: &str) -> Result<Vec<Vec<Vec<u8>>>, String> {
+    let mut commands = vec![];
+    for (n, line) in input.lines().enumerate() {
+        match repl::split_args(line) {
+            Some(args) if args.is_empty() => {}
+            Some(args) => commands.push(args),
+            None => return Err(format!("unbalanced quotes on line {}", n + 1)),
+        }
+    }
+    Ok(commands)
+}
+
+fn parse_resp(input: Bytes) -> Result<Vec<Vec<Vec<u8>>>, Box<dyn Error>> {
+    let mut commands = vec![];
+    let mut pos = 0;
+    while pos < input.len() {
+        let (model, next) = match Model::parse(&input, pos)? {
+            Some(parsed) => parsed,
+            None => return Err("truncated command at the end of the input".into()),
+        };
+        let args = match model {
+            Model::Array(args) => args.into_iter().map(|arg| match arg {
+                Model::String(arg) => Ok(arg.to_vec()),
+                _ => Err("expected an array of bulk strings"),
+            }),
+            _ => return Err("expected an array of bulk strings".into()),
+        };
+        commands.push(args.collect::<Result<_, _>>()?);
+        pos = next;
+    }
+    Ok(commands)
+}
+
+async fn interact(client: &mut Client, options: &mut Options) -> Result<(), Box<dyn Error>> {
+    let mut editor: Editor<repl::Commands, DefaultHistory> = Editor::new()?;
+    editor.set_helper(Some(repl::Commands));
+    let history = env::var_os("HOME").map(|home| PathBuf::from(home).join(".aredis_cli_history"));
+    if let Some(history) = history.as_ref() {
+        let _ = editor.load_history(history);
+    }
+    loop {
+        let line = match editor.readline(&options.prompt()) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        let args = match repl::split_args(&line) {
+            Some(args) if args.is_empty() => continue,
+            Some(args) => args,
+            None => {
+                eprintln!("Invalid argument(s)");
+                continue;
+            }
+        };
+        editor.add_history_entry(line.as_str())?;
+        let name = String::from_utf8_lossy(&args[0]).to_uppercase();
+        if name == "QUIT" || name == "EXIT" {
+            break;
+        }
+        let db = match (name.as_str(), args.get(1)) {
+            ("SELECT", Some(db)) => std::str::from_utf8(db).ok().and_then(|db| db.parse().ok()),
+            _ => None,
+        };
+        let reply = client.call(args).await?;
+        if let (Some(db), Model::Status(_)) = (db, &reply) {
+            options.db = db;
+        }
+        options.print(&reply);
+    }
+    if let Some(history) = history.as_ref() {
+        editor.save_history(history)?;
+    }
+    Ok(())
+}
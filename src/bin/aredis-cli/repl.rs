@@ -0,0 +1,125 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Line editing for the interactive mode.
+
+use aredis::command::COMMAND_NAMES;
+use rustyline::{
+    completion::Completer, highlight::Highlighter, hint::Hinter, validate::Validator, Context,
+    Helper,
+};
+
+/// Completes the command name, the first word of the line.
+pub struct Commands;
+
+impl Completer for Commands {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let typed = &line[..pos];
+        let start = typed.len() - typed.trim_start().len();
+        let prefix = &typed[start..];
+        if prefix.contains(char::is_whitespace) {
+            return Ok((pos, vec![]));
+        }
+        let lowercase = !prefix.is_empty() && prefix == prefix.to_lowercase();
+        let upper = prefix.to_uppercase();
+        let candidates = COMMAND_NAMES
+            .iter()
+            .filter(|name| name.starts_with(upper.as_str()))
+            .map(|name| match lowercase {
+                true => name.to_lowercase(),
+                false => name.to_string(),
+            })
+            .collect();
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for Commands {
+    type Hint = String;
+}
+
+impl Highlighter for Commands {}
+
+impl Validator for Commands {}
+
+impl Helper for Commands {}
+
+/// Splits a line into arguments as redis-cli does: on whitespace, except
+/// within double quotes, which take C-like escapes, or single quotes. Returns
+/// `None` for unbalanced quotes.
+pub fn split_args(line: &str) -> Option<Vec<Vec<u8>>> {
+    let mut args = vec![];
+    let mut chars = line.as_bytes().iter().copied().peekable();
+    loop {
+        while chars.next_if(u8::is_ascii_whitespace).is_some() {}
+        let first = match chars.peek() {
+            None => return Some(args),
+            Some(&c) => c,
+        };
+        let mut arg = vec![];
+        match first {
+            b'"' => {
+                chars.next();
+                loop {
+                    match chars.next()? {
+                        b'"' => break,
+                        b'\\' => match chars.next()? {
+                            b'n' => arg.push(b'\n'),
+                            b'r' => arg.push(b'\r'),
+                            b't' => arg.push(b'\t'),
+                            b'b' => arg.push(0x08),
+                            b'a' => arg.push(0x07),
+                            b'x' => {
+                                let hex = [chars.next()?, chars.next()?];
+                                let hex = std::str::from_utf8(&hex).ok()?;
+                                arg.push(u8::from_str_radix(hex, 16).ok()?);
+                            }
+                            c => arg.push(c),
+                        },
+                        c => arg.push(c),
+                    }
+                }
+            }
+            b'\'' => {
+                chars.next();
+                loop {
+                    match chars.next()? {
+                        b'\'' => break,
+                        b'\\' if chars.peek() == Some(&b'\'') => arg.push(chars.next()?),
+                        c => arg.push(c),
+                    }
+                }
+            }
+            _ => {
+                while let Some(c) = chars.next_if(|c| !c.is_ascii_whitespace()) {
+                    arg.push(c);
+                }
+                args.push(arg);
+                continue;
+            }
+        }
+        // a closing quote must end the argument
+        if chars.peek().is_some_and(|c| !c.is_ascii_whitespace()) {
+            return None;
+        }
+        args.push(arg);
+    }
+}
@@ -0,0 +1,961 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A synchronous facade over [`crate::Client`].
+
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    iter,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use bytes::Bytes;
+use futures::{io::AllowStdIo, StreamExt};
+use tokio::{
+    net::ToSocketAddrs,
+    runtime::{Builder, Runtime},
+};
+
+use crate::{command::*, observer::ClientObserver, Limits, Model, Result};
+
+/// Runs every command of an async [`crate::Client`] to completion on an
+/// internal current-thread runtime.
+pub struct Client {
+    inner: crate::Client,
+    runtime: Runtime,
+}
+
+impl Client {
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> Result<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        let inner = runtime.block_on(crate::Client::connect(addr))?;
+        Ok(Client { inner, runtime })
+    }
+
+    /// Speaks to the server over an already established stream, such as a
+    /// [`std::net::TcpStream`] or a TLS stream over one.
+    pub fn from_stream<T: Read + Write + Send + 'static>(stream: T) -> Result<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        let inner = runtime.block_on(crate::Client::from_stream(AllowStdIo::new(stream)))?;
+        Ok(Client { inner, runtime })
+    }
+
+    /// Bounds what the server may send, see [`Limits`].
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.inner.set_limits(limits);
+    }
+
+    /// Reports the commands run on this client to `observer`, see
+    /// [`crate::Client::set_observer`].
+    pub fn set_observer(&mut self, observer: Arc<dyn ClientObserver>) {
+        self.inner.set_observer(observer);
+    }
+
+    /// Sets how command spans show keys, see [`crate::TraceKeys`].
+    #[cfg(feature = "tracing")]
+    pub fn set_trace_keys(&mut self, keys: crate::TraceKeys) {
+        self.inner.set_trace_keys(keys);
+    }
+
+    /// Runs any command, given as its name followed by its arguments, and
+    /// returns the reply as is. A server error is an [`Model::Error`] reply.
+    pub fn call<In>(&mut self, args: Vec<In>) -> Result<Model>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.call(args))
+    }
+
+    /// Like [`Client::call`] for each command in turn, but pipelined so that
+    /// they all go out in one write before the replies are read.
+    pub fn call_all<In>(&mut self, commands: Vec<Vec<In>>) -> Result<Vec<Model>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.call_all(commands))
+    }
+
+    pub fn ping(&mut self) -> Result<()> {
+        self.runtime.block_on(self.inner.ping())
+    }
+
+    pub fn get<In, Out>(&mut self, key: In) -> Result<Option<Out>>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        self.runtime.block_on(self.inner.get(key))
+    }
+
+    pub fn set<In0, In1>(&mut self, key: In0, value: In1, option: SetOption) -> Result<bool>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.set(key, value, option))
+    }
+
+    /// Like [`Client::set`], but the value is `len` bytes read from `reader`
+    /// while sending instead of being buffered whole.
+    pub fn set_from<In, R>(
+        &mut self,
+        key: In,
+        reader: &mut R,
+        len: u64,
+        option: SetOption,
+    ) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+        R: Read + ?Sized,
+    {
+        let mut reader = AllowStdIo::new(reader);
+        self.runtime
+            .block_on(self.inner.set_from(key, &mut reader, len, option))
+    }
+
+    /// Streams the value of `key` into `writer` chunk by chunk. Returns its
+    /// length, or `None` if the key does not exist.
+    pub fn get_to<In, W>(&mut self, key: In, writer: &mut W) -> Result<Option<u64>>
+    where
+        In: Into<Vec<u8>>,
+        W: Write + ?Sized,
+    {
+        let mut writer = AllowStdIo::new(writer);
+        self.runtime.block_on(self.inner.get_to(key, &mut writer))
+    }
+
+    pub fn get_set<In0, In1, Out>(
+        &mut self,
+        key: In0,
+        value: In1,
+        option: SetOption,
+    ) -> Result<Option<Out>>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        self.runtime
+            .block_on(self.inner.get_set(key, value, option))
+    }
+
+    pub fn set_nx<In0, In1>(&mut self, key: In0, value: In1) -> Result<bool>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.set_nx(key, value))
+    }
+
+    pub fn set_ex<In0, In1>(&mut self, key: In0, ttl: Duration, value: In1) -> Result<()>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.set_ex(key, ttl, value))
+    }
+
+    pub fn pset_ex<In0, In1>(&mut self, key: In0, ttl: Duration, value: In1) -> Result<()>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.pset_ex(key, ttl, value))
+    }
+
+    pub fn get_del<In, Out>(&mut self, key: In) -> Result<Option<Out>>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        self.runtime.block_on(self.inner.get_del(key))
+    }
+
+    pub fn get_ex<In, Out>(&mut self, key: In, option: GetExOption) -> Result<Option<Out>>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        self.runtime.block_on(self.inner.get_ex(key, option))
+    }
+
+    pub fn flush_all(&mut self, sync: bool) -> Result<()> {
+        self.runtime.block_on(self.inner.flush_all(sync))
+    }
+
+    pub fn del<In>(&mut self, keys: Vec<In>) -> Result<u64>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.del(keys))
+    }
+
+    pub fn exists<In>(&mut self, keys: Vec<In>) -> Result<u64>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.exists(keys))
+    }
+
+    pub fn mset<In0, In1>(&mut self, kvs: Vec<(In0, In1)>) -> Result<()>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.mset(kvs))
+    }
+
+    pub fn msetnx<In0, In1>(&mut self, kvs: Vec<(In0, In1)>) -> Result<bool>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.msetnx(kvs))
+    }
+
+    pub fn mget<In, Out>(&mut self, keys: Vec<In>) -> Result<Vec<Option<Out>>>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        self.runtime.block_on(self.inner.mget(keys))
+    }
+
+    pub fn strlen<In>(&mut self, key: In) -> Result<u64>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.strlen(key))
+    }
+
+    pub fn get_range<In, Out>(&mut self, key: In, start: i64, end: i64) -> Result<Out>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        self.runtime.block_on(self.inner.get_range(key, start, end))
+    }
+
+    pub fn substr<In, Out>(&mut self, key: In, start: i64, end: i64) -> Result<Out>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        self.runtime.block_on(self.inner.substr(key, start, end))
+    }
+
+    pub fn set_range<In0, In1>(&mut self, key: In0, index: i64, substitute: In1) -> Result<u64>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime
+            .block_on(self.inner.set_range(key, index, substitute))
+    }
+
+    pub fn append<In0, In1>(&mut self, key: In0, suffix: In1) -> Result<u64>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.append(key, suffix))
+    }
+
+    pub fn append_from<In, R>(&mut self, key: In, reader: &mut R, len: u64) -> Result<u64>
+    where
+        In: Into<Vec<u8>>,
+        R: Read + ?Sized,
+    {
+        let mut reader = AllowStdIo::new(reader);
+        self.runtime
+            .block_on(self.inner.append_from(key, &mut reader, len))
+    }
+
+    pub fn lcs<In0, In1, Out>(&mut self, key1: In0, key2: In1) -> Result<Out>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        self.runtime.block_on(self.inner.lcs(key1, key2))
+    }
+
+    pub fn lcs_len<In0, In1>(&mut self, key1: In0, key2: In1) -> Result<u64>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.lcs_len(key1, key2))
+    }
+
+    pub fn lcs_idx<In0, In1>(
+        &mut self,
+        key1: In0,
+        key2: In1,
+        min_match_len: Option<u64>,
+        with_match_len: bool,
+    ) -> Result<LcsMatches>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(
+            self.inner
+                .lcs_idx(key1, key2, min_match_len, with_match_len),
+        )
+    }
+
+    pub fn incr<In>(&mut self, key: In) -> Result<i64>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.incr(key))
+    }
+
+    pub fn incr_by<In>(&mut self, key: In, increment: i64) -> Result<i64>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.incr_by(key, increment))
+    }
+
+    pub fn incr_by_float<In, Out>(&mut self, key: In, increment: f64) -> Result<f64>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime
+            .block_on(self.inner.incr_by_float::<In, Out>(key, increment))
+    }
+
+    pub fn decr<In>(&mut self, key: In) -> Result<i64>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.decr(key))
+    }
+
+    pub fn decr_by<In>(&mut self, key: In, decrement: i64) -> Result<i64>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.decr_by(key, decrement))
+    }
+
+    pub fn expire<In>(&mut self, key: In, ttl: Duration, condition: ExpireCondition) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime
+            .block_on(self.inner.expire(key, ttl, condition))
+    }
+
+    pub fn pexpire<In>(
+        &mut self,
+        key: In,
+        ttl: Duration,
+        condition: ExpireCondition,
+    ) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime
+            .block_on(self.inner.pexpire(key, ttl, condition))
+    }
+
+    pub fn expire_at<In>(
+        &mut self,
+        key: In,
+        at: SystemTime,
+        condition: ExpireCondition,
+    ) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime
+            .block_on(self.inner.expire_at(key, at, condition))
+    }
+
+    pub fn pexpire_at<In>(
+        &mut self,
+        key: In,
+        at: SystemTime,
+        condition: ExpireCondition,
+    ) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime
+            .block_on(self.inner.pexpire_at(key, at, condition))
+    }
+
+    pub fn ttl<In>(&mut self, key: In) -> Result<Expiry<Duration>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.ttl(key))
+    }
+
+    pub fn pttl<In>(&mut self, key: In) -> Result<Expiry<Duration>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.pttl(key))
+    }
+
+    pub fn expire_time<In>(&mut self, key: In) -> Result<Expiry<SystemTime>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.expire_time(key))
+    }
+
+    pub fn pexpire_time<In>(&mut self, key: In) -> Result<Expiry<SystemTime>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.pexpire_time(key))
+    }
+
+    pub fn persist<In>(&mut self, key: In) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.persist(key))
+    }
+
+    pub fn key_type<In>(&mut self, key: In) -> Result<KeyType>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.key_type(key))
+    }
+
+    pub fn rename<In0, In1>(&mut self, key: In0, new_key: In1) -> Result<()>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.rename(key, new_key))
+    }
+
+    pub fn rename_nx<In0, In1>(&mut self, key: In0, new_key: In1) -> Result<bool>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.rename_nx(key, new_key))
+    }
+
+    pub fn copy<In0, In1>(
+        &mut self,
+        source: In0,
+        destination: In1,
+        db: Option<u64>,
+        replace: bool,
+    ) -> Result<bool>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime
+            .block_on(self.inner.copy(source, destination, db, replace))
+    }
+
+    pub fn move_key<In>(&mut self, key: In, db: u64) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.move_key(key, db))
+    }
+
+    pub fn unlink<In>(&mut self, keys: Vec<In>) -> Result<u64>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.unlink(keys))
+    }
+
+    pub fn touch<In>(&mut self, keys: Vec<In>) -> Result<u64>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.touch(keys))
+    }
+
+    pub fn object_encoding<In>(&mut self, key: In) -> Result<Option<String>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.object_encoding(key))
+    }
+
+    pub fn object_freq<In>(&mut self, key: In) -> Result<Option<u64>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.object_freq(key))
+    }
+
+    pub fn object_idle_time<In>(&mut self, key: In) -> Result<Option<Duration>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.object_idle_time(key))
+    }
+
+    pub fn dump<In>(&mut self, key: In) -> Result<Option<Vec<u8>>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.dump(key))
+    }
+
+    pub fn dump_to<In, W>(&mut self, key: In, writer: &mut W) -> Result<Option<u64>>
+    where
+        In: Into<Vec<u8>>,
+        W: Write + ?Sized,
+    {
+        let mut writer = AllowStdIo::new(writer);
+        self.runtime.block_on(self.inner.dump_to(key, &mut writer))
+    }
+
+    pub fn restore<In0, In1>(
+        &mut self,
+        key: In0,
+        serialized: In1,
+        option: RestoreOption,
+    ) -> Result<()>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime
+            .block_on(self.inner.restore(key, serialized, option))
+    }
+
+    pub fn random_key<Out>(&mut self) -> Result<Option<Out>>
+    where
+        Out: From<Bytes>,
+    {
+        self.runtime.block_on(self.inner.random_key())
+    }
+
+    pub fn keys<In, Out>(&mut self, pattern: In) -> Result<Vec<Out>>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        self.runtime.block_on(self.inner.keys(pattern))
+    }
+
+    pub fn sort<In, Out>(&mut self, key: In, option: SortOption) -> Result<Vec<Option<Out>>>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        self.runtime.block_on(self.inner.sort(key, option))
+    }
+
+    pub fn sort_store<In0, In1>(
+        &mut self,
+        key: In0,
+        destination: In1,
+        option: SortOption,
+    ) -> Result<u64>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime
+            .block_on(self.inner.sort_store(key, destination, option))
+    }
+
+    pub fn sort_ro<In, Out>(&mut self, key: In, option: SortOption) -> Result<Vec<Option<Out>>>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        self.runtime.block_on(self.inner.sort_ro(key, option))
+    }
+
+    pub fn set_bit<In>(&mut self, key: In, offset: u64, value: bool) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime
+            .block_on(self.inner.set_bit(key, offset, value))
+    }
+
+    pub fn get_bit<In>(&mut self, key: In, offset: u64) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.get_bit(key, offset))
+    }
+
+    pub fn bit_count<In>(&mut self, key: In, range: BitRange) -> Result<u64>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.bit_count(key, range))
+    }
+
+    pub fn bit_pos<In>(&mut self, key: In, bit: bool, range: BitRange) -> Result<Option<u64>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.bit_pos(key, bit, range))
+    }
+
+    pub fn bit_op<In0, In1>(
+        &mut self,
+        operation: BitOperation,
+        destination: In0,
+        keys: Vec<In1>,
+    ) -> Result<u64>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime
+            .block_on(self.inner.bit_op(operation, destination, keys))
+    }
+
+    pub fn bit_field<In>(&mut self, key: In, option: BitFieldOption) -> Result<Vec<Option<i64>>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.bit_field(key, option))
+    }
+
+    pub fn bit_field_ro<In>(&mut self, key: In, option: BitFieldOption) -> Result<Vec<Option<i64>>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.bit_field_ro(key, option))
+    }
+
+    pub fn pf_add<In0, In1>(&mut self, key: In0, elements: Vec<In1>) -> Result<bool>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.pf_add(key, elements))
+    }
+
+    pub fn pf_count<In>(&mut self, keys: Vec<In>) -> Result<u64>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.pf_count(keys))
+    }
+
+    pub fn pf_merge<In0, In1>(&mut self, destination: In0, sources: Vec<In1>) -> Result<()>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime
+            .block_on(self.inner.pf_merge(destination, sources))
+    }
+
+    pub fn geo_add<In0, In1>(
+        &mut self,
+        key: In0,
+        members: Vec<(GeoCoord, In1)>,
+        option: GeoAddOption,
+    ) -> Result<u64>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime
+            .block_on(self.inner.geo_add(key, members, option))
+    }
+
+    pub fn geo_pos<In0, In1>(
+        &mut self,
+        key: In0,
+        members: Vec<In1>,
+    ) -> Result<Vec<Option<GeoCoord>>>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.geo_pos(key, members))
+    }
+
+    pub fn geo_dist<In0, In1, In2>(
+        &mut self,
+        key: In0,
+        member1: In1,
+        member2: In2,
+        unit: GeoUnit,
+    ) -> Result<Option<f64>>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+        In2: Into<Vec<u8>>,
+    {
+        self.runtime
+            .block_on(self.inner.geo_dist(key, member1, member2, unit))
+    }
+
+    pub fn geo_hash<In0, In1>(&mut self, key: In0, members: Vec<In1>) -> Result<Vec<Option<String>>>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.geo_hash(key, members))
+    }
+
+    pub fn geo_search<In, Out>(
+        &mut self,
+        key: In,
+        option: GeoSearchOption,
+    ) -> Result<Vec<GeoMember<Out>>>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        self.runtime.block_on(self.inner.geo_search(key, option))
+    }
+
+    pub fn geo_search_store<In0, In1>(
+        &mut self,
+        destination: In0,
+        source: In1,
+        option: GeoSearchOption,
+        store_dist: bool,
+    ) -> Result<u64>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(
+            self.inner
+                .geo_search_store(destination, source, option, store_dist),
+        )
+    }
+
+    pub fn info<In>(&mut self, sections: Vec<In>) -> Result<ServerInfo>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.info(sections))
+    }
+
+    pub fn config_get<In>(&mut self, parameters: Vec<In>) -> Result<HashMap<String, String>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.config_get(parameters))
+    }
+
+    pub fn config_set<In0, In1>(&mut self, parameters: Vec<(In0, In1)>) -> Result<()>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.config_set(parameters))
+    }
+
+    pub fn config_reset_stat(&mut self) -> Result<()> {
+        self.runtime.block_on(self.inner.config_reset_stat())
+    }
+
+    pub fn config_rewrite(&mut self) -> Result<()> {
+        self.runtime.block_on(self.inner.config_rewrite())
+    }
+
+    pub fn db_size(&mut self) -> Result<u64> {
+        self.runtime.block_on(self.inner.db_size())
+    }
+
+    pub fn flush_db(&mut self, sync: bool) -> Result<()> {
+        self.runtime.block_on(self.inner.flush_db(sync))
+    }
+
+    pub fn time(&mut self) -> Result<SystemTime> {
+        self.runtime.block_on(self.inner.time())
+    }
+
+    pub fn last_save(&mut self) -> Result<SystemTime> {
+        self.runtime.block_on(self.inner.last_save())
+    }
+
+    pub fn save(&mut self) -> Result<()> {
+        self.runtime.block_on(self.inner.save())
+    }
+
+    pub fn bg_save(&mut self) -> Result<String> {
+        self.runtime.block_on(self.inner.bg_save())
+    }
+
+    pub fn bg_rewrite_aof(&mut self) -> Result<String> {
+        self.runtime.block_on(self.inner.bg_rewrite_aof())
+    }
+
+    pub fn slowlog_get(&mut self, count: Option<i64>) -> Result<Vec<SlowLogEntry>> {
+        self.runtime.block_on(self.inner.slowlog_get(count))
+    }
+
+    pub fn slowlog_len(&mut self) -> Result<u64> {
+        self.runtime.block_on(self.inner.slowlog_len())
+    }
+
+    pub fn slowlog_reset(&mut self) -> Result<()> {
+        self.runtime.block_on(self.inner.slowlog_reset())
+    }
+
+    pub fn client_set_name<In>(&mut self, name: In) -> Result<()>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.client_set_name(name))
+    }
+
+    pub fn client_get_name<Out>(&mut self) -> Result<Option<Out>>
+    where
+        Out: From<Bytes>,
+    {
+        self.runtime.block_on(self.inner.client_get_name())
+    }
+
+    pub fn client_id(&mut self) -> Result<u64> {
+        self.runtime.block_on(self.inner.client_id())
+    }
+
+    pub fn client_info(&mut self) -> Result<ClientInfo> {
+        self.runtime.block_on(self.inner.client_info())
+    }
+
+    pub fn client_list(&mut self, client_type: Option<ClientType>) -> Result<Vec<ClientInfo>> {
+        self.runtime.block_on(self.inner.client_list(client_type))
+    }
+
+    pub fn client_kill(&mut self, option: ClientKillOption) -> Result<u64> {
+        self.runtime.block_on(self.inner.client_kill(option))
+    }
+
+    pub fn client_pause(&mut self, timeout: Duration, write_only: bool) -> Result<()> {
+        self.runtime
+            .block_on(self.inner.client_pause(timeout, write_only))
+    }
+
+    pub fn client_unpause(&mut self) -> Result<()> {
+        self.runtime.block_on(self.inner.client_unpause())
+    }
+
+    pub fn client_no_evict(&mut self, on: bool) -> Result<()> {
+        self.runtime.block_on(self.inner.client_no_evict(on))
+    }
+
+    pub fn client_no_touch(&mut self, on: bool) -> Result<()> {
+        self.runtime.block_on(self.inner.client_no_touch(on))
+    }
+
+    /// Only [`ClientReplyMode::On`] is accepted, see
+    /// [`crate::Client::client_reply`].
+    pub fn client_reply(&mut self, mode: ClientReplyMode) -> Result<()> {
+        self.runtime.block_on(self.inner.client_reply(mode))
+    }
+
+    pub fn client_set_info(&mut self, lib_name: Option<&str>, lib_ver: Option<&str>) -> Result<()> {
+        self.runtime
+            .block_on(self.inner.client_set_info(lib_name, lib_ver))
+    }
+
+    pub fn latency_latest(&mut self) -> Result<Vec<LatencyEvent>> {
+        self.runtime.block_on(self.inner.latency_latest())
+    }
+
+    pub fn latency_history<In>(&mut self, event: In) -> Result<Vec<LatencySample>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.latency_history(event))
+    }
+
+    pub fn memory_usage<In>(&mut self, key: In, samples: Option<u64>) -> Result<Option<u64>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        self.runtime.block_on(self.inner.memory_usage(key, samples))
+    }
+
+    /// Returns the statistics in reply order. Values are integers, doubles,
+    /// strings or nested maps depending on the field and the Redis version.
+    pub fn memory_stats(&mut self) -> Result<Vec<(String, Model)>> {
+        self.runtime.block_on(self.inner.memory_stats())
+    }
+
+    pub fn memory_doctor(&mut self) -> Result<String> {
+        self.runtime.block_on(self.inner.memory_doctor())
+    }
+
+    pub fn scan<Out>(&mut self, option: ScanOption) -> impl Iterator<Item = Result<Out>> + '_
+    where
+        Out: From<Bytes>,
+    {
+        let runtime = &self.runtime;
+        let mut stream = Box::pin(self.inner.scan(option));
+        iter::from_fn(move || runtime.block_on(stream.next()))
+    }
+
+    pub fn hscan<In, Out0, Out1>(
+        &mut self,
+        key: In,
+        option: ScanOption,
+    ) -> impl Iterator<Item = Result<(Out0, Out1)>> + '_
+    where
+        In: Into<Vec<u8>>,
+        Out0: From<Bytes>,
+        Out1: From<Bytes>,
+    {
+        let runtime = &self.runtime;
+        let mut stream = Box::pin(self.inner.hscan(key, option));
+        iter::from_fn(move || runtime.block_on(stream.next()))
+    }
+
+    pub fn sscan<In, Out>(
+        &mut self,
+        key: In,
+        option: ScanOption,
+    ) -> impl Iterator<Item = Result<Out>> + '_
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        let runtime = &self.runtime;
+        let mut stream = Box::pin(self.inner.sscan(key, option));
+        iter::from_fn(move || runtime.block_on(stream.next()))
+    }
+
+    pub fn zscan<In, Out>(
+        &mut self,
+        key: In,
+        option: ScanOption,
+    ) -> impl Iterator<Item = Result<(Out, f64)>> + '_
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        let runtime = &self.runtime;
+        let mut stream = Box::pin(self.inner.zscan(key, option));
+        iter::from_fn(move || runtime.block_on(stream.next()))
+    }
+}
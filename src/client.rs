@@ -12,40 +12,237 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use tokio::net::{TcpStream, ToSocketAddrs};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-use crate::{command::*, Connection, Error, Model, Result};
+use bytes::{Bytes, BytesMut};
+use futures::{
+    io::{AsyncRead, AsyncWrite},
+    stream, Stream, TryStreamExt,
+};
+
+#[cfg(feature = "tracing")]
+use crate::TraceKeys;
+use crate::{
+    command::*,
+    connection::Streamed,
+    observer::ClientObserver,
+    replicated::Replicas,
+    runtime,
+    runtime::{ToSocketAddrs, Transport},
+    tracking::Cache,
+    Connection, Error, Limits, Model, Result,
+};
 
 pub struct Client {
     connection: Connection,
+    replicas: Option<Replicas>,
+    cache: Option<Cache>,
 }
 
 impl Client {
     pub async fn connect<A: ToSocketAddrs>(addr: A) -> Result<Self> {
-        let socket = TcpStream::connect(addr).await?;
-        let connection = Connection::new(socket);
-        Ok(Self { connection })
+        let mut connection = runtime::connect(addr).await?;
+        identify(&mut connection).await?;
+        Ok(Self {
+            connection,
+            replicas: None,
+            cache: None,
+        })
+    }
+
+    /// Speaks to the server over an already established stream.
+    pub async fn from_stream<T: Transport + 'static>(stream: T) -> Result<Self> {
+        let mut connection = Connection::new(stream);
+        identify(&mut connection).await?;
+        Ok(Self {
+            connection,
+            replicas: None,
+            cache: None,
+        })
+    }
+
+    /// Bounds what the server may send, see [`Limits`].
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.connection.set_limits(limits);
+    }
+
+    /// Reports the commands run on this client, replicas included, to
+    /// `observer`.
+    pub fn set_observer(&mut self, observer: Arc<dyn ClientObserver>) {
+        if let Some(replicas) = self.replicas.as_mut() {
+            replicas.set_observer(observer.clone());
+        }
+        self.connection.set_observer(observer);
+    }
+
+    /// Sets how command spans show keys, see [`TraceKeys`].
+    #[cfg(feature = "tracing")]
+    pub fn set_trace_keys(&mut self, keys: TraceKeys) {
+        self.connection.set_trace_keys(keys);
+    }
+
+    pub(crate) fn with_replicas(connection: Connection, replicas: Replicas) -> Self {
+        Self {
+            connection,
+            replicas: Some(replicas),
+            cache: None,
+        }
+    }
+
+    pub(crate) fn with_cache(connection: Connection, cache: Cache) -> Self {
+        Self {
+            connection,
+            replicas: None,
+            cache: Some(cache),
+        }
+    }
+
+    async fn execute(&mut self, cmd: impl Command) -> Result<Option<Model>> {
+        if cmd.read_only() {
+            if let Some(replicas) = self.replicas.as_mut() {
+                if let Some(model) = replicas.execute(&cmd).await {
+                    return Ok(Some(model));
+                }
+            }
+        }
+        let result = match self.connection.send(&cmd).await {
+            Ok(()) => self.connection.recv().await,
+            Err(e) => Err(e),
+        };
+        self.settle(matches!(result, Ok(Some(_))))?;
+        result
+    }
+
+    /// Executes `cmd` on the primary, streaming a bulk string reply into
+    /// `writer`.
+    async fn execute_to<W>(&mut self, cmd: impl Command, writer: &mut W) -> Result<Streamed>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let result = match self.connection.send(&cmd).await {
+            Ok(()) => self.connection.recv_to(writer).await,
+            Err(e) => Err(e),
+        };
+        self.settle(matches!(
+            result,
+            Ok(Streamed::Written(_) | Streamed::Reply(Some(_)))
+        ))?;
+        result
+    }
+
+    /// Executes a command whose streamed argument is `len` bytes from `reader`.
+    async fn execute_from<R>(
+        &mut self,
+        name: &'static str,
+        encode: impl FnOnce(&mut BytesMut) -> usize,
+        reader: &mut R,
+        len: u64,
+    ) -> Result<Option<Model>>
+    where
+        R: AsyncRead + Unpin + ?Sized,
+    {
+        let result = match self.connection.send_from(name, encode, reader, len).await {
+            Ok(()) => self.connection.recv().await,
+            Err(e) => Err(e),
+        };
+        self.settle(matches!(result, Ok(Some(_))))?;
+        result
+    }
+
+    /// Keeps the client-side cache, if any, in step with the connection.
+    fn settle(&mut self, ok: bool) -> Result<()> {
+        match self.cache.as_mut() {
+            Some(cache) if ok => cache.sync(&mut self.connection),
+            Some(cache) => {
+                cache.disable();
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Looks `key` up in the client-side cache, if there is one.
+    fn cached(&mut self, key: &[u8]) -> Result<Option<Option<Bytes>>> {
+        match self.cache.as_mut() {
+            Some(cache) => {
+                cache.sync(&mut self.connection)?;
+                Ok(cache.get(key))
+            }
+            None => Ok(None),
+        }
     }
 
     pub async fn ping(&mut self) -> Result<()> {
-        self.connection.send(Ping).await?;
-        match self.connection.recv().await? {
+        match self.execute(Ping).await? {
             Some(Model::Status(status)) if status.eq_ignore_ascii_case("PONG") => Ok(()),
             model => match_failure(model),
         }
     }
 
+    /// Runs any command, given as its name followed by its arguments, and
+    /// returns the reply as is. A server error is an [`Model::Error`] reply.
+    pub async fn call<In>(&mut self, args: Vec<In>) -> Result<Model>
+    where
+        In: Into<Vec<u8>>,
+    {
+        let args = args.into_iter().map(Into::into).collect();
+        match self.execute(Raw::new(args)).await? {
+            Some(model) => Ok(model),
+            None => Err(Error::Internal("connection closed".to_string())),
+        }
+    }
+
+    /// Like [`Client::call`] for each command in turn, but pipelined so that
+    /// they all go out in one write before the replies are read.
+    pub async fn call_all<In>(&mut self, commands: Vec<Vec<In>>) -> Result<Vec<Model>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        let len = commands.len();
+        for args in commands.into_iter() {
+            let args = args.into_iter().map(Into::into).collect();
+            self.connection.write(&Raw::new(args));
+        }
+        let result = self.recv_all(len).await;
+        self.settle(result.is_ok())?;
+        result
+    }
+
+    async fn recv_all(&mut self, len: usize) -> Result<Vec<Model>> {
+        self.connection.flush().await?;
+        let mut replies = Vec::with_capacity(len);
+        for _ in 0..len {
+            match self.connection.recv().await? {
+                Some(model) => replies.push(model),
+                None => return Err(Error::Internal("connection closed".to_string())),
+            }
+        }
+        Ok(replies)
+    }
+
     pub async fn get<In, Out>(&mut self, key: In) -> Result<Option<Out>>
     where
         In: Into<Vec<u8>>,
-        Out: From<Vec<u8>>,
+        Out: From<Bytes>,
     {
-        self.connection.send(Get::new(key.into())).await?;
-        match self.connection.recv().await? {
-            Some(Model::String(result)) => Ok(Some(result.into())),
-            Some(Model::Nil) => Ok(None),
-            model => match_failure(model),
+        let key = key.into();
+        if let Some(value) = self.cached(&key)? {
+            return Ok(value.map(Out::from));
+        }
+        let epoch = self.cache.as_ref().map(Cache::epoch);
+        let value = match self.execute(Get::new(key.clone())).await? {
+            Some(Model::String(result)) => Some(result),
+            Some(Model::Nil) => None,
+            model => return match_failure(model),
+        };
+        if let (Some(cache), Some(epoch)) = (self.cache.as_mut(), epoch) {
+            cache.insert(epoch, key, value.clone());
         }
+        Ok(value.map(Out::from))
     }
 
     pub async fn set<In0, In1>(&mut self, key: In0, value: In1, option: SetOption) -> Result<bool>
@@ -53,15 +250,54 @@ impl Client {
         In0: Into<Vec<u8>>,
         In1: Into<Vec<u8>>,
     {
-        let set = option.build(key.into(), value.into(), false);
-        self.connection.send(set).await?;
-        match self.connection.recv().await? {
-            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(true),
-            Some(Model::Nil) => Ok(false),
+        let set = option.build(key.into(), value.into());
+        match self.execute(&set).await? {
+            Some(model) => match set.is_written(&model) {
+                Some(written) => Ok(written),
+                None => match_failure(Some(model)),
+            },
+            model => match_failure(model),
+        }
+    }
+
+    /// Like [`Client::set`], but the value is `len` bytes read from `reader`
+    /// while sending instead of being buffered whole.
+    pub async fn set_from<In, R>(
+        &mut self,
+        key: In,
+        reader: &mut R,
+        len: u64,
+        option: SetOption,
+    ) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+        R: AsyncRead + Unpin + ?Sized,
+    {
+        let set = option.build(key.into(), vec![]);
+        let encode = |out: &mut BytesMut| set.encode_streamed(out, len);
+        match self.execute_from(set.name(), encode, reader, len).await? {
+            Some(model) => match set.is_written(&model) {
+                Some(written) => Ok(written),
+                None => match_failure(Some(model)),
+            },
             model => match_failure(model),
         }
     }
 
+    /// Streams the value of `key` into `writer` chunk by chunk. Returns its
+    /// length, or `None` if the key does not exist.
+    pub async fn get_to<In, W>(&mut self, key: In, writer: &mut W) -> Result<Option<u64>>
+    where
+        In: Into<Vec<u8>>,
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        match self.execute_to(Get::new(key.into()), writer).await? {
+            Streamed::Written(len) => Ok(Some(len)),
+            Streamed::Reply(Some(Model::Nil)) => Ok(None),
+            Streamed::Reply(model) => match_failure(model),
+        }
+    }
+
     pub async fn get_set<In0, In1, Out>(
         &mut self,
         key: In0,
@@ -71,20 +307,82 @@ impl Client {
     where
         In0: Into<Vec<u8>>,
         In1: Into<Vec<u8>>,
-        Out: From<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        let set = option.get().build(key.into(), value.into());
+        match self.execute(set).await? {
+            Some(Model::Nil) => Ok(None),
+            Some(Model::String(result)) => Ok(Some(result.into())),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn set_nx<In0, In1>(&mut self, key: In0, value: In1) -> Result<bool>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        match self.execute(SetNx::new(key.into(), value.into())).await? {
+            Some(Model::Integer(0)) => Ok(false),
+            Some(Model::Integer(1)) => Ok(true),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn set_ex<In0, In1>(&mut self, key: In0, ttl: Duration, value: In1) -> Result<()>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        match self
+            .execute(SetEx::new(key.into(), ttl, value.into()))
+            .await?
+        {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn pset_ex<In0, In1>(&mut self, key: In0, ttl: Duration, value: In1) -> Result<()>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        match self
+            .execute(PSetEx::new(key.into(), ttl, value.into()))
+            .await?
+        {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn get_del<In, Out>(&mut self, key: In) -> Result<Option<Out>>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
     {
-        let set = option.build(key.into(), value.into(), true);
-        self.connection.send(set).await?;
-        match self.connection.recv().await? {
+        match self.execute(GetDel::new(key.into())).await? {
+            Some(Model::String(result)) => Ok(Some(result.into())),
             Some(Model::Nil) => Ok(None),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn get_ex<In, Out>(&mut self, key: In, option: GetExOption) -> Result<Option<Out>>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        match self.execute(option.build(key.into())).await? {
             Some(Model::String(result)) => Ok(Some(result.into())),
+            Some(Model::Nil) => Ok(None),
             model => match_failure(model),
         }
     }
 
     pub async fn flush_all(&mut self, sync: bool) -> Result<()> {
-        self.connection.send(FlushAll::new(sync)).await?;
-        match self.connection.recv().await? {
+        match self.execute(FlushAll::new(sync)).await? {
             Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
             model => match_failure(model),
         }
@@ -95,8 +393,7 @@ impl Client {
         In: Into<Vec<u8>>,
     {
         let keys = keys.into_iter().map(|k| k.into()).collect();
-        self.connection.send(Del::new(keys)).await?;
-        match self.connection.recv().await? {
+        match self.execute(Del::new(keys)).await? {
             Some(Model::Integer(result)) if result >= 0 => Ok(result as u64),
             model => match_failure(model),
         }
@@ -107,8 +404,7 @@ impl Client {
         In: Into<Vec<u8>>,
     {
         let keys = keys.into_iter().map(|k| k.into()).collect();
-        self.connection.send(Exists::new(keys)).await?;
-        match self.connection.recv().await? {
+        match self.execute(Exists::new(keys)).await? {
             Some(Model::Integer(result)) if result >= 0 => Ok(result as u64),
             model => match_failure(model),
         }
@@ -123,8 +419,7 @@ impl Client {
             .into_iter()
             .map(|kv| (kv.0.into(), kv.1.into()))
             .collect();
-        self.connection.send(MSet::new(kvs)).await?;
-        match self.connection.recv().await? {
+        match self.execute(MSet::new(kvs)).await? {
             Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
             model => match_failure(model),
         }
@@ -139,8 +434,7 @@ impl Client {
             .into_iter()
             .map(|kv| (kv.0.into(), kv.1.into()))
             .collect();
-        self.connection.send(MSetNx::new(kvs)).await?;
-        match self.connection.recv().await? {
+        match self.execute(MSetNx::new(kvs)).await? {
             Some(Model::Integer(result)) => match result {
                 0 => Ok(false),
                 1 => Ok(true),
@@ -153,32 +447,53 @@ impl Client {
     pub async fn mget<In, Out>(&mut self, keys: Vec<In>) -> Result<Vec<Option<Out>>>
     where
         In: Into<Vec<u8>>,
-        Out: From<Vec<u8>>,
+        Out: From<Bytes>,
     {
-        let keys = keys.into_iter().map(|k| k.into()).collect();
-        self.connection.send(MGet::new(keys)).await?;
-        match self.connection.recv().await? {
-            Some(Model::Array(models)) => {
-                let mut result = vec![];
-                for model in models.into_iter() {
-                    match model {
-                        Model::Nil => result.push(None),
-                        Model::String(v) => result.push(Some(v.into())),
-                        _ => return match_failure(Some(model)),
+        let keys: Vec<Vec<u8>> = keys.into_iter().map(|k| k.into()).collect();
+        if self.cache.is_none() {
+            return match self.execute(MGet::new(keys)).await? {
+                Some(Model::Array(models)) => to_optional_vec(models),
+                model => match_failure(model),
+            };
+        }
+
+        let mut values = Vec::with_capacity(keys.len());
+        let mut missing = vec![];
+        for key in keys.iter() {
+            let value = self.cached(key)?;
+            if value.is_none() {
+                missing.push(key.clone());
+            }
+            values.push(value);
+        }
+        if !missing.is_empty() {
+            let epoch = self.cache.as_ref().map_or(0, Cache::epoch);
+            let fetched: Vec<Option<Bytes>> = match self.execute(MGet::new(missing)).await? {
+                Some(Model::Array(models)) => to_optional_vec(models)?,
+                model => return match_failure(model),
+            };
+            let mut fetched = fetched.into_iter();
+            for (key, value) in keys.into_iter().zip(values.iter_mut()) {
+                if value.is_none() {
+                    let fresh = fetched.next().flatten();
+                    if let Some(cache) = self.cache.as_mut() {
+                        cache.insert(epoch, key, fresh.clone());
                     }
+                    *value = Some(fresh);
                 }
-                Ok(result)
             }
-            model => match_failure(model),
         }
+        Ok(values
+            .into_iter()
+            .map(|v| v.flatten().map(Out::from))
+            .collect())
     }
 
     pub async fn strlen<In>(&mut self, key: In) -> Result<u64>
     where
         In: Into<Vec<u8>>,
     {
-        self.connection.send(Strlen::new(key.into())).await?;
-        match self.connection.recv().await? {
+        match self.execute(Strlen::new(key.into())).await? {
             Some(Model::Integer(len)) if len >= 0 => Ok(len as u64),
             model => match_failure(model),
         }
@@ -187,12 +502,20 @@ impl Client {
     pub async fn get_range<In, Out>(&mut self, key: In, start: i64, end: i64) -> Result<Out>
     where
         In: Into<Vec<u8>>,
-        Out: From<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        match self.execute(GetRange::new(key.into(), start, end)).await? {
+            Some(Model::String(result)) => Ok(result.into()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn substr<In, Out>(&mut self, key: In, start: i64, end: i64) -> Result<Out>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
     {
-        self.connection
-            .send(GetRange::new(key.into(), start, end))
-            .await?;
-        match self.connection.recv().await? {
+        match self.execute(Substr::new(key.into(), start, end)).await? {
             Some(Model::String(result)) => Ok(result.into()),
             model => match_failure(model),
         }
@@ -208,10 +531,10 @@ impl Client {
         In0: Into<Vec<u8>>,
         In1: Into<Vec<u8>>,
     {
-        self.connection
-            .send(SetRange::new(key.into(), index, substitute.into()))
-            .await?;
-        match self.connection.recv().await? {
+        match self
+            .execute(SetRange::new(key.into(), index, substitute.into()))
+            .await?
+        {
             Some(Model::Integer(len)) if len >= 0 => Ok(len as u64),
             model => match_failure(model),
         }
@@ -222,21 +545,76 @@ impl Client {
         In0: Into<Vec<u8>>,
         In1: Into<Vec<u8>>,
     {
-        self.connection
-            .send(Append::new(key.into(), suffix.into()))
-            .await?;
-        match self.connection.recv().await? {
+        match self.execute(Append::new(key.into(), suffix.into())).await? {
+            Some(Model::Integer(len)) if len >= 0 => Ok(len as u64),
+            model => match_failure(model),
+        }
+    }
+
+    /// Like [`Client::append`], but the suffix is `len` bytes read from
+    /// `reader` while sending instead of being buffered whole.
+    pub async fn append_from<In, R>(&mut self, key: In, reader: &mut R, len: u64) -> Result<u64>
+    where
+        In: Into<Vec<u8>>,
+        R: AsyncRead + Unpin + ?Sized,
+    {
+        let append = Append::new(key.into(), vec![]);
+        let encode = |out: &mut BytesMut| append.encode_streamed(out, len);
+        match self
+            .execute_from(append.name(), encode, reader, len)
+            .await?
+        {
+            Some(Model::Integer(len)) if len >= 0 => Ok(len as u64),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn lcs<In0, In1, Out>(&mut self, key1: In0, key2: In1) -> Result<Out>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        match self.execute(Lcs::new(key1.into(), key2.into())).await? {
+            Some(Model::String(result)) => Ok(result.into()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn lcs_len<In0, In1>(&mut self, key1: In0, key2: In1) -> Result<u64>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        match self.execute(Lcs::len(key1.into(), key2.into())).await? {
             Some(Model::Integer(len)) if len >= 0 => Ok(len as u64),
             model => match_failure(model),
         }
     }
 
+    pub async fn lcs_idx<In0, In1>(
+        &mut self,
+        key1: In0,
+        key2: In1,
+        min_match_len: Option<u64>,
+        with_match_len: bool,
+    ) -> Result<LcsMatches>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        let lcs = Lcs::idx(key1.into(), key2.into(), min_match_len, with_match_len);
+        match self.execute(lcs).await? {
+            Some(model) => to_lcs_matches(to_pairs(model)?),
+            model => match_failure(model),
+        }
+    }
+
     pub async fn incr<In>(&mut self, key: In) -> Result<i64>
     where
         In: Into<Vec<u8>>,
     {
-        self.connection.send(Incr::new(key.into())).await?;
-        match self.connection.recv().await? {
+        match self.execute(Incr::new(key.into())).await? {
             Some(Model::Integer(result)) => Ok(result),
             model => match_failure(model),
         }
@@ -246,10 +624,7 @@ impl Client {
     where
         In: Into<Vec<u8>>,
     {
-        self.connection
-            .send(IncrBy::new(key.into(), increment))
-            .await?;
-        match self.connection.recv().await? {
+        match self.execute(IncrBy::new(key.into(), increment)).await? {
             Some(Model::Integer(result)) => Ok(result),
             model => match_failure(model),
         }
@@ -259,15 +634,11 @@ impl Client {
     where
         In: Into<Vec<u8>>,
     {
-        self.connection
-            .send(IncrByFloat::new(key.into(), increment))
-            .await?;
-        match self.connection.recv().await? {
-            Some(Model::String(result)) => {
-                let result = String::from_utf8(result)?;
-                let result = result.parse()?;
-                Ok(result)
-            }
+        match self
+            .execute(IncrByFloat::new(key.into(), increment))
+            .await?
+        {
+            Some(model @ Model::String(_)) => to_double(model),
             model => match_failure(model),
         }
     }
@@ -276,8 +647,7 @@ impl Client {
     where
         In: Into<Vec<u8>>,
     {
-        self.connection.send(Decr::new(key.into())).await?;
-        match self.connection.recv().await? {
+        match self.execute(Decr::new(key.into())).await? {
             Some(Model::Integer(result)) => Ok(result),
             model => match_failure(model),
         }
@@ -287,17 +657,1409 @@ impl Client {
     where
         In: Into<Vec<u8>>,
     {
-        self.connection
-            .send(DecrBy::new(key.into(), decrement))
-            .await?;
-        match self.connection.recv().await? {
+        match self.execute(DecrBy::new(key.into(), decrement)).await? {
             Some(Model::Integer(result)) => Ok(result),
             model => match_failure(model),
         }
     }
+
+    pub async fn expire<In>(
+        &mut self,
+        key: In,
+        ttl: Duration,
+        condition: ExpireCondition,
+    ) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self
+            .execute(Expire::new(key.into(), ttl, condition))
+            .await?
+        {
+            Some(Model::Integer(0)) => Ok(false),
+            Some(Model::Integer(1)) => Ok(true),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn pexpire<In>(
+        &mut self,
+        key: In,
+        ttl: Duration,
+        condition: ExpireCondition,
+    ) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self
+            .execute(PExpire::new(key.into(), ttl, condition))
+            .await?
+        {
+            Some(Model::Integer(0)) => Ok(false),
+            Some(Model::Integer(1)) => Ok(true),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn expire_at<In>(
+        &mut self,
+        key: In,
+        at: SystemTime,
+        condition: ExpireCondition,
+    ) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self
+            .execute(ExpireAt::new(key.into(), at, condition))
+            .await?
+        {
+            Some(Model::Integer(0)) => Ok(false),
+            Some(Model::Integer(1)) => Ok(true),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn pexpire_at<In>(
+        &mut self,
+        key: In,
+        at: SystemTime,
+        condition: ExpireCondition,
+    ) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self
+            .execute(PExpireAt::new(key.into(), at, condition))
+            .await?
+        {
+            Some(Model::Integer(0)) => Ok(false),
+            Some(Model::Integer(1)) => Ok(true),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn ttl<In>(&mut self, key: In) -> Result<Expiry<Duration>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(Ttl::new(key.into())).await? {
+            Some(Model::Integer(result)) => to_expiry(result, Duration::from_secs),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn pttl<In>(&mut self, key: In) -> Result<Expiry<Duration>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(PTtl::new(key.into())).await? {
+            Some(Model::Integer(result)) => to_expiry(result, Duration::from_millis),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn expire_time<In>(&mut self, key: In) -> Result<Expiry<SystemTime>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(ExpireTime::new(key.into())).await? {
+            Some(Model::Integer(result)) => {
+                to_expiry(result, |n| UNIX_EPOCH + Duration::from_secs(n))
+            }
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn pexpire_time<In>(&mut self, key: In) -> Result<Expiry<SystemTime>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(PExpireTime::new(key.into())).await? {
+            Some(Model::Integer(result)) => {
+                to_expiry(result, |n| UNIX_EPOCH + Duration::from_millis(n))
+            }
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn persist<In>(&mut self, key: In) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(Persist::new(key.into())).await? {
+            Some(Model::Integer(0)) => Ok(false),
+            Some(Model::Integer(1)) => Ok(true),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn key_type<In>(&mut self, key: In) -> Result<KeyType>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(Type::new(key.into())).await? {
+            Some(Model::Status(result)) => Ok(result.into()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn rename<In0, In1>(&mut self, key: In0, new_key: In1) -> Result<()>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        match self
+            .execute(Rename::new(key.into(), new_key.into()))
+            .await?
+        {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn rename_nx<In0, In1>(&mut self, key: In0, new_key: In1) -> Result<bool>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        match self
+            .execute(RenameNx::new(key.into(), new_key.into()))
+            .await?
+        {
+            Some(Model::Integer(0)) => Ok(false),
+            Some(Model::Integer(1)) => Ok(true),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn copy<In0, In1>(
+        &mut self,
+        source: In0,
+        destination: In1,
+        db: Option<u64>,
+        replace: bool,
+    ) -> Result<bool>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        let copy = CopyKey::new(source.into(), destination.into(), db, replace);
+        match self.execute(copy).await? {
+            Some(Model::Integer(0)) => Ok(false),
+            Some(Model::Integer(1)) => Ok(true),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn move_key<In>(&mut self, key: In, db: u64) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(Move::new(key.into(), db)).await? {
+            Some(Model::Integer(0)) => Ok(false),
+            Some(Model::Integer(1)) => Ok(true),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn unlink<In>(&mut self, keys: Vec<In>) -> Result<u64>
+    where
+        In: Into<Vec<u8>>,
+    {
+        let keys = keys.into_iter().map(|k| k.into()).collect();
+        match self.execute(Unlink::new(keys)).await? {
+            Some(Model::Integer(result)) if result >= 0 => Ok(result as u64),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn touch<In>(&mut self, keys: Vec<In>) -> Result<u64>
+    where
+        In: Into<Vec<u8>>,
+    {
+        let keys = keys.into_iter().map(|k| k.into()).collect();
+        match self.execute(Touch::new(keys)).await? {
+            Some(Model::Integer(result)) if result >= 0 => Ok(result as u64),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn object_encoding<In>(&mut self, key: In) -> Result<Option<String>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(ObjectEncoding::new(key.into())).await? {
+            Some(Model::String(result)) => Ok(Some(String::from_utf8(result.into())?)),
+            Some(Model::Nil) => Ok(None),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn object_freq<In>(&mut self, key: In) -> Result<Option<u64>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(ObjectFreq::new(key.into())).await? {
+            Some(Model::Integer(result)) if result >= 0 => Ok(Some(result as u64)),
+            Some(Model::Nil) => Ok(None),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn object_idle_time<In>(&mut self, key: In) -> Result<Option<Duration>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(ObjectIdleTime::new(key.into())).await? {
+            Some(Model::Integer(result)) if result >= 0 => {
+                Ok(Some(Duration::from_secs(result as u64)))
+            }
+            Some(Model::Nil) => Ok(None),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn dump<In>(&mut self, key: In) -> Result<Option<Vec<u8>>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(Dump::new(key.into())).await? {
+            Some(Model::String(result)) => Ok(Some(result.into())),
+            Some(Model::Nil) => Ok(None),
+            model => match_failure(model),
+        }
+    }
+
+    /// Streams the serialized value of `key` into `writer` chunk by chunk.
+    /// Returns its length, or `None` if the key does not exist.
+    pub async fn dump_to<In, W>(&mut self, key: In, writer: &mut W) -> Result<Option<u64>>
+    where
+        In: Into<Vec<u8>>,
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        match self.execute_to(Dump::new(key.into()), writer).await? {
+            Streamed::Written(len) => Ok(Some(len)),
+            Streamed::Reply(Some(Model::Nil)) => Ok(None),
+            Streamed::Reply(model) => match_failure(model),
+        }
+    }
+
+    pub async fn restore<In0, In1>(
+        &mut self,
+        key: In0,
+        serialized: In1,
+        option: RestoreOption,
+    ) -> Result<()>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        let restore = option.build(key.into(), serialized.into());
+        match self.execute(restore).await? {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn random_key<Out>(&mut self) -> Result<Option<Out>>
+    where
+        Out: From<Bytes>,
+    {
+        match self.execute(RandomKey).await? {
+            Some(Model::String(result)) => Ok(Some(result.into())),
+            Some(Model::Nil) => Ok(None),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn keys<In, Out>(&mut self, pattern: In) -> Result<Vec<Out>>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        match self.execute(Keys::new(pattern.into())).await? {
+            Some(Model::Array(models)) | Some(Model::Set(models)) => {
+                let mut result = vec![];
+                for model in models.into_iter() {
+                    match model {
+                        Model::String(v) => result.push(v.into()),
+                        _ => return match_failure(Some(model)),
+                    }
+                }
+                Ok(result)
+            }
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn sort<In, Out>(&mut self, key: In, option: SortOption) -> Result<Vec<Option<Out>>>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        let sort = option.build(key.into(), None);
+        match self.execute(sort).await? {
+            Some(Model::Array(models)) => to_optional_vec(models),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn sort_store<In0, In1>(
+        &mut self,
+        key: In0,
+        destination: In1,
+        option: SortOption,
+    ) -> Result<u64>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        let sort = option.build(key.into(), Some(destination.into()));
+        match self.execute(sort).await? {
+            Some(Model::Integer(result)) if result >= 0 => Ok(result as u64),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn sort_ro<In, Out>(
+        &mut self,
+        key: In,
+        option: SortOption,
+    ) -> Result<Vec<Option<Out>>>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        match self.execute(option.build_ro(key.into())).await? {
+            Some(Model::Array(models)) => to_optional_vec(models),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn set_bit<In>(&mut self, key: In, offset: u64, value: bool) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(SetBit::new(key.into(), offset, value)).await? {
+            Some(Model::Integer(0)) => Ok(false),
+            Some(Model::Integer(1)) => Ok(true),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn get_bit<In>(&mut self, key: In, offset: u64) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(GetBit::new(key.into(), offset)).await? {
+            Some(Model::Integer(0)) => Ok(false),
+            Some(Model::Integer(1)) => Ok(true),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn bit_count<In>(&mut self, key: In, range: BitRange) -> Result<u64>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(BitCount::new(key.into(), range)).await? {
+            Some(Model::Integer(result)) if result >= 0 => Ok(result as u64),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn bit_pos<In>(&mut self, key: In, bit: bool, range: BitRange) -> Result<Option<u64>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(BitPos::new(key.into(), bit, range)).await? {
+            Some(Model::Integer(-1)) => Ok(None),
+            Some(Model::Integer(result)) if result >= 0 => Ok(Some(result as u64)),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn bit_op<In0, In1>(
+        &mut self,
+        operation: BitOperation,
+        destination: In0,
+        keys: Vec<In1>,
+    ) -> Result<u64>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        let keys = keys.into_iter().map(|k| k.into()).collect();
+        match self
+            .execute(BitOp::new(operation, destination.into(), keys))
+            .await?
+        {
+            Some(Model::Integer(result)) if result >= 0 => Ok(result as u64),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn bit_field<In>(
+        &mut self,
+        key: In,
+        option: BitFieldOption,
+    ) -> Result<Vec<Option<i64>>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(option.build(key.into())).await? {
+            Some(Model::Array(models)) => to_optional_integers(models),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn bit_field_ro<In>(
+        &mut self,
+        key: In,
+        option: BitFieldOption,
+    ) -> Result<Vec<Option<i64>>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(option.build_ro(key.into())).await? {
+            Some(Model::Array(models)) => to_optional_integers(models),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn pf_add<In0, In1>(&mut self, key: In0, elements: Vec<In1>) -> Result<bool>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        let elements = elements.into_iter().map(|e| e.into()).collect();
+        match self.execute(PfAdd::new(key.into(), elements)).await? {
+            Some(Model::Integer(0)) => Ok(false),
+            Some(Model::Integer(1)) => Ok(true),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn pf_count<In>(&mut self, keys: Vec<In>) -> Result<u64>
+    where
+        In: Into<Vec<u8>>,
+    {
+        let keys = keys.into_iter().map(|k| k.into()).collect();
+        match self.execute(PfCount::new(keys)).await? {
+            Some(Model::Integer(result)) if result >= 0 => Ok(result as u64),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn pf_merge<In0, In1>(&mut self, destination: In0, sources: Vec<In1>) -> Result<()>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        let sources = sources.into_iter().map(|k| k.into()).collect();
+        match self
+            .execute(PfMerge::new(destination.into(), sources))
+            .await?
+        {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn geo_add<In0, In1>(
+        &mut self,
+        key: In0,
+        members: Vec<(GeoCoord, In1)>,
+        option: GeoAddOption,
+    ) -> Result<u64>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        let members = members.into_iter().map(|(c, m)| (c, m.into())).collect();
+        match self.execute(option.build(key.into(), members)).await? {
+            Some(Model::Integer(result)) if result >= 0 => Ok(result as u64),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn geo_pos<In0, In1>(
+        &mut self,
+        key: In0,
+        members: Vec<In1>,
+    ) -> Result<Vec<Option<GeoCoord>>>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        let members = members.into_iter().map(|m| m.into()).collect();
+        match self.execute(GeoPos::new(key.into(), members)).await? {
+            Some(Model::Array(models)) => {
+                let mut result = vec![];
+                for model in models.into_iter() {
+                    match model {
+                        Model::Nil => result.push(None),
+                        model => result.push(Some(to_geo_coord(model)?)),
+                    }
+                }
+                Ok(result)
+            }
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn geo_dist<In0, In1, In2>(
+        &mut self,
+        key: In0,
+        member1: In1,
+        member2: In2,
+        unit: GeoUnit,
+    ) -> Result<Option<f64>>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+        In2: Into<Vec<u8>>,
+    {
+        let dist = GeoDist::new(key.into(), member1.into(), member2.into(), unit);
+        match self.execute(dist).await? {
+            Some(Model::Nil) => Ok(None),
+            Some(model) => Ok(Some(to_double(model)?)),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn geo_hash<In0, In1>(
+        &mut self,
+        key: In0,
+        members: Vec<In1>,
+    ) -> Result<Vec<Option<String>>>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        let members = members.into_iter().map(|m| m.into()).collect();
+        match self.execute(GeoHash::new(key.into(), members)).await? {
+            Some(Model::Array(models)) => {
+                let mut result = vec![];
+                for model in models.into_iter() {
+                    match model {
+                        Model::Nil => result.push(None),
+                        Model::String(hash) => result.push(Some(String::from_utf8(hash.into())?)),
+                        _ => return match_failure(Some(model)),
+                    }
+                }
+                Ok(result)
+            }
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn geo_search<In, Out>(
+        &mut self,
+        key: In,
+        option: GeoSearchOption,
+    ) -> Result<Vec<GeoMember<Out>>>
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        match self.execute(option.build(key.into())).await? {
+            Some(Model::Array(models)) => {
+                let mut result = vec![];
+                for model in models.into_iter() {
+                    result.push(to_geo_member(model)?);
+                }
+                Ok(result)
+            }
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn geo_search_store<In0, In1>(
+        &mut self,
+        destination: In0,
+        source: In1,
+        option: GeoSearchOption,
+        store_dist: bool,
+    ) -> Result<u64>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        let search = option.build_store(destination.into(), source.into(), store_dist);
+        match self.execute(search).await? {
+            Some(Model::Integer(result)) if result >= 0 => Ok(result as u64),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn info<In>(&mut self, sections: Vec<In>) -> Result<ServerInfo>
+    where
+        In: Into<Vec<u8>>,
+    {
+        let sections = sections.into_iter().map(|s| s.into()).collect();
+        match self.execute(Info::new(sections)).await? {
+            Some(model) => Ok(ServerInfo::parse(&to_text(model)?)),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn config_get<In>(&mut self, parameters: Vec<In>) -> Result<HashMap<String, String>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        let parameters = parameters.into_iter().map(|p| p.into()).collect();
+        match self.execute(ConfigGet::new(parameters)).await? {
+            Some(model) => {
+                let mut result = HashMap::new();
+                for (k, v) in to_pairs(model)? {
+                    result.insert(to_text(k)?, to_text(v)?);
+                }
+                Ok(result)
+            }
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn config_set<In0, In1>(&mut self, parameters: Vec<(In0, In1)>) -> Result<()>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        let parameters = parameters
+            .into_iter()
+            .map(|kv| (kv.0.into(), kv.1.into()))
+            .collect();
+        match self.execute(ConfigSet::new(parameters)).await? {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn config_reset_stat(&mut self) -> Result<()> {
+        match self.execute(ConfigResetStat).await? {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn config_rewrite(&mut self) -> Result<()> {
+        match self.execute(ConfigRewrite).await? {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn db_size(&mut self) -> Result<u64> {
+        match self.execute(DbSize).await? {
+            Some(Model::Integer(result)) if result >= 0 => Ok(result as u64),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn flush_db(&mut self, sync: bool) -> Result<()> {
+        match self.execute(FlushDb::new(sync)).await? {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn time(&mut self) -> Result<SystemTime> {
+        match self.execute(Time).await? {
+            Some(Model::Array(models)) => match <[Model; 2]>::try_from(models) {
+                Ok([Model::String(secs), Model::String(micros)]) => {
+                    match (atoi::atoi(&secs), atoi::atoi(&micros)) {
+                        (Some(secs), Some(micros)) => Ok(UNIX_EPOCH
+                            + Duration::from_secs(secs)
+                            + Duration::from_micros(micros)),
+                        _ => match_failure(Some(Model::String(secs))),
+                    }
+                }
+                Ok(models) => match_failure(Some(Model::Array(models.into()))),
+                Err(models) => match_failure(Some(Model::Array(models))),
+            },
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn last_save(&mut self) -> Result<SystemTime> {
+        match self.execute(LastSave).await? {
+            Some(Model::Integer(result)) if result >= 0 => {
+                Ok(UNIX_EPOCH + Duration::from_secs(result as u64))
+            }
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn save(&mut self) -> Result<()> {
+        match self.execute(Save).await? {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn bg_save(&mut self) -> Result<String> {
+        match self.execute(BgSave).await? {
+            Some(Model::Status(status)) => Ok(status),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn bg_rewrite_aof(&mut self) -> Result<String> {
+        match self.execute(BgRewriteAof).await? {
+            Some(Model::Status(status)) => Ok(status),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn slowlog_get(&mut self, count: Option<i64>) -> Result<Vec<SlowLogEntry>> {
+        match self.execute(SlowLogGet::new(count)).await? {
+            Some(Model::Array(models)) => {
+                let mut result = vec![];
+                for model in models.into_iter() {
+                    result.push(to_slowlog_entry(model)?);
+                }
+                Ok(result)
+            }
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn slowlog_len(&mut self) -> Result<u64> {
+        match self.execute(SlowLogLen).await? {
+            Some(Model::Integer(result)) if result >= 0 => Ok(result as u64),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn slowlog_reset(&mut self) -> Result<()> {
+        match self.execute(SlowLogReset).await? {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn client_set_name<In>(&mut self, name: In) -> Result<()>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(ClientSetName::new(name.into())).await? {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn client_get_name<Out>(&mut self) -> Result<Option<Out>>
+    where
+        Out: From<Bytes>,
+    {
+        match self.execute(ClientGetName).await? {
+            Some(Model::String(result)) => Ok(Some(result.into())),
+            Some(Model::Nil) => Ok(None),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn client_id(&mut self) -> Result<u64> {
+        match self.execute(ClientId).await? {
+            Some(Model::Integer(result)) if result >= 0 => Ok(result as u64),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn client_info(&mut self) -> Result<ClientInfo> {
+        let line = match self.execute(ClientInfoCommand).await? {
+            Some(model) => to_text(model)?,
+            model => return match_failure(model),
+        };
+        ClientInfo::parse(&line)
+            .ok_or_else(|| Error::Internal(format!("malformed client info: {}", line)))
+    }
+
+    pub async fn client_list(
+        &mut self,
+        client_type: Option<ClientType>,
+    ) -> Result<Vec<ClientInfo>> {
+        let text = match self.execute(ClientList::new(client_type)).await? {
+            Some(model) => to_text(model)?,
+            model => return match_failure(model),
+        };
+        let mut result = vec![];
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            match ClientInfo::parse(line) {
+                Some(info) => result.push(info),
+                None => return Err(Error::Internal(format!("malformed client info: {}", line))),
+            }
+        }
+        Ok(result)
+    }
+
+    pub async fn client_kill(&mut self, option: ClientKillOption) -> Result<u64> {
+        match self.execute(option.build()).await? {
+            Some(Model::Integer(result)) if result >= 0 => Ok(result as u64),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn client_pause(&mut self, timeout: Duration, write_only: bool) -> Result<()> {
+        match self.execute(ClientPause::new(timeout, write_only)).await? {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn client_unpause(&mut self) -> Result<()> {
+        match self.execute(ClientUnpause).await? {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn client_no_evict(&mut self, on: bool) -> Result<()> {
+        match self.execute(ClientNoEvict::new(on)).await? {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn client_no_touch(&mut self, on: bool) -> Result<()> {
+        match self.execute(ClientNoTouch::new(on)).await? {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    /// Only [`ClientReplyMode::On`] is accepted: every command of a client
+    /// awaits its reply, so one silenced by OFF or SKIP would never return.
+    pub async fn client_reply(&mut self, mode: ClientReplyMode) -> Result<()> {
+        if mode != ClientReplyMode::On {
+            return Err(Error::InvalidArgument(
+                "only CLIENT REPLY ON is supported".to_string(),
+            ));
+        }
+        match self.execute(ClientReply::new(mode)).await? {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => Ok(()),
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn client_set_info(
+        &mut self,
+        lib_name: Option<&str>,
+        lib_ver: Option<&str>,
+    ) -> Result<()> {
+        let mut cmds = vec![];
+        if let Some(lib_name) = lib_name {
+            cmds.push(ClientSetInfo::lib_name(lib_name.into()));
+        }
+        if let Some(lib_ver) = lib_ver {
+            cmds.push(ClientSetInfo::lib_ver(lib_ver.into()));
+        }
+        for cmd in cmds {
+            match self.execute(cmd).await? {
+                Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => {}
+                model => return match_failure(model),
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn latency_latest(&mut self) -> Result<Vec<LatencyEvent>> {
+        match self.execute(LatencyLatest).await? {
+            Some(Model::Array(models)) => {
+                let mut result = vec![];
+                for model in models.into_iter() {
+                    result.push(to_latency_event(model)?);
+                }
+                Ok(result)
+            }
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn latency_history<In>(&mut self, event: In) -> Result<Vec<LatencySample>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(LatencyHistory::new(event.into())).await? {
+            Some(Model::Array(models)) => {
+                let mut result = vec![];
+                for model in models.into_iter() {
+                    match model {
+                        Model::Array(fields) => match <[Model; 2]>::try_from(fields) {
+                            Ok([Model::Integer(time), Model::Integer(latency)])
+                                if time >= 0 && latency >= 0 =>
+                            {
+                                result.push(LatencySample {
+                                    time: UNIX_EPOCH + Duration::from_secs(time as u64),
+                                    latency: Duration::from_millis(latency as u64),
+                                })
+                            }
+                            Ok(fields) => return match_failure(Some(Model::Array(fields.into()))),
+                            Err(fields) => return match_failure(Some(Model::Array(fields))),
+                        },
+                        _ => return match_failure(Some(model)),
+                    }
+                }
+                Ok(result)
+            }
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn memory_usage<In>(&mut self, key: In, samples: Option<u64>) -> Result<Option<u64>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.execute(MemoryUsage::new(key.into(), samples)).await? {
+            Some(Model::Integer(result)) if result >= 0 => Ok(Some(result as u64)),
+            Some(Model::Nil) => Ok(None),
+            model => match_failure(model),
+        }
+    }
+
+    /// Returns the statistics in reply order. Values are integers, doubles,
+    /// strings or nested maps depending on the field and the Redis version.
+    pub async fn memory_stats(&mut self) -> Result<Vec<(String, Model)>> {
+        match self.execute(MemoryStats).await? {
+            Some(model) => {
+                let mut result = vec![];
+                for (k, v) in to_pairs(model)? {
+                    result.push((to_text(k)?, v));
+                }
+                Ok(result)
+            }
+            model => match_failure(model),
+        }
+    }
+
+    pub async fn memory_doctor(&mut self) -> Result<String> {
+        match self.execute(MemoryDoctor).await? {
+            Some(model) => to_text(model),
+            model => match_failure(model),
+        }
+    }
+
+    pub fn scan<Out>(&mut self, option: ScanOption) -> impl Stream<Item = Result<Out>> + '_
+    where
+        Out: From<Bytes>,
+    {
+        let dedupe = option.is_dedupe();
+        scan_stream(self, 1, dedupe, move |cursor| {
+            Scan::new(cursor, option.clone())
+        })
+        .map_ok(|mut chunk| chunk.remove(0).into())
+    }
+
+    pub fn hscan<In, Out0, Out1>(
+        &mut self,
+        key: In,
+        option: ScanOption,
+    ) -> impl Stream<Item = Result<(Out0, Out1)>> + '_
+    where
+        In: Into<Vec<u8>>,
+        Out0: From<Bytes>,
+        Out1: From<Bytes>,
+    {
+        let key = key.into();
+        let dedupe = option.is_dedupe();
+        scan_stream(self, 2, dedupe, move |cursor| {
+            HScan::new(key.clone(), cursor, option.clone())
+        })
+        .map_ok(|mut chunk| {
+            let value = chunk.pop().unwrap_or_default();
+            let field = chunk.pop().unwrap_or_default();
+            (field.into(), value.into())
+        })
+    }
+
+    pub fn sscan<In, Out>(
+        &mut self,
+        key: In,
+        option: ScanOption,
+    ) -> impl Stream<Item = Result<Out>> + '_
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        let key = key.into();
+        let dedupe = option.is_dedupe();
+        scan_stream(self, 1, dedupe, move |cursor| {
+            SScan::new(key.clone(), cursor, option.clone())
+        })
+        .map_ok(|mut chunk| chunk.remove(0).into())
+    }
+
+    pub fn zscan<In, Out>(
+        &mut self,
+        key: In,
+        option: ScanOption,
+    ) -> impl Stream<Item = Result<(Out, f64)>> + '_
+    where
+        In: Into<Vec<u8>>,
+        Out: From<Bytes>,
+    {
+        let key = key.into();
+        let dedupe = option.is_dedupe();
+        scan_stream(self, 2, dedupe, move |cursor| {
+            ZScan::new(key.clone(), cursor, option.clone())
+        })
+        .and_then(|mut chunk| async move {
+            let score = String::from_utf8(chunk.pop().unwrap_or_default().into())?;
+            let member = chunk.pop().unwrap_or_default();
+            Ok((member.into(), score.parse()?))
+        })
+    }
+}
+
+struct ScanState<'a, F> {
+    client: &'a mut Client,
+    make: F,
+    cursor: u64,
+    chunk: usize,
+    buffer: VecDeque<Vec<Bytes>>,
+    seen: Option<HashSet<Bytes>>,
+    done: bool,
+}
+
+/// Drives a SCAN-family cursor until the server returns it to 0, yielding the
+/// reply elements grouped `chunk` at a time, e.g. field-value pairs for HSCAN.
+fn scan_stream<'a, C, F>(
+    client: &'a mut Client,
+    chunk: usize,
+    dedupe: bool,
+    make: F,
+) -> impl Stream<Item = Result<Vec<Bytes>>> + 'a
+where
+    C: Command + 'a,
+    F: Fn(u64) -> C + 'a,
+{
+    let state = ScanState {
+        client,
+        make,
+        cursor: 0,
+        chunk,
+        buffer: VecDeque::new(),
+        seen: dedupe.then(HashSet::new),
+        done: false,
+    };
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(elements) = state.buffer.pop_front() {
+                return Some((Ok(elements), state));
+            }
+            if state.done {
+                return None;
+            }
+            match state.next_page().await {
+                Ok(()) => continue,
+                Err(e) => {
+                    state.done = true;
+                    return Some((Err(e), state));
+                }
+            }
+        }
+    })
+}
+
+impl<C, F> ScanState<'_, F>
+where
+    C: Command,
+    F: Fn(u64) -> C,
+{
+    async fn next_page(&mut self) -> Result<()> {
+        let cmd = (self.make)(self.cursor);
+        let (cursor, models) = match self.client.execute(cmd).await? {
+            Some(Model::Array(mut models)) if models.len() == 2 => {
+                let elements = models.pop();
+                let cursor = models.pop();
+                match (cursor, elements) {
+                    (Some(Model::String(cursor)), Some(Model::Array(models))) => (cursor, models),
+                    (cursor, _) => return match_failure(cursor),
+                }
+            }
+            model => return match_failure(model),
+        };
+        self.cursor = match atoi::atoi(&cursor) {
+            Some(cursor) => cursor,
+            None => return match_failure(Some(Model::String(cursor))),
+        };
+        self.done = self.cursor == 0;
+
+        let mut elements = Vec::with_capacity(models.len());
+        for model in models.into_iter() {
+            match model {
+                Model::String(v) => elements.push(v),
+                _ => return match_failure(Some(model)),
+            }
+        }
+        let mut elements = elements.into_iter();
+        loop {
+            let chunk: Vec<Bytes> = elements.by_ref().take(self.chunk).collect();
+            if chunk.is_empty() {
+                break Ok(());
+            }
+            if chunk.len() < self.chunk {
+                break Err(Error::Protocol(format!(
+                    "scan page ends with {} of {} elements",
+                    chunk.len(),
+                    self.chunk
+                )));
+            }
+            if let Some(seen) = self.seen.as_mut() {
+                if !seen.insert(chunk[0].clone()) {
+                    continue;
+                }
+            }
+            self.buffer.push_back(chunk);
+        }
+    }
+}
+
+fn to_lcs_matches(pairs: Vec<(Model, Model)>) -> Result<LcsMatches> {
+    let mut result = LcsMatches {
+        matches: vec![],
+        len: 0,
+    };
+    for pair in pairs.into_iter() {
+        match pair {
+            (Model::String(k), Model::Array(matches)) if k == b"matches"[..] => {
+                for m in matches.into_iter() {
+                    result.matches.push(to_lcs_match(m)?);
+                }
+            }
+            (Model::String(k), Model::Integer(len)) if k == b"len"[..] && len >= 0 => {
+                result.len = len as u64;
+            }
+            (_, model) => return match_failure(Some(model)),
+        }
+    }
+    Ok(result)
+}
+
+fn to_lcs_match(model: Model) -> Result<LcsMatch> {
+    let range = |model: Option<Model>| match model {
+        Some(Model::Array(bounds)) => match bounds.as_slice() {
+            [Model::Integer(start), Model::Integer(end)] if *start >= 0 && *end >= 0 => {
+                Ok((*start as u64, *end as u64))
+            }
+            _ => match_failure(Some(Model::Array(bounds))),
+        },
+        model => match_failure(model),
+    };
+    match model {
+        Model::Array(parts) if parts.len() == 2 || parts.len() == 3 => {
+            let mut parts = parts.into_iter();
+            let key1 = range(parts.next())?;
+            let key2 = range(parts.next())?;
+            let len = match parts.next() {
+                None => None,
+                Some(Model::Integer(len)) if len >= 0 => Some(len as u64),
+                model => return match_failure(model),
+            };
+            Ok(LcsMatch { key1, key2, len })
+        }
+        model => match_failure(Some(model)),
+    }
+}
+
+fn to_optional_integers(models: Vec<Model>) -> Result<Vec<Option<i64>>> {
+    let mut result = vec![];
+    for model in models.into_iter() {
+        match model {
+            Model::Nil => result.push(None),
+            Model::Integer(n) => result.push(Some(n)),
+            _ => return match_failure(Some(model)),
+        }
+    }
+    Ok(result)
+}
+
+/// Reads a textual reply, which may be a status, a bulk string, or in RESP3 a
+/// verbatim string.
+fn to_text(model: Model) -> Result<String> {
+    match model {
+        Model::Status(text) => Ok(text),
+        Model::String(text) | Model::Verb(_, text) => Ok(String::from_utf8(text.into())?),
+        model => match_failure(Some(model)),
+    }
+}
+
+/// Reads a RESP3 map, or its RESP2 form as a flat array of keys and values.
+fn to_pairs(model: Model) -> Result<Vec<(Model, Model)>> {
+    match model {
+        Model::Map(pairs) => Ok(pairs),
+        Model::Array(models) if models.len() % 2 == 0 => {
+            let mut pairs = vec![];
+            let mut models = models.into_iter();
+            while let (Some(k), Some(v)) = (models.next(), models.next()) {
+                pairs.push((k, v));
+            }
+            Ok(pairs)
+        }
+        model => match_failure(Some(model)),
+    }
+}
+
+fn to_slowlog_entry(model: Model) -> Result<SlowLogEntry> {
+    let fields = match model {
+        Model::Array(fields) if fields.len() >= 4 => fields,
+        model => return match_failure(Some(model)),
+    };
+    let mut fields = fields.into_iter();
+    let mut integer = || match fields.next() {
+        Some(Model::Integer(n)) if n >= 0 => Ok(n as u64),
+        model => match_failure(model),
+    };
+    let id = integer()?;
+    let time = UNIX_EPOCH + Duration::from_secs(integer()?);
+    let duration = Duration::from_micros(integer()?);
+    let args = match fields.next() {
+        Some(Model::Array(models)) => {
+            let mut args = vec![];
+            for model in models.into_iter() {
+                match model {
+                    Model::String(arg) => args.push(arg.into()),
+                    model => return match_failure(Some(model)),
+                }
+            }
+            args
+        }
+        model => return match_failure(model),
+    };
+    let client_addr = fields.next().map(to_text).transpose()?;
+    let client_name = fields.next().map(to_text).transpose()?;
+    Ok(SlowLogEntry {
+        id,
+        time,
+        duration,
+        args,
+        client_addr,
+        client_name,
+    })
+}
+
+fn to_latency_event(model: Model) -> Result<LatencyEvent> {
+    let fields = match model {
+        Model::Array(fields) if fields.len() >= 4 => fields,
+        model => return match_failure(Some(model)),
+    };
+    let mut fields = fields.into_iter();
+    let event = match fields.next() {
+        Some(model) => to_text(model)?,
+        model => return match_failure(model),
+    };
+    let mut integer = || match fields.next() {
+        Some(Model::Integer(n)) if n >= 0 => Ok(n as u64),
+        model => match_failure(model),
+    };
+    Ok(LatencyEvent {
+        event,
+        time: UNIX_EPOCH + Duration::from_secs(integer()?),
+        latest: Duration::from_millis(integer()?),
+        max: Duration::from_millis(integer()?),
+    })
+}
+
+fn to_double(model: Model) -> Result<f64> {
+    match model {
+        Model::Double(result) => Ok(result),
+        Model::String(result) => {
+            let result = String::from_utf8(result.into())?;
+            let result = result.parse()?;
+            Ok(result)
+        }
+        model => match_failure(Some(model)),
+    }
+}
+
+fn to_geo_coord(model: Model) -> Result<GeoCoord> {
+    match model {
+        Model::Array(models) => match <[Model; 2]>::try_from(models) {
+            Ok([longitude, latitude]) => {
+                Ok(GeoCoord::new(to_double(longitude)?, to_double(latitude)?))
+            }
+            Err(models) => match_failure(Some(Model::Array(models))),
+        },
+        model => match_failure(Some(model)),
+    }
+}
+
+/// Decodes a GEOSEARCH result element. The optional fields always come in the
+/// order of distance, hash and coordinates, and are told apart by their types.
+fn to_geo_member<Out: From<Bytes>>(model: Model) -> Result<GeoMember<Out>> {
+    let models = match model {
+        Model::String(name) => {
+            return Ok(GeoMember {
+                name: name.into(),
+                dist: None,
+                hash: None,
+                coord: None,
+            })
+        }
+        Model::Array(models) => models,
+        model => return match_failure(Some(model)),
+    };
+
+    let mut models = models.into_iter();
+    let mut member = match models.next() {
+        Some(Model::String(name)) => GeoMember {
+            name: name.into(),
+            dist: None,
+            hash: None,
+            coord: None,
+        },
+        model => return match_failure(model),
+    };
+    for model in models {
+        match model {
+            Model::String(_) | Model::Double(_) => member.dist = Some(to_double(model)?),
+            Model::Integer(hash) => member.hash = Some(hash),
+            Model::Array(_) => member.coord = Some(to_geo_coord(model)?),
+            model => return match_failure(Some(model)),
+        }
+    }
+    Ok(member)
+}
+
+fn to_expiry<T>(n: i64, f: impl FnOnce(u64) -> T) -> Result<Expiry<T>> {
+    match n {
+        -2 => Ok(Expiry::Missing),
+        -1 => Ok(Expiry::Persistent),
+        n if n >= 0 => Ok(Expiry::Expire(f(n as u64))),
+        n => match_failure(Some(Model::Integer(n))),
+    }
+}
+
+fn to_optional_vec<Out: From<Bytes>>(models: Vec<Model>) -> Result<Vec<Option<Out>>> {
+    let mut result = vec![];
+    for model in models.into_iter() {
+        match model {
+            Model::Nil => result.push(None),
+            Model::String(v) => result.push(Some(v.into())),
+            _ => return match_failure(Some(model)),
+        }
+    }
+    Ok(result)
+}
+
+/// Reports this library through CLIENT SETINFO. Servers before 7.2 reject the
+/// command, which is not worth failing the connection over.
+pub(crate) async fn identify(connection: &mut Connection) -> Result<()> {
+    connection.write(&ClientSetInfo::lib_name(env!("CARGO_PKG_NAME").into()));
+    connection
+        .send(&ClientSetInfo::lib_ver(env!("CARGO_PKG_VERSION").into()))
+        .await?;
+    for _ in 0..2 {
+        if connection.recv().await?.is_none() {
+            return Err(Error::Internal("connection closed".to_string()));
+        }
+    }
+    Ok(())
 }
 
-fn match_failure<T>(model: Option<Model>) -> Result<T> {
+pub(crate) fn match_failure<T>(model: Option<Model>) -> Result<T> {
     match model {
         Some(Model::Error(e)) => Err(Error::Server(e)),
         model => Err(Error::Internal(format!("unreachable model: {:?}", model))),
@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::{args_to_bytes, Command};
+use bytes::BytesMut;
+
+use crate::command::{encode_args, encode_args_streamed, encode_args_vectored, Command};
 
 pub struct Append {
     key: Vec<u8>,
@@ -25,12 +27,34 @@ impl Append {
     }
 }
 
-impl Command for Append {
-    fn as_bytes(&self) -> Vec<u8> {
-        args_to_bytes(vec![
+impl Append {
+    /// Encodes the command for its suffix to be streamed, see
+    /// [`encode_args_streamed`].
+    pub(crate) fn encode_streamed(&self, out: &mut BytesMut, len: u64) -> usize {
+        encode_args_streamed(out, &self.args(), 2, len)
+    }
+
+    fn args(&self) -> [&[u8]; 3] {
+        [
             "APPEND".as_bytes(),
             self.key.as_slice(),
             self.suffix.as_slice(),
-        ])
+        ]
+    }
+}
+
+impl Command for Append {
+    fn name(&self) -> &'static str {
+        "APPEND"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &self.args())
+    }
+
+    fn encode_vectored<'a>(&'a self, out: &mut BytesMut) -> Vec<(usize, &'a [u8])> {
+        let args = self.args();
+        let large = encode_args_vectored(out, &args);
+        large.into_iter().map(|(at, i)| (at, args[i])).collect()
     }
 }
@@ -0,0 +1,67 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+pub struct BitCount {
+    key: Vec<u8>,
+    range: BitRange,
+}
+
+impl BitCount {
+    pub fn new(key: Vec<u8>, range: BitRange) -> Self {
+        BitCount { key, range }
+    }
+}
+
+impl Command for BitCount {
+    fn name(&self) -> &'static str {
+        "BITCOUNT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec!["BITCOUNT".as_bytes(), self.key.as_slice()];
+        let range = self.range.to_args();
+        args.extend(range.iter().map(|arg| arg.as_bytes()));
+        encode_args(out, &args)
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+}
+
+/// An inclusive range of a bitmap, counted in bytes or in bits. Negative
+/// indexes count from the end.
+#[derive(Clone, Copy)]
+pub enum BitRange {
+    All,
+    Byte(i64, i64),
+    Bit(i64, i64),
+}
+
+impl BitRange {
+    pub(crate) fn to_args(self) -> Vec<String> {
+        match self {
+            BitRange::All => vec![],
+            // BYTE is the default unit, and omitting it keeps Redis < 7.0 working.
+            BitRange::Byte(start, end) => vec![start.to_string(), end.to_string()],
+            BitRange::Bit(start, end) => {
+                vec![start.to_string(), end.to_string(), "BIT".to_string()]
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use bytes::BytesMut;
+
+use crate::{
+    command::{encode_args, Command},
+    Error, Result,
+};
+
+pub struct BitField {
+    key: Vec<u8>,
+    ops: Vec<BitFieldOp>,
+    read_only: bool,
+}
+
+impl Command for BitField {
+    fn name(&self) -> &'static str {
+        if self.read_only {
+            "BITFIELD_RO"
+        } else {
+            "BITFIELD"
+        }
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut strings = vec![];
+        for op in &self.ops {
+            match op {
+                BitFieldOp::Get(t, offset) => {
+                    strings.push("GET".to_string());
+                    strings.push(t.to_string());
+                    strings.push(offset.to_string());
+                }
+                BitFieldOp::Set(t, offset, value) => {
+                    strings.push("SET".to_string());
+                    strings.push(t.to_string());
+                    strings.push(offset.to_string());
+                    strings.push(value.to_string());
+                }
+                BitFieldOp::IncrBy(t, offset, increment) => {
+                    strings.push("INCRBY".to_string());
+                    strings.push(t.to_string());
+                    strings.push(offset.to_string());
+                    strings.push(increment.to_string());
+                }
+                BitFieldOp::Overflow(overflow) => {
+                    strings.push("OVERFLOW".to_string());
+                    strings.push(overflow.to_string());
+                }
+            }
+        }
+
+        let mut args = vec![self.name().as_bytes(), self.key.as_slice()];
+        args.extend(strings.iter().map(|s| s.as_bytes()));
+        encode_args(out, &args)
+    }
+
+    fn read_only(&self) -> bool {
+        self.read_only
+    }
+}
+
+/// An integer type of a bitfield, i1 to i64 or u1 to u63.
+#[derive(Clone, Copy)]
+pub struct BitFieldType {
+    signed: bool,
+    bits: u8,
+}
+
+impl BitFieldType {
+    pub fn signed(bits: u8) -> Result<Self> {
+        BitFieldType::new(true, bits, 64)
+    }
+
+    pub fn unsigned(bits: u8) -> Result<Self> {
+        BitFieldType::new(false, bits, 63)
+    }
+
+    fn new(signed: bool, bits: u8, max: u8) -> Result<Self> {
+        let t = BitFieldType { signed, bits };
+        if (1..=max).contains(&bits) {
+            Ok(t)
+        } else {
+            Err(Error::InvalidArgument(format!(
+                "invalid bitfield type {}",
+                t
+            )))
+        }
+    }
+}
+
+impl fmt::Display for BitFieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.signed { 'i' } else { 'u' };
+        write!(f, "{}{}", sign, self.bits)
+    }
+}
+
+/// A bit offset, or with `Index` an offset in multiples of the type width.
+#[derive(Clone, Copy)]
+pub enum BitFieldOffset {
+    Bit(u64),
+    Index(u64),
+}
+
+impl From<u64> for BitFieldOffset {
+    fn from(offset: u64) -> Self {
+        BitFieldOffset::Bit(offset)
+    }
+}
+
+impl fmt::Display for BitFieldOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BitFieldOffset::Bit(offset) => write!(f, "{}", offset),
+            BitFieldOffset::Index(index) => write!(f, "#{}", index),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Overflow {
+    Wrap,
+    Sat,
+    Fail,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Overflow::Wrap => write!(f, "WRAP"),
+            Overflow::Sat => write!(f, "SAT"),
+            Overflow::Fail => write!(f, "FAIL"),
+        }
+    }
+}
+
+enum BitFieldOp {
+    Get(BitFieldType, BitFieldOffset),
+    Set(BitFieldType, BitFieldOffset, i64),
+    IncrBy(BitFieldType, BitFieldOffset, i64),
+    Overflow(Overflow),
+}
+
+/// Sub-operations of a BITFIELD, executed in the order they are added. An
+/// `overflow` policy applies to the SET and INCRBY operations after it.
+#[derive(Default)]
+pub struct BitFieldOption {
+    ops: Vec<BitFieldOp>,
+}
+
+impl BitFieldOption {
+    pub fn get<O: Into<BitFieldOffset>>(mut self, t: BitFieldType, offset: O) -> Self {
+        self.ops.push(BitFieldOp::Get(t, offset.into()));
+        self
+    }
+
+    pub fn set<O: Into<BitFieldOffset>>(mut self, t: BitFieldType, offset: O, value: i64) -> Self {
+        self.ops.push(BitFieldOp::Set(t, offset.into(), value));
+        self
+    }
+
+    pub fn incr_by<O: Into<BitFieldOffset>>(
+        mut self,
+        t: BitFieldType,
+        offset: O,
+        increment: i64,
+    ) -> Self {
+        self.ops
+            .push(BitFieldOp::IncrBy(t, offset.into(), increment));
+        self
+    }
+
+    pub fn overflow(mut self, overflow: Overflow) -> Self {
+        self.ops.push(BitFieldOp::Overflow(overflow));
+        self
+    }
+
+    pub(crate) fn build(self, key: Vec<u8>) -> BitField {
+        BitField {
+            key,
+            ops: self.ops,
+            read_only: false,
+        }
+    }
+
+    /// BITFIELD_RO only accepts GET operations.
+    pub(crate) fn build_ro(self, key: Vec<u8>) -> BitField {
+        BitField {
+            key,
+            ops: self.ops,
+            read_only: true,
+        }
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+pub struct BitOp {
+    operation: BitOperation,
+    destination: Vec<u8>,
+    keys: Vec<Vec<u8>>,
+}
+
+impl BitOp {
+    pub fn new(operation: BitOperation, destination: Vec<u8>, keys: Vec<Vec<u8>>) -> Self {
+        BitOp {
+            operation,
+            destination,
+            keys,
+        }
+    }
+}
+
+impl Command for BitOp {
+    fn name(&self) -> &'static str {
+        "BITOP"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let operation = match self.operation {
+            BitOperation::And => "AND",
+            BitOperation::Or => "OR",
+            BitOperation::Xor => "XOR",
+            BitOperation::Not => "NOT",
+        };
+        let mut args = vec![
+            "BITOP".as_bytes(),
+            operation.as_bytes(),
+            self.destination.as_slice(),
+        ];
+        for key in &self.keys {
+            args.push(key.as_slice());
+        }
+        encode_args(out, &args)
+    }
+}
+
+pub enum BitOperation {
+    And,
+    Or,
+    Xor,
+    Not,
+}
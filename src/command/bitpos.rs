@@ -0,0 +1,50 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, BitRange, Command};
+
+pub struct BitPos {
+    key: Vec<u8>,
+    bit: bool,
+    range: BitRange,
+}
+
+impl BitPos {
+    pub fn new(key: Vec<u8>, bit: bool, range: BitRange) -> Self {
+        BitPos { key, bit, range }
+    }
+}
+
+impl Command for BitPos {
+    fn name(&self) -> &'static str {
+        "BITPOS"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec![
+            "BITPOS".as_bytes(),
+            self.key.as_slice(),
+            if self.bit { "1" } else { "0" }.as_bytes(),
+        ];
+        let range = self.range.to_args();
+        args.extend(range.iter().map(|arg| arg.as_bytes()));
+        encode_args(out, &args)
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+}
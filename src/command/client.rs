@@ -0,0 +1,508 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::HashMap, time::Duration};
+
+use bytes::BytesMut;
+
+use crate::command::{ceil_secs, encode_args, Command};
+
+pub struct ClientSetName {
+    name: Vec<u8>,
+}
+
+impl ClientSetName {
+    pub fn new(name: Vec<u8>) -> Self {
+        ClientSetName { name }
+    }
+}
+
+impl Command for ClientSetName {
+    fn name(&self) -> &'static str {
+        "CLIENT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(
+            out,
+            &[
+                "CLIENT".as_bytes(),
+                "SETNAME".as_bytes(),
+                self.name.as_slice(),
+            ],
+        )
+    }
+}
+
+pub struct ClientGetName;
+
+impl Command for ClientGetName {
+    fn name(&self) -> &'static str {
+        "CLIENT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["CLIENT".as_bytes(), "GETNAME".as_bytes()])
+    }
+}
+
+pub struct ClientId;
+
+impl Command for ClientId {
+    fn name(&self) -> &'static str {
+        "CLIENT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["CLIENT".as_bytes(), "ID".as_bytes()])
+    }
+}
+
+/// CLIENT INFO, named apart from the [`ClientInfo`] it replies with.
+pub struct ClientInfoCommand;
+
+impl Command for ClientInfoCommand {
+    fn name(&self) -> &'static str {
+        "CLIENT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["CLIENT".as_bytes(), "INFO".as_bytes()])
+    }
+}
+
+pub struct ClientList {
+    client_type: Option<ClientType>,
+}
+
+impl ClientList {
+    pub fn new(client_type: Option<ClientType>) -> Self {
+        ClientList { client_type }
+    }
+}
+
+impl Command for ClientList {
+    fn name(&self) -> &'static str {
+        "CLIENT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec!["CLIENT".as_bytes(), "LIST".as_bytes()];
+        if let Some(client_type) = &self.client_type {
+            args.push("TYPE".as_bytes());
+            args.push(client_type.as_str().as_bytes());
+        }
+        encode_args(out, &args)
+    }
+}
+
+pub struct ClientKill {
+    option: ClientKillOption,
+}
+
+impl Command for ClientKill {
+    fn name(&self) -> &'static str {
+        "CLIENT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut strings: Vec<Vec<u8>> = vec![];
+        if let Some(id) = self.option.id {
+            strings.push(b"ID".to_vec());
+            strings.push(id.to_string().into_bytes());
+        }
+        if let Some(client_type) = &self.option.client_type {
+            strings.push(b"TYPE".to_vec());
+            strings.push(client_type.as_str().as_bytes().to_vec());
+        }
+        if let Some(user) = &self.option.user {
+            strings.push(b"USER".to_vec());
+            strings.push(user.clone());
+        }
+        if let Some(addr) = &self.option.addr {
+            strings.push(b"ADDR".to_vec());
+            strings.push(addr.clone());
+        }
+        if let Some(laddr) = &self.option.laddr {
+            strings.push(b"LADDR".to_vec());
+            strings.push(laddr.clone());
+        }
+        if let Some(skip_me) = self.option.skip_me {
+            strings.push(b"SKIPME".to_vec());
+            strings.push(if skip_me {
+                b"yes".to_vec()
+            } else {
+                b"no".to_vec()
+            });
+        }
+        if let Some(max_age) = self.option.max_age {
+            strings.push(b"MAXAGE".to_vec());
+            strings.push(ceil_secs(max_age).to_string().into_bytes());
+        }
+
+        let mut args = vec!["CLIENT".as_bytes(), "KILL".as_bytes()];
+        args.extend(strings.iter().map(|arg| arg.as_slice()));
+        encode_args(out, &args)
+    }
+}
+
+/// Filters of CLIENT KILL; a client is killed only if it matches all of them.
+#[derive(Default)]
+pub struct ClientKillOption {
+    id: Option<u64>,
+    client_type: Option<ClientType>,
+    user: Option<Vec<u8>>,
+    addr: Option<Vec<u8>>,
+    laddr: Option<Vec<u8>>,
+    skip_me: Option<bool>,
+    max_age: Option<Duration>,
+}
+
+impl ClientKillOption {
+    pub fn id(self, id: u64) -> Self {
+        ClientKillOption {
+            id: Some(id),
+            ..self
+        }
+    }
+
+    pub fn client_type(self, client_type: ClientType) -> Self {
+        ClientKillOption {
+            client_type: Some(client_type),
+            ..self
+        }
+    }
+
+    pub fn user<U: Into<Vec<u8>>>(self, user: U) -> Self {
+        ClientKillOption {
+            user: Some(user.into()),
+            ..self
+        }
+    }
+
+    pub fn addr<A: Into<Vec<u8>>>(self, addr: A) -> Self {
+        ClientKillOption {
+            addr: Some(addr.into()),
+            ..self
+        }
+    }
+
+    pub fn laddr<A: Into<Vec<u8>>>(self, laddr: A) -> Self {
+        ClientKillOption {
+            laddr: Some(laddr.into()),
+            ..self
+        }
+    }
+
+    pub fn skip_me(self, skip_me: bool) -> Self {
+        ClientKillOption {
+            skip_me: Some(skip_me),
+            ..self
+        }
+    }
+
+    pub fn max_age(self, max_age: Duration) -> Self {
+        ClientKillOption {
+            max_age: Some(max_age),
+            ..self
+        }
+    }
+
+    pub(crate) fn build(self) -> ClientKill {
+        ClientKill { option: self }
+    }
+}
+
+pub struct ClientPause {
+    timeout: Duration,
+    write_only: bool,
+}
+
+impl ClientPause {
+    pub fn new(timeout: Duration, write_only: bool) -> Self {
+        ClientPause {
+            timeout,
+            write_only,
+        }
+    }
+}
+
+impl Command for ClientPause {
+    fn name(&self) -> &'static str {
+        "CLIENT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(
+            out,
+            &[
+                "CLIENT".as_bytes(),
+                "PAUSE".as_bytes(),
+                self.timeout.as_millis().to_string().as_bytes(),
+                if self.write_only { "WRITE" } else { "ALL" }.as_bytes(),
+            ],
+        )
+    }
+}
+
+pub struct ClientUnpause;
+
+impl Command for ClientUnpause {
+    fn name(&self) -> &'static str {
+        "CLIENT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["CLIENT".as_bytes(), "UNPAUSE".as_bytes()])
+    }
+}
+
+pub struct ClientNoEvict {
+    on: bool,
+}
+
+impl ClientNoEvict {
+    pub fn new(on: bool) -> Self {
+        ClientNoEvict { on }
+    }
+}
+
+impl Command for ClientNoEvict {
+    fn name(&self) -> &'static str {
+        "CLIENT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(
+            out,
+            &[
+                "CLIENT".as_bytes(),
+                "NO-EVICT".as_bytes(),
+                if self.on { "ON" } else { "OFF" }.as_bytes(),
+            ],
+        )
+    }
+}
+
+pub struct ClientNoTouch {
+    on: bool,
+}
+
+impl ClientNoTouch {
+    pub fn new(on: bool) -> Self {
+        ClientNoTouch { on }
+    }
+}
+
+impl Command for ClientNoTouch {
+    fn name(&self) -> &'static str {
+        "CLIENT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(
+            out,
+            &[
+                "CLIENT".as_bytes(),
+                "NO-TOUCH".as_bytes(),
+                if self.on { "ON" } else { "OFF" }.as_bytes(),
+            ],
+        )
+    }
+}
+
+pub struct ClientReply {
+    mode: ClientReplyMode,
+}
+
+impl ClientReply {
+    pub fn new(mode: ClientReplyMode) -> Self {
+        ClientReply { mode }
+    }
+}
+
+impl Command for ClientReply {
+    fn name(&self) -> &'static str {
+        "CLIENT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mode = match self.mode {
+            ClientReplyMode::On => "ON",
+            ClientReplyMode::Off => "OFF",
+            ClientReplyMode::Skip => "SKIP",
+        };
+        encode_args(
+            out,
+            &["CLIENT".as_bytes(), "REPLY".as_bytes(), mode.as_bytes()],
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ClientReplyMode {
+    On,
+    Off,
+    Skip,
+}
+
+pub struct ClientSetInfo {
+    attr: &'static str,
+    value: Vec<u8>,
+}
+
+impl ClientSetInfo {
+    pub fn lib_name(name: Vec<u8>) -> Self {
+        ClientSetInfo {
+            attr: "LIB-NAME",
+            value: name,
+        }
+    }
+
+    pub fn lib_ver(version: Vec<u8>) -> Self {
+        ClientSetInfo {
+            attr: "LIB-VER",
+            value: version,
+        }
+    }
+}
+
+impl Command for ClientSetInfo {
+    fn name(&self) -> &'static str {
+        "CLIENT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(
+            out,
+            &[
+                "CLIENT".as_bytes(),
+                "SETINFO".as_bytes(),
+                self.attr.as_bytes(),
+                self.value.as_slice(),
+            ],
+        )
+    }
+}
+
+pub struct ClientTracking {
+    on: bool,
+    redirect: Option<u64>,
+    bcast: bool,
+    prefixes: Vec<Vec<u8>>,
+}
+
+impl ClientTracking {
+    pub fn new(on: bool, redirect: Option<u64>, bcast: bool, prefixes: Vec<Vec<u8>>) -> Self {
+        ClientTracking {
+            on,
+            redirect,
+            bcast,
+            prefixes,
+        }
+    }
+}
+
+impl Command for ClientTracking {
+    fn name(&self) -> &'static str {
+        "CLIENT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let redirect = self.redirect.map(|id| id.to_string());
+        let mut args = vec![
+            "CLIENT".as_bytes(),
+            "TRACKING".as_bytes(),
+            if self.on { "ON" } else { "OFF" }.as_bytes(),
+        ];
+        if let Some(redirect) = &redirect {
+            args.push("REDIRECT".as_bytes());
+            args.push(redirect.as_bytes());
+        }
+        if self.bcast {
+            args.push("BCAST".as_bytes());
+        }
+        for prefix in &self.prefixes {
+            args.push("PREFIX".as_bytes());
+            args.push(prefix.as_slice());
+        }
+        encode_args(out, &args)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClientType {
+    Normal,
+    Master,
+    Replica,
+    PubSub,
+}
+
+impl ClientType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ClientType::Normal => "normal",
+            ClientType::Master => "master",
+            ClientType::Replica => "replica",
+            ClientType::PubSub => "pubsub",
+        }
+    }
+}
+
+/// A connection as described by CLIENT INFO and CLIENT LIST. Fields missing
+/// from older servers are left empty; `fields` holds every reported field.
+#[derive(Debug, Default)]
+pub struct ClientInfo {
+    pub id: u64,
+    pub addr: String,
+    pub laddr: Option<String>,
+    pub name: Option<String>,
+    pub age: Duration,
+    pub idle: Duration,
+    pub flags: String,
+    pub db: u64,
+    pub cmd: Option<String>,
+    pub user: Option<String>,
+    pub lib_name: Option<String>,
+    pub lib_ver: Option<String>,
+    pub fields: HashMap<String, String>,
+}
+
+impl ClientInfo {
+    /// Parses one line of `id=... addr=...` pairs.
+    pub fn parse(line: &str) -> Option<Self> {
+        let fields: HashMap<String, String> = line
+            .split_whitespace()
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let text = |k: &str| fields.get(k).filter(|v| !v.is_empty()).cloned();
+        let number = |k: &str| fields.get(k).and_then(|v| v.parse::<u64>().ok());
+        Some(ClientInfo {
+            id: number("id")?,
+            addr: text("addr")?,
+            laddr: text("laddr"),
+            name: text("name"),
+            age: Duration::from_secs(number("age").unwrap_or_default()),
+            idle: Duration::from_secs(number("idle").unwrap_or_default()),
+            flags: text("flags").unwrap_or_default(),
+            db: number("db").unwrap_or_default(),
+            cmd: text("cmd"),
+            user: text("user"),
+            lib_name: text("lib-name"),
+            lib_ver: text("lib-ver"),
+            fields,
+        })
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+pub struct ConfigGet {
+    parameters: Vec<Vec<u8>>,
+}
+
+impl ConfigGet {
+    pub fn new(parameters: Vec<Vec<u8>>) -> Self {
+        ConfigGet { parameters }
+    }
+}
+
+impl Command for ConfigGet {
+    fn name(&self) -> &'static str {
+        "CONFIG"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec!["CONFIG".as_bytes(), "GET".as_bytes()];
+        for parameter in &self.parameters {
+            args.push(parameter.as_slice());
+        }
+        encode_args(out, &args)
+    }
+}
+
+pub struct ConfigSet {
+    parameters: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl ConfigSet {
+    pub fn new(parameters: Vec<(Vec<u8>, Vec<u8>)>) -> Self {
+        ConfigSet { parameters }
+    }
+}
+
+impl Command for ConfigSet {
+    fn name(&self) -> &'static str {
+        "CONFIG"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec!["CONFIG".as_bytes(), "SET".as_bytes()];
+        for (parameter, value) in &self.parameters {
+            args.push(parameter.as_slice());
+            args.push(value.as_slice());
+        }
+        encode_args(out, &args)
+    }
+}
+
+pub struct ConfigResetStat;
+
+impl Command for ConfigResetStat {
+    fn name(&self) -> &'static str {
+        "CONFIG"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["CONFIG".as_bytes(), "RESETSTAT".as_bytes()])
+    }
+}
+
+pub struct ConfigRewrite;
+
+impl Command for ConfigRewrite {
+    fn name(&self) -> &'static str {
+        "CONFIG"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["CONFIG".as_bytes(), "REWRITE".as_bytes()])
+    }
+}
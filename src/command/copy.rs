@@ -0,0 +1,62 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+pub struct CopyKey {
+    source: Vec<u8>,
+    destination: Vec<u8>,
+    db: Option<u64>,
+    replace: bool,
+}
+
+impl CopyKey {
+    pub fn new(source: Vec<u8>, destination: Vec<u8>, db: Option<u64>, replace: bool) -> Self {
+        CopyKey {
+            source,
+            destination,
+            db,
+            replace,
+        }
+    }
+}
+
+impl Command for CopyKey {
+    fn name(&self) -> &'static str {
+        "COPY"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec![
+            "COPY".as_bytes(),
+            self.source.as_slice(),
+            self.destination.as_slice(),
+        ];
+
+        let db; // lift db index lifetime
+        if let Some(n) = self.db {
+            db = n.to_string();
+            args.push("DB".as_bytes());
+            args.push(db.as_bytes());
+        }
+
+        if self.replace {
+            args.push("REPLACE".as_bytes());
+        }
+
+        encode_args(out, &args)
+    }
+}
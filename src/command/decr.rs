@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::{args_to_bytes, Command};
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
 
 pub struct Decr {
     key: Vec<u8>,
@@ -25,7 +27,11 @@ impl Decr {
 }
 
 impl Command for Decr {
-    fn as_bytes(&self) -> Vec<u8> {
-        args_to_bytes(vec!["DECR".as_bytes(), self.key.as_slice()])
+    fn name(&self) -> &'static str {
+        "DECR"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["DECR".as_bytes(), self.key.as_slice()])
     }
 }
@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::{args_to_bytes, Command};
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
 
 pub struct DecrBy {
     key: Vec<u8>,
@@ -26,11 +28,18 @@ impl DecrBy {
 }
 
 impl Command for DecrBy {
-    fn as_bytes(&self) -> Vec<u8> {
-        args_to_bytes(vec![
-            "DECRBY".as_bytes(),
-            self.key.as_slice(),
-            self.decrement.to_string().as_bytes(),
-        ])
+    fn name(&self) -> &'static str {
+        "DECRBY"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(
+            out,
+            &[
+                "DECRBY".as_bytes(),
+                self.key.as_slice(),
+                self.decrement.to_string().as_bytes(),
+            ],
+        )
     }
 }
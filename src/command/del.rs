@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::{args_to_bytes, Command};
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
 
 pub struct Del {
     keys: Vec<Vec<u8>>,
@@ -25,11 +27,15 @@ impl Del {
 }
 
 impl Command for Del {
-    fn as_bytes(&self) -> Vec<u8> {
+    fn name(&self) -> &'static str {
+        "DEL"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
         let mut args = vec!["DEL".as_bytes()];
         for key in &self.keys {
             args.push(key.as_slice());
         }
-        args_to_bytes(args)
+        encode_args(out, &args)
     }
 }
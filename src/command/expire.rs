@@ -0,0 +1,147 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, SystemTime};
+
+use bytes::BytesMut;
+
+use crate::command::{ceil_secs, encode_args, unix_millis, unix_secs, Command};
+
+pub enum ExpireCondition {
+    None,
+    Nx,
+    Xx,
+    Gt,
+    Lt,
+}
+
+impl ExpireCondition {
+    fn push(&self, args: &mut Vec<&[u8]>) {
+        match self {
+            ExpireCondition::None => {}
+            ExpireCondition::Nx => args.push("NX".as_bytes()),
+            ExpireCondition::Xx => args.push("XX".as_bytes()),
+            ExpireCondition::Gt => args.push("GT".as_bytes()),
+            ExpireCondition::Lt => args.push("LT".as_bytes()),
+        }
+    }
+}
+
+pub struct Expire {
+    key: Vec<u8>,
+    ttl: Duration,
+    condition: ExpireCondition,
+}
+
+impl Expire {
+    pub fn new(key: Vec<u8>, ttl: Duration, condition: ExpireCondition) -> Self {
+        Expire {
+            key,
+            ttl,
+            condition,
+        }
+    }
+}
+
+impl Command for Expire {
+    fn name(&self) -> &'static str {
+        "EXPIRE"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let ttl = ceil_secs(self.ttl).to_string();
+        let mut args = vec!["EXPIRE".as_bytes(), self.key.as_slice(), ttl.as_bytes()];
+        self.condition.push(&mut args);
+        encode_args(out, &args)
+    }
+}
+
+pub struct PExpire {
+    key: Vec<u8>,
+    ttl: Duration,
+    condition: ExpireCondition,
+}
+
+impl PExpire {
+    pub fn new(key: Vec<u8>, ttl: Duration, condition: ExpireCondition) -> Self {
+        PExpire {
+            key,
+            ttl,
+            condition,
+        }
+    }
+}
+
+impl Command for PExpire {
+    fn name(&self) -> &'static str {
+        "PEXPIRE"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let ttl = self.ttl.as_millis().to_string();
+        let mut args = vec!["PEXPIRE".as_bytes(), self.key.as_slice(), ttl.as_bytes()];
+        self.condition.push(&mut args);
+        encode_args(out, &args)
+    }
+}
+
+pub struct ExpireAt {
+    key: Vec<u8>,
+    at: SystemTime,
+    condition: ExpireCondition,
+}
+
+impl ExpireAt {
+    pub fn new(key: Vec<u8>, at: SystemTime, condition: ExpireCondition) -> Self {
+        ExpireAt { key, at, condition }
+    }
+}
+
+impl Command for ExpireAt {
+    fn name(&self) -> &'static str {
+        "EXPIREAT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let at = unix_secs(self.at).to_string();
+        let mut args = vec!["EXPIREAT".as_bytes(), self.key.as_slice(), at.as_bytes()];
+        self.condition.push(&mut args);
+        encode_args(out, &args)
+    }
+}
+
+pub struct PExpireAt {
+    key: Vec<u8>,
+    at: SystemTime,
+    condition: ExpireCondition,
+}
+
+impl PExpireAt {
+    pub fn new(key: Vec<u8>, at: SystemTime, condition: ExpireCondition) -> Self {
+        PExpireAt { key, at, condition }
+    }
+}
+
+impl Command for PExpireAt {
+    fn name(&self) -> &'static str {
+        "PEXPIREAT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let at = unix_millis(self.at).to_string();
+        let mut args = vec!["PEXPIREAT".as_bytes(), self.key.as_slice(), at.as_bytes()];
+        self.condition.push(&mut args);
+        encode_args(out, &args)
+    }
+}
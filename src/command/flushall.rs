@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::{args_to_bytes, Command};
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
 
 pub struct FlushAll {
     sync: bool,
@@ -25,10 +27,17 @@ impl FlushAll {
 }
 
 impl Command for FlushAll {
-    fn as_bytes(&self) -> Vec<u8> {
-        args_to_bytes(vec![
-            "FLUSHALL".as_bytes(),
-            if self.sync { "SYNC" } else { "ASYNC" }.as_bytes(),
-        ])
+    fn name(&self) -> &'static str {
+        "FLUSHALL"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(
+            out,
+            &[
+                "FLUSHALL".as_bytes(),
+                if self.sync { "SYNC" } else { "ASYNC" }.as_bytes(),
+            ],
+        )
     }
 }
@@ -0,0 +1,94 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command, GeoCoord};
+
+pub struct GeoAdd {
+    key: Vec<u8>,
+    members: Vec<(GeoCoord, Vec<u8>)>,
+    option: GeoAddOption,
+}
+
+impl Command for GeoAdd {
+    fn name(&self) -> &'static str {
+        "GEOADD"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec!["GEOADD".as_bytes(), self.key.as_slice()];
+
+        if self.option.nx {
+            args.push("NX".as_bytes());
+        }
+        if self.option.xx {
+            args.push("XX".as_bytes());
+        }
+        if self.option.ch {
+            args.push("CH".as_bytes());
+        }
+
+        let coords: Vec<(String, String)> = self
+            .members
+            .iter()
+            .map(|(coord, _)| (coord.longitude.to_string(), coord.latitude.to_string()))
+            .collect();
+        for ((longitude, latitude), (_, member)) in coords.iter().zip(self.members.iter()) {
+            args.push(longitude.as_bytes());
+            args.push(latitude.as_bytes());
+            args.push(member.as_slice());
+        }
+
+        encode_args(out, &args)
+    }
+}
+
+#[derive(Default)]
+pub struct GeoAddOption {
+    nx: bool,
+    xx: bool,
+    ch: bool,
+}
+
+impl GeoAddOption {
+    pub fn nx(self) -> Self {
+        GeoAddOption {
+            nx: true,
+            xx: false,
+            ..self
+        }
+    }
+
+    pub fn xx(self) -> Self {
+        GeoAddOption {
+            nx: false,
+            xx: true,
+            ..self
+        }
+    }
+
+    /// Count changed members, not only added ones, in the reply.
+    pub fn ch(self) -> Self {
+        GeoAddOption { ch: true, ..self }
+    }
+
+    pub(crate) fn build(self, key: Vec<u8>, members: Vec<(GeoCoord, Vec<u8>)>) -> GeoAdd {
+        GeoAdd {
+            key,
+            members,
+            option: self,
+        }
+    }
+}
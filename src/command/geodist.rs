@@ -0,0 +1,58 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command, GeoUnit};
+
+pub struct GeoDist {
+    key: Vec<u8>,
+    member1: Vec<u8>,
+    member2: Vec<u8>,
+    unit: GeoUnit,
+}
+
+impl GeoDist {
+    pub fn new(key: Vec<u8>, member1: Vec<u8>, member2: Vec<u8>, unit: GeoUnit) -> Self {
+        GeoDist {
+            key,
+            member1,
+            member2,
+            unit,
+        }
+    }
+}
+
+impl Command for GeoDist {
+    fn name(&self) -> &'static str {
+        "GEODIST"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(
+            out,
+            &[
+                "GEODIST".as_bytes(),
+                self.key.as_slice(),
+                self.member1.as_slice(),
+                self.member2.as_slice(),
+                self.unit.as_str().as_bytes(),
+            ],
+        )
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+}
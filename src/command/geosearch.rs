@@ -0,0 +1,308 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeoCoord {
+    pub longitude: f64,
+    pub latitude: f64,
+}
+
+impl GeoCoord {
+    pub fn new(longitude: f64, latitude: f64) -> Self {
+        GeoCoord {
+            longitude,
+            latitude,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum GeoUnit {
+    M,
+    Km,
+    Mi,
+    Ft,
+}
+
+impl GeoUnit {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            GeoUnit::M => "m",
+            GeoUnit::Km => "km",
+            GeoUnit::Mi => "mi",
+            GeoUnit::Ft => "ft",
+        }
+    }
+}
+
+/// A GEOSEARCH result. `dist`, `hash` and `coord` are only filled in when
+/// requested with WITHDIST, WITHHASH and WITHCOORD respectively.
+#[derive(Debug, PartialEq)]
+pub struct GeoMember<T = Vec<u8>> {
+    pub name: T,
+    pub dist: Option<f64>,
+    pub hash: Option<i64>,
+    pub coord: Option<GeoCoord>,
+}
+
+pub struct GeoSearch {
+    key: Vec<u8>,
+    option: GeoSearchOption,
+}
+
+impl Command for GeoSearch {
+    fn name(&self) -> &'static str {
+        "GEOSEARCH"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec!["GEOSEARCH".as_bytes(), self.key.as_slice()];
+        let strings = self.option.to_args();
+        args.extend(strings.iter().map(|arg| arg.as_slice()));
+
+        if self.option.with_coord {
+            args.push("WITHCOORD".as_bytes());
+        }
+        if self.option.with_dist {
+            args.push("WITHDIST".as_bytes());
+        }
+        if self.option.with_hash {
+            args.push("WITHHASH".as_bytes());
+        }
+
+        encode_args(out, &args)
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+}
+
+pub struct GeoSearchStore {
+    destination: Vec<u8>,
+    source: Vec<u8>,
+    option: GeoSearchOption,
+    store_dist: bool,
+}
+
+impl Command for GeoSearchStore {
+    fn name(&self) -> &'static str {
+        "GEOSEARCHSTORE"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec![
+            "GEOSEARCHSTORE".as_bytes(),
+            self.destination.as_slice(),
+            self.source.as_slice(),
+        ];
+        let strings = self.option.to_args();
+        args.extend(strings.iter().map(|arg| arg.as_slice()));
+
+        if self.store_dist {
+            args.push("STOREDIST".as_bytes());
+        }
+
+        encode_args(out, &args)
+    }
+}
+
+enum GeoFrom {
+    None,
+    Member(Vec<u8>),
+    LonLat(GeoCoord),
+}
+
+enum GeoBy {
+    None,
+    Radius(f64, GeoUnit),
+    Box(f64, f64, GeoUnit),
+}
+
+/// Where and how to search. One of `from_member` and `from_lonlat`, and one
+/// of `by_radius` and `by_box` are required.
+pub struct GeoSearchOption {
+    from: GeoFrom,
+    by: GeoBy,
+    order: Option<bool>,
+    count: Option<(u64, bool)>,
+    with_coord: bool,
+    with_dist: bool,
+    with_hash: bool,
+}
+
+impl Default for GeoSearchOption {
+    fn default() -> Self {
+        GeoSearchOption {
+            from: GeoFrom::None,
+            by: GeoBy::None,
+            order: None,
+            count: None,
+            with_coord: false,
+            with_dist: false,
+            with_hash: false,
+        }
+    }
+}
+
+impl GeoSearchOption {
+    pub fn from_member<M: Into<Vec<u8>>>(self, member: M) -> Self {
+        GeoSearchOption {
+            from: GeoFrom::Member(member.into()),
+            ..self
+        }
+    }
+
+    pub fn from_lonlat(self, coord: GeoCoord) -> Self {
+        GeoSearchOption {
+            from: GeoFrom::LonLat(coord),
+            ..self
+        }
+    }
+
+    pub fn by_radius(self, radius: f64, unit: GeoUnit) -> Self {
+        GeoSearchOption {
+            by: GeoBy::Radius(radius, unit),
+            ..self
+        }
+    }
+
+    pub fn by_box(self, width: f64, height: f64, unit: GeoUnit) -> Self {
+        GeoSearchOption {
+            by: GeoBy::Box(width, height, unit),
+            ..self
+        }
+    }
+
+    pub fn asc(self) -> Self {
+        GeoSearchOption {
+            order: Some(true),
+            ..self
+        }
+    }
+
+    pub fn desc(self) -> Self {
+        GeoSearchOption {
+            order: Some(false),
+            ..self
+        }
+    }
+
+    pub fn count(self, count: u64) -> Self {
+        GeoSearchOption {
+            count: Some((count, false)),
+            ..self
+        }
+    }
+
+    /// Return as soon as `count` matches are found, which are not necessarily
+    /// the closest ones.
+    pub fn count_any(self, count: u64) -> Self {
+        GeoSearchOption {
+            count: Some((count, true)),
+            ..self
+        }
+    }
+
+    pub fn with_coord(self) -> Self {
+        GeoSearchOption {
+            with_coord: true,
+            ..self
+        }
+    }
+
+    pub fn with_dist(self) -> Self {
+        GeoSearchOption {
+            with_dist: true,
+            ..self
+        }
+    }
+
+    pub fn with_hash(self) -> Self {
+        GeoSearchOption {
+            with_hash: true,
+            ..self
+        }
+    }
+
+    pub(crate) fn build(self, key: Vec<u8>) -> GeoSearch {
+        GeoSearch { key, option: self }
+    }
+
+    pub(crate) fn build_store(
+        self,
+        destination: Vec<u8>,
+        source: Vec<u8>,
+        store_dist: bool,
+    ) -> GeoSearchStore {
+        GeoSearchStore {
+            destination,
+            source,
+            option: self,
+            store_dist,
+        }
+    }
+
+    fn to_args(&self) -> Vec<Vec<u8>> {
+        let mut args: Vec<Vec<u8>> = vec![];
+
+        match &self.from {
+            GeoFrom::None => {}
+            GeoFrom::Member(member) => {
+                args.push(b"FROMMEMBER".to_vec());
+                args.push(member.clone());
+            }
+            GeoFrom::LonLat(coord) => {
+                args.push(b"FROMLONLAT".to_vec());
+                args.push(coord.longitude.to_string().into_bytes());
+                args.push(coord.latitude.to_string().into_bytes());
+            }
+        }
+
+        match self.by {
+            GeoBy::None => {}
+            GeoBy::Radius(radius, unit) => {
+                args.push(b"BYRADIUS".to_vec());
+                args.push(radius.to_string().into_bytes());
+                args.push(unit.as_str().as_bytes().to_vec());
+            }
+            GeoBy::Box(width, height, unit) => {
+                args.push(b"BYBOX".to_vec());
+                args.push(width.to_string().into_bytes());
+                args.push(height.to_string().into_bytes());
+                args.push(unit.as_str().as_bytes().to_vec());
+            }
+        }
+
+        match self.order {
+            None => {}
+            Some(true) => args.push(b"ASC".to_vec()),
+            Some(false) => args.push(b"DESC".to_vec()),
+        }
+
+        if let Some((count, any)) = self.count {
+            args.push(b"COUNT".to_vec());
+            args.push(count.to_string().into_bytes());
+            if any {
+                args.push(b"ANY".to_vec());
+            }
+        }
+
+        args
+    }
+}
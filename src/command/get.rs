@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::{args_to_bytes, Command};
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
 
 pub struct Get {
     key: Vec<u8>,
@@ -25,7 +27,15 @@ impl Get {
 }
 
 impl Command for Get {
-    fn as_bytes(&self) -> Vec<u8> {
-        args_to_bytes(vec!["GET".as_bytes(), self.key.as_slice()])
+    fn name(&self) -> &'static str {
+        "GET"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["GET".as_bytes(), self.key.as_slice()])
+    }
+
+    fn read_only(&self) -> bool {
+        true
     }
 }
@@ -0,0 +1,122 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, SystemTime};
+
+use bytes::BytesMut;
+
+use crate::command::{ceil_secs, encode_args, unix_millis, unix_secs, Command};
+
+pub struct GetEx {
+    key: Vec<u8>,
+    expire: GetExExpire,
+}
+
+impl Command for GetEx {
+    fn name(&self) -> &'static str {
+        "GETEX"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec!["GETEX".as_bytes(), self.key.as_slice()];
+
+        let expire; // lift expire duration/timestamp lifetime
+        match self.expire {
+            GetExExpire::None => {}
+            GetExExpire::Ex(d) => {
+                expire = ceil_secs(d).to_string();
+                args.push("EX".as_bytes());
+                args.push(expire.as_bytes());
+            }
+            GetExExpire::Px(d) => {
+                expire = d.as_millis().to_string();
+                args.push("PX".as_bytes());
+                args.push(expire.as_bytes());
+            }
+            GetExExpire::Exat(t) => {
+                expire = unix_secs(t).to_string();
+                args.push("EXAT".as_bytes());
+                args.push(expire.as_bytes());
+            }
+            GetExExpire::Pxat(t) => {
+                expire = unix_millis(t).to_string();
+                args.push("PXAT".as_bytes());
+                args.push(expire.as_bytes());
+            }
+            GetExExpire::Persist => args.push("PERSIST".as_bytes()),
+        }
+
+        encode_args(out, &args)
+    }
+}
+
+pub struct GetExOption {
+    expire: GetExExpire,
+}
+
+enum GetExExpire {
+    None,
+    Ex(Duration),
+    Px(Duration),
+    Exat(SystemTime),
+    Pxat(SystemTime),
+    Persist,
+}
+
+impl Default for GetExOption {
+    fn default() -> Self {
+        GetExOption {
+            expire: GetExExpire::None,
+        }
+    }
+}
+
+impl GetExOption {
+    pub fn ex(self, ex: Duration) -> Self {
+        GetExOption {
+            expire: GetExExpire::Ex(ex),
+        }
+    }
+
+    pub fn px(self, px: Duration) -> Self {
+        GetExOption {
+            expire: GetExExpire::Px(px),
+        }
+    }
+
+    pub fn exat(self, exat: SystemTime) -> Self {
+        GetExOption {
+            expire: GetExExpire::Exat(exat),
+        }
+    }
+
+    pub fn pxat(self, pxat: SystemTime) -> Self {
+        GetExOption {
+            expire: GetExExpire::Pxat(pxat),
+        }
+    }
+
+    pub fn persist(self) -> Self {
+        GetExOption {
+            expire: GetExExpire::Persist,
+        }
+    }
+
+    pub(crate) fn build(self, key: Vec<u8>) -> GetEx {
+        GetEx {
+            key,
+            expire: self.expire,
+        }
+    }
+}
@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::{args_to_bytes, Command};
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
 
 pub struct GetRange {
     key: Vec<u8>,
@@ -27,12 +29,23 @@ impl GetRange {
 }
 
 impl Command for GetRange {
-    fn as_bytes(&self) -> Vec<u8> {
-        args_to_bytes(vec![
-            "GETRANGE".as_bytes(),
-            self.key.as_slice(),
-            self.start.to_string().as_bytes(),
-            self.end.to_string().as_bytes(),
-        ])
+    fn name(&self) -> &'static str {
+        "GETRANGE"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(
+            out,
+            &[
+                "GETRANGE".as_bytes(),
+                self.key.as_slice(),
+                self.start.to_string().as_bytes(),
+                self.end.to_string().as_bytes(),
+            ],
+        )
+    }
+
+    fn read_only(&self) -> bool {
+        true
     }
 }
@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::{args_to_bytes, Command};
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
 
 pub struct IncrBy {
     key: Vec<u8>,
@@ -26,11 +28,18 @@ impl IncrBy {
 }
 
 impl Command for IncrBy {
-    fn as_bytes(&self) -> Vec<u8> {
-        args_to_bytes(vec![
-            "INCRBY".as_bytes(),
-            self.key.as_slice(),
-            self.increment.to_string().as_bytes(),
-        ])
+    fn name(&self) -> &'static str {
+        "INCRBY"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(
+            out,
+            &[
+                "INCRBY".as_bytes(),
+                self.key.as_slice(),
+                self.increment.to_string().as_bytes(),
+            ],
+        )
     }
 }
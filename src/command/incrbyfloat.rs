@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::{args_to_bytes, Command};
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
 
 pub struct IncrByFloat {
     key: Vec<u8>,
@@ -26,11 +28,18 @@ impl IncrByFloat {
 }
 
 impl Command for IncrByFloat {
-    fn as_bytes(&self) -> Vec<u8> {
-        args_to_bytes(vec![
-            "INCRBYFLOAT".as_bytes(),
-            self.key.as_slice(),
-            self.increment.to_string().as_bytes(),
-        ])
+    fn name(&self) -> &'static str {
+        "INCRBYFLOAT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(
+            out,
+            &[
+                "INCRBYFLOAT".as_bytes(),
+                self.key.as_slice(),
+                self.increment.to_string().as_bytes(),
+            ],
+        )
     }
 }
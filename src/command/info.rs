@@ -0,0 +1,133 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::HashMap, str::FromStr, time::Duration};
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+pub struct Info {
+    sections: Vec<Vec<u8>>,
+}
+
+impl Info {
+    pub fn new(sections: Vec<Vec<u8>>) -> Self {
+        Info { sections }
+    }
+}
+
+impl Command for Info {
+    fn name(&self) -> &'static str {
+        "INFO"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec!["INFO".as_bytes()];
+        for section in &self.sections {
+            args.push(section.as_slice());
+        }
+        encode_args(out, &args)
+    }
+}
+
+/// The reply of INFO, as fields grouped by lowercase section names.
+#[derive(Debug, Default)]
+pub struct ServerInfo {
+    sections: HashMap<String, HashMap<String, String>>,
+}
+
+impl ServerInfo {
+    pub fn parse(text: &str) -> Self {
+        let mut info = ServerInfo::default();
+        let mut section = String::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if let Some(name) = line.strip_prefix('#') {
+                section = name.trim().to_lowercase();
+            } else if let Some((field, value)) = line.split_once(':') {
+                info.sections
+                    .entry(section.clone())
+                    .or_default()
+                    .insert(field.to_string(), value.to_string());
+            }
+        }
+        info
+    }
+
+    pub fn sections(&self) -> impl Iterator<Item = &str> {
+        self.sections.keys().map(|s| s.as_str())
+    }
+
+    pub fn section(&self, name: &str) -> Option<&HashMap<String, String>> {
+        self.sections.get(&name.to_lowercase())
+    }
+
+    /// Looks a field up in all sections.
+    pub fn get(&self, field: &str) -> Option<&str> {
+        self.sections
+            .values()
+            .find_map(|fields| fields.get(field))
+            .map(|value| value.as_str())
+    }
+
+    pub fn get_parsed<T: FromStr>(&self, field: &str) -> Option<T> {
+        self.get(field).and_then(|value| value.parse().ok())
+    }
+
+    pub fn redis_version(&self) -> Option<&str> {
+        self.get("redis_version")
+    }
+
+    pub fn role(&self) -> Option<&str> {
+        self.get("role")
+    }
+
+    pub fn uptime(&self) -> Option<Duration> {
+        self.get_parsed("uptime_in_seconds")
+            .map(Duration::from_secs)
+    }
+
+    pub fn connected_clients(&self) -> Option<u64> {
+        self.get_parsed("connected_clients")
+    }
+
+    pub fn blocked_clients(&self) -> Option<u64> {
+        self.get_parsed("blocked_clients")
+    }
+
+    pub fn used_memory(&self) -> Option<u64> {
+        self.get_parsed("used_memory")
+    }
+
+    pub fn used_memory_peak(&self) -> Option<u64> {
+        self.get_parsed("used_memory_peak")
+    }
+
+    pub fn maxmemory(&self) -> Option<u64> {
+        self.get_parsed("maxmemory")
+    }
+
+    pub fn total_commands_processed(&self) -> Option<u64> {
+        self.get_parsed("total_commands_processed")
+    }
+
+    pub fn keyspace_hits(&self) -> Option<u64> {
+        self.get_parsed("keyspace_hits")
+    }
+
+    pub fn keyspace_misses(&self) -> Option<u64> {
+        self.get_parsed("keyspace_misses")
+    }
+}
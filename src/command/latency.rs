@@ -0,0 +1,73 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, SystemTime};
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+pub struct LatencyLatest;
+
+impl Command for LatencyLatest {
+    fn name(&self) -> &'static str {
+        "LATENCY"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["LATENCY".as_bytes(), "LATEST".as_bytes()])
+    }
+}
+
+pub struct LatencyHistory {
+    event: Vec<u8>,
+}
+
+impl LatencyHistory {
+    pub fn new(event: Vec<u8>) -> Self {
+        LatencyHistory { event }
+    }
+}
+
+impl Command for LatencyHistory {
+    fn name(&self) -> &'static str {
+        "LATENCY"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(
+            out,
+            &[
+                "LATENCY".as_bytes(),
+                "HISTORY".as_bytes(),
+                self.event.as_slice(),
+            ],
+        )
+    }
+}
+
+/// The latest and the all-time maximum latency spike of an event.
+#[derive(Debug)]
+pub struct LatencyEvent {
+    pub event: String,
+    pub time: SystemTime,
+    pub latest: Duration,
+    pub max: Duration,
+}
+
+#[derive(Debug)]
+pub struct LatencySample {
+    pub time: SystemTime,
+    pub latency: Duration,
+}
@@ -0,0 +1,118 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+pub struct Lcs {
+    key1: Vec<u8>,
+    key2: Vec<u8>,
+    mode: LcsMode,
+}
+
+enum LcsMode {
+    String,
+    Len,
+    Idx {
+        min_match_len: Option<u64>,
+        with_match_len: bool,
+    },
+}
+
+impl Lcs {
+    pub fn new(key1: Vec<u8>, key2: Vec<u8>) -> Self {
+        Lcs {
+            key1,
+            key2,
+            mode: LcsMode::String,
+        }
+    }
+
+    pub fn len(key1: Vec<u8>, key2: Vec<u8>) -> Self {
+        Lcs {
+            key1,
+            key2,
+            mode: LcsMode::Len,
+        }
+    }
+
+    pub fn idx(
+        key1: Vec<u8>,
+        key2: Vec<u8>,
+        min_match_len: Option<u64>,
+        with_match_len: bool,
+    ) -> Self {
+        Lcs {
+            key1,
+            key2,
+            mode: LcsMode::Idx {
+                min_match_len,
+                with_match_len,
+            },
+        }
+    }
+}
+
+impl Command for Lcs {
+    fn name(&self) -> &'static str {
+        "LCS"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec!["LCS".as_bytes(), self.key1.as_slice(), self.key2.as_slice()];
+
+        let min_match_len; // lift min match len lifetime
+        match self.mode {
+            LcsMode::String => {}
+            LcsMode::Len => args.push("LEN".as_bytes()),
+            LcsMode::Idx {
+                min_match_len: n,
+                with_match_len,
+            } => {
+                args.push("IDX".as_bytes());
+                if let Some(n) = n {
+                    min_match_len = n.to_string();
+                    args.push("MINMATCHLEN".as_bytes());
+                    args.push(min_match_len.as_bytes());
+                }
+                if with_match_len {
+                    args.push("WITHMATCHLEN".as_bytes());
+                }
+            }
+        }
+
+        encode_args(out, &args)
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+}
+
+/// Decoded reply of `LCS ... IDX`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct LcsMatches {
+    pub matches: Vec<LcsMatch>,
+    pub len: u64,
+}
+
+/// A common substring, as inclusive byte ranges in the first and the second
+/// string. `len` is only filled in when WITHMATCHLEN is requested.
+#[derive(Debug, PartialEq, Eq)]
+pub struct LcsMatch {
+    pub key1: (u64, u64),
+    pub key2: (u64, u64),
+    pub len: Option<u64>,
+}
@@ -0,0 +1,68 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+pub struct MemoryUsage {
+    key: Vec<u8>,
+    samples: Option<u64>,
+}
+
+impl MemoryUsage {
+    pub fn new(key: Vec<u8>, samples: Option<u64>) -> Self {
+        MemoryUsage { key, samples }
+    }
+}
+
+impl Command for MemoryUsage {
+    fn name(&self) -> &'static str {
+        "MEMORY"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec!["MEMORY".as_bytes(), "USAGE".as_bytes(), self.key.as_slice()];
+        let samples = self.samples.map(|n| n.to_string());
+        if let Some(samples) = &samples {
+            args.push("SAMPLES".as_bytes());
+            args.push(samples.as_bytes());
+        }
+        encode_args(out, &args)
+    }
+}
+
+pub struct MemoryStats;
+
+impl Command for MemoryStats {
+    fn name(&self) -> &'static str {
+        "MEMORY"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["MEMORY".as_bytes(), "STATS".as_bytes()])
+    }
+}
+
+pub struct MemoryDoctor;
+
+impl Command for MemoryDoctor {
+    fn name(&self) -> &'static str {
+        "MEMORY"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["MEMORY".as_bytes(), "DOCTOR".as_bytes()])
+    }
+}
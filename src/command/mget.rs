@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::{args_to_bytes, Command};
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
 
 pub struct MGet {
     keys: Vec<Vec<u8>>,
@@ -25,11 +27,19 @@ impl MGet {
 }
 
 impl Command for MGet {
-    fn as_bytes(&self) -> Vec<u8> {
+    fn name(&self) -> &'static str {
+        "MGET"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
         let mut args = vec!["MGET".as_bytes()];
         for key in &self.keys {
             args.push(key.as_slice());
         }
-        args_to_bytes(args)
+        encode_args(out, &args)
+    }
+
+    fn read_only(&self) -> bool {
+        true
     }
 }
@@ -13,55 +13,549 @@
 // limitations under the License.
 
 mod append;
+mod bgrewriteaof;
+mod bgsave;
+mod bitcount;
+mod bitfield;
+mod bitop;
+mod bitpos;
+mod client;
+mod config;
+mod copy;
+mod dbsize;
 mod decr;
 mod decrby;
 mod del;
+mod dump;
 mod exists;
+mod expire;
+mod expiretime;
 mod flushall;
+mod flushdb;
+mod geoadd;
+mod geodist;
+mod geohash;
+mod geopos;
+mod geosearch;
 mod get;
+mod getbit;
+mod getdel;
+mod getex;
 mod getrange;
+mod hello;
 mod incr;
 mod incrby;
 mod incrbyfloat;
+mod info;
+mod keys;
+mod lastsave;
+mod latency;
+mod lcs;
+mod memory;
 mod mget;
+mod r#move;
 mod mset;
 mod msetnx;
+mod object;
+mod persist;
+mod pexpiretime;
+mod pfadd;
+mod pfcount;
+mod pfmerge;
 mod ping;
+mod psetex;
+mod pttl;
+mod randomkey;
+mod raw;
+mod readonly;
+mod rename;
+mod renamenx;
+mod restore;
+mod save;
+mod scan;
 mod set;
+mod setbit;
+mod setex;
+mod setnx;
 mod setrange;
+mod slowlog;
+mod sort;
 mod strlen;
+mod subscribe;
+mod substr;
+mod time;
+mod touch;
+mod ttl;
+mod r#type;
+mod unlink;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub use append::Append;
+pub use bgrewriteaof::BgRewriteAof;
+pub use bgsave::BgSave;
+pub use bitcount::{BitCount, BitRange};
+pub use bitfield::{BitField, BitFieldOffset, BitFieldOption, BitFieldType, Overflow};
+pub use bitop::{BitOp, BitOperation};
+pub use bitpos::BitPos;
 use bytes::{BufMut, BytesMut};
+pub use client::{
+    ClientGetName, ClientId, ClientInfo, ClientInfoCommand, ClientKill, ClientKillOption,
+    ClientList, ClientNoEvict, ClientNoTouch, ClientPause, ClientReply, ClientReplyMode,
+    ClientSetInfo, ClientSetName, ClientTracking, ClientType, ClientUnpause,
+};
+pub use config::{ConfigGet, ConfigResetStat, ConfigRewrite, ConfigSet};
+pub use copy::CopyKey;
+pub use dbsize::DbSize;
 pub use decr::Decr;
 pub use decrby::DecrBy;
 pub use del::Del;
+pub use dump::Dump;
 pub use exists::Exists;
+pub use expire::{Expire, ExpireAt, ExpireCondition, PExpire, PExpireAt};
+pub use expiretime::ExpireTime;
 pub use flushall::FlushAll;
+pub use flushdb::FlushDb;
+pub use geoadd::{GeoAdd, GeoAddOption};
+pub use geodist::GeoDist;
+pub use geohash::GeoHash;
+pub use geopos::GeoPos;
+pub use geosearch::{GeoCoord, GeoMember, GeoSearch, GeoSearchOption, GeoSearchStore, GeoUnit};
 pub use get::Get;
+pub use getbit::GetBit;
+pub use getdel::GetDel;
+pub use getex::{GetEx, GetExOption};
 pub use getrange::GetRange;
+pub use hello::Hello;
 pub use incr::Incr;
 pub use incrby::IncrBy;
 pub use incrbyfloat::IncrByFloat;
+pub use info::{Info, ServerInfo};
+pub use keys::Keys;
+pub use lastsave::LastSave;
+pub use latency::{LatencyEvent, LatencyHistory, LatencyLatest, LatencySample};
+pub use lcs::{Lcs, LcsMatch, LcsMatches};
+pub use memory::{MemoryDoctor, MemoryStats, MemoryUsage};
 pub use mget::MGet;
 pub use mset::MSet;
 pub use msetnx::MSetNx;
+pub use object::{ObjectEncoding, ObjectFreq, ObjectIdleTime};
+pub use persist::Persist;
+pub use pexpiretime::PExpireTime;
+pub use pfadd::PfAdd;
+pub use pfcount::PfCount;
+pub use pfmerge::PfMerge;
 pub use ping::Ping;
+pub use psetex::PSetEx;
+pub use pttl::PTtl;
+pub use r#move::Move;
+pub use r#type::{KeyType, Type};
+pub use randomkey::RandomKey;
+pub use raw::Raw;
+pub use readonly::ReadOnly;
+pub use rename::Rename;
+pub use renamenx::RenameNx;
+pub use restore::{Restore, RestoreOption};
+pub use save::Save;
+pub use scan::{HScan, SScan, Scan, ScanOption, ZScan};
 pub use set::{Set, SetOption};
+pub use setbit::SetBit;
+pub use setex::SetEx;
+pub use setnx::SetNx;
 pub use setrange::SetRange;
+pub use slowlog::{SlowLogEntry, SlowLogGet, SlowLogLen, SlowLogReset};
+pub use sort::{Sort, SortOption, SortRo};
 pub use strlen::Strlen;
+pub use subscribe::{PSubscribe, Subscribe};
+pub use substr::Substr;
+pub use time::Time;
+pub use touch::Touch;
+pub use ttl::{Expiry, Ttl};
+pub use unlink::Unlink;
 
 pub trait Command {
-    fn as_bytes(&self) -> Vec<u8>;
+    /// The name the command is sent with, such as `GET`, or `CLIENT` for the
+    /// `CLIENT` subcommands.
+    fn name(&self) -> &'static str;
+
+    /// Appends the RESP encoding of the command to `out`.
+    fn encode(&self, out: &mut BytesMut);
+
+    /// Like [`Command::encode`], but arguments of at least [`LARGE_ARG`]
+    /// bytes are left out of `out` and returned, in order, along with the
+    /// offset they belong at, so that they can be written in place with a
+    /// vectored write instead of being copied.
+    fn encode_vectored<'a>(&'a self, out: &mut BytesMut) -> Vec<(usize, &'a [u8])> {
+        self.encode(out);
+        vec![]
+    }
+
+    /// Whether the command only reads data and thus can be served by a replica.
+    fn read_only(&self) -> bool {
+        false
+    }
 }
 
-fn args_to_bytes(args: Vec<&[u8]>) -> Vec<u8> {
-    let mut result = BytesMut::new();
-    result.put_slice(format!("*{}\r\n", args.len()).as_bytes());
+impl<C: Command> Command for &C {
+    fn name(&self) -> &'static str {
+        (*self).name()
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        (*self).encode(out)
+    }
+
+    fn encode_vectored<'a>(&'a self, out: &mut BytesMut) -> Vec<(usize, &'a [u8])> {
+        (*self).encode_vectored(out)
+    }
+
+    fn read_only(&self) -> bool {
+        (*self).read_only()
+    }
+}
+
+/// The size from which [`Command::encode_vectored`] leaves an argument out.
+pub const LARGE_ARG: usize = 64 * 1024;
+
+/// The names of the commands the server knows, in order. A [`Raw`] command by
+/// any other name reports itself as [`UNKNOWN_COMMAND`].
+pub const COMMAND_NAMES: &[&str] = &[
+    "ACL",
+    "APPEND",
+    "ASKING",
+    "AUTH",
+    "BGREWRITEAOF",
+    "BGSAVE",
+    "BITCOUNT",
+    "BITFIELD",
+    "BITFIELD_RO",
+    "BITOP",
+    "BITPOS",
+    "BLMOVE",
+    "BLMPOP",
+    "BLPOP",
+    "BRPOP",
+    "BRPOPLPUSH",
+    "BZMPOP",
+    "BZPOPMAX",
+    "BZPOPMIN",
+    "CLIENT",
+    "CLUSTER",
+    "COMMAND",
+    "CONFIG",
+    "COPY",
+    "DBSIZE",
+    "DEBUG",
+    "DECR",
+    "DECRBY",
+    "DEL",
+    "DISCARD",
+    "DUMP",
+    "ECHO",
+    "EVAL",
+    "EVALSHA",
+    "EVALSHA_RO",
+    "EVAL_RO",
+    "EXEC",
+    "EXISTS",
+    "EXPIRE",
+    "EXPIREAT",
+    "EXPIRETIME",
+    "FAILOVER",
+    "FCALL",
+    "FCALL_RO",
+    "FLUSHALL",
+    "FLUSHDB",
+    "FUNCTION",
+    "GEOADD",
+    "GEODIST",
+    "GEOHASH",
+    "GEOPOS",
+    "GEORADIUS",
+    "GEORADIUSBYMEMBER",
+    "GEOSEARCH",
+    "GEOSEARCHSTORE",
+    "GET",
+    "GETBIT",
+    "GETDEL",
+    "GETEX",
+    "GETRANGE",
+    "GETSET",
+    "HDEL",
+    "HELLO",
+    "HEXISTS",
+    "HGET",
+    "HGETALL",
+    "HINCRBY",
+    "HINCRBYFLOAT",
+    "HKEYS",
+    "HLEN",
+    "HMGET",
+    "HMSET",
+    "HRANDFIELD",
+    "HSCAN",
+    "HSET",
+    "HSETNX",
+    "HSTRLEN",
+    "HVALS",
+    "INCR",
+    "INCRBY",
+    "INCRBYFLOAT",
+    "INFO",
+    "KEYS",
+    "LASTSAVE",
+    "LATENCY",
+    "LCS",
+    "LINDEX",
+    "LINSERT",
+    "LLEN",
+    "LMOVE",
+    "LMPOP",
+    "LOLWUT",
+    "LPOP",
+    "LPOS",
+    "LPUSH",
+    "LPUSHX",
+    "LRANGE",
+    "LREM",
+    "LSET",
+    "LTRIM",
+    "MEMORY",
+    "MGET",
+    "MIGRATE",
+    "MODULE",
+    "MONITOR",
+    "MOVE",
+    "MSET",
+    "MSETNX",
+    "MULTI",
+    "OBJECT",
+    "PERSIST",
+    "PEXPIRE",
+    "PEXPIREAT",
+    "PEXPIRETIME",
+    "PFADD",
+    "PFCOUNT",
+    "PFMERGE",
+    "PING",
+    "PSETEX",
+    "PSUBSCRIBE",
+    "PSYNC",
+    "PTTL",
+    "PUBLISH",
+    "PUBSUB",
+    "PUNSUBSCRIBE",
+    "QUIT",
+    "RANDOMKEY",
+    "READONLY",
+    "READWRITE",
+    "RENAME",
+    "RENAMENX",
+    "REPLICAOF",
+    "RESET",
+    "RESTORE",
+    "ROLE",
+    "RPOP",
+    "RPOPLPUSH",
+    "RPUSH",
+    "RPUSHX",
+    "SADD",
+    "SAVE",
+    "SCAN",
+    "SCARD",
+    "SCRIPT",
+    "SDIFF",
+    "SDIFFSTORE",
+    "SELECT",
+    "SET",
+    "SETBIT",
+    "SETEX",
+    "SETNX",
+    "SETRANGE",
+    "SHUTDOWN",
+    "SINTER",
+    "SINTERCARD",
+    "SINTERSTORE",
+    "SISMEMBER",
+    "SLOWLOG",
+    "SMEMBERS",
+    "SMISMEMBER",
+    "SMOVE",
+    "SORT",
+    "SORT_RO",
+    "SPOP",
+    "SPUBLISH",
+    "SRANDMEMBER",
+    "SREM",
+    "SSCAN",
+    "SSUBSCRIBE",
+    "STRLEN",
+    "SUBSCRIBE",
+    "SUBSTR",
+    "SUNION",
+    "SUNIONSTORE",
+    "SUNSUBSCRIBE",
+    "SWAPDB",
+    "SYNC",
+    "TIME",
+    "TOUCH",
+    "TTL",
+    "TYPE",
+    "UNLINK",
+    "UNSUBSCRIBE",
+    "UNWATCH",
+    "WAIT",
+    "WAITAOF",
+    "WATCH",
+    "XACK",
+    "XADD",
+    "XAUTOCLAIM",
+    "XCLAIM",
+    "XDEL",
+    "XGROUP",
+    "XINFO",
+    "XLEN",
+    "XPENDING",
+    "XRANGE",
+    "XREAD",
+    "XREADGROUP",
+    "XREVRANGE",
+    "XSETID",
+    "XTRIM",
+    "ZADD",
+    "ZCARD",
+    "ZCOUNT",
+    "ZDIFF",
+    "ZDIFFSTORE",
+    "ZINCRBY",
+    "ZINTER",
+    "ZINTERCARD",
+    "ZINTERSTORE",
+    "ZLEXCOUNT",
+    "ZMPOP",
+    "ZMSCORE",
+    "ZPOPMAX",
+    "ZPOPMIN",
+    "ZRANDMEMBER",
+    "ZRANGE",
+    "ZRANGEBYLEX",
+    "ZRANGEBYSCORE",
+    "ZRANGESTORE",
+    "ZRANK",
+    "ZREM",
+    "ZREMRANGEBYLEX",
+    "ZREMRANGEBYRANK",
+    "ZREMRANGEBYSCORE",
+    "ZREVRANGE",
+    "ZREVRANGEBYLEX",
+    "ZREVRANGEBYSCORE",
+    "ZREVRANK",
+    "ZSCAN",
+    "ZSCORE",
+    "ZUNION",
+    "ZUNIONSTORE",
+];
+
+/// The name [`Command::name`] gives a [`Raw`] command not in
+/// [`COMMAND_NAMES`], so that names taken from input stay a bounded set.
+pub const UNKNOWN_COMMAND: &str = "UNKNOWN";
+
+fn encode_header(out: &mut BytesMut, prefix: u8, len: impl itoa::Integer) {
+    let mut buf = itoa::Buffer::new();
+    let len = buf.format(len).as_bytes();
+    out.reserve(len.len() + 3);
+    out.put_u8(prefix);
+    out.put_slice(len);
+    out.put_slice(b"\r\n");
+}
+
+fn encode_args(out: &mut BytesMut, args: &[&[u8]]) {
+    encode_header(out, b'*', args.len());
     for arg in args {
-        result.put_slice(format!("${}\r\n", arg.len()).as_bytes());
-        result.put_slice(arg);
-        result.put_slice("\r\n".as_bytes());
+        encode_header(out, b'$', arg.len());
+        out.reserve(arg.len() + 2);
+        out.put_slice(arg);
+        out.put_slice(b"\r\n");
+    }
+}
+
+/// Encodes like [`encode_args`] but leaves out arguments of at least
+/// [`LARGE_ARG`] bytes, returning the offset each one belongs at along with
+/// its index in `args`.
+fn encode_args_vectored(out: &mut BytesMut, args: &[&[u8]]) -> Vec<(usize, usize)> {
+    let mut large = vec![];
+    encode_header(out, b'*', args.len());
+    for (index, arg) in args.iter().enumerate() {
+        encode_header(out, b'$', arg.len());
+        if arg.len() >= LARGE_ARG {
+            large.push((out.len(), index));
+        } else {
+            out.put_slice(arg);
+        }
+        out.put_slice(b"\r\n");
+    }
+    large
+}
+
+/// Encodes like [`encode_args`] except for the argument at `index`, which is
+/// declared `len` bytes long but left to be written separately. Returns the
+/// offset it belongs at.
+fn encode_args_streamed(out: &mut BytesMut, args: &[&[u8]], index: usize, len: u64) -> usize {
+    let mut at = 0;
+    encode_header(out, b'*', args.len());
+    for (i, arg) in args.iter().enumerate() {
+        if i == index {
+            encode_header(out, b'$', len);
+            at = out.len();
+        } else {
+            encode_header(out, b'$', arg.len());
+            out.put_slice(arg);
+        }
+        out.put_slice(b"\r\n");
+    }
+    at
+}
+
+/// Reads up to `n` arguments of an encoded command, stopping early at one
+/// that is left out of the buffer for a vectored or streamed write.
+#[cfg(feature = "tracing")]
+pub(crate) fn leading_args(encoded: &[u8], n: usize) -> Vec<&[u8]> {
+    let mut args = vec![];
+    let mut pos = match decode_header(encoded, 0) {
+        Some((b'*', _, next)) => next,
+        _ => return args,
+    };
+    while args.len() < n {
+        let (len, start) = match decode_header(encoded, pos) {
+            Some((b'$', len, next)) => (len, next),
+            _ => break,
+        };
+        match encoded.get(start..start + len) {
+            Some(arg) if len < LARGE_ARG => args.push(arg),
+            _ => break,
+        }
+        pos = start + len + 2;
     }
-    result.to_vec()
+    args
+}
+
+/// Parses a `<prefix><length>\r\n` header at `pos`.
+#[cfg(feature = "tracing")]
+fn decode_header(encoded: &[u8], pos: usize) -> Option<(u8, usize, usize)> {
+    let prefix = *encoded.get(pos)?;
+    let end = pos + encoded[pos..].windows(2).position(|w| w == b"\r\n")?;
+    Some((prefix, atoi::atoi(&encoded[pos + 1..end])?, end + 2))
+}
+
+/// Rounds up, so that a sub-second TTL does not become `0` and expire at once.
+fn ceil_secs(d: Duration) -> u64 {
+    d.as_secs() + u64::from(d.subsec_nanos() > 0)
+}
+
+fn unix_secs(t: SystemTime) -> u64 {
+    t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+fn unix_millis(t: SystemTime) -> u128 {
+    t.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis()
 }
@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::{args_to_bytes, Command};
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
 
 pub struct MSetNx {
     kvs: Vec<(Vec<u8>, Vec<u8>)>,
@@ -25,12 +27,16 @@ impl MSetNx {
 }
 
 impl Command for MSetNx {
-    fn as_bytes(&self) -> Vec<u8> {
+    fn name(&self) -> &'static str {
+        "MSETNX"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
         let mut args = vec!["MSETNX".as_bytes()];
         for kv in &self.kvs {
             args.push(kv.0.as_slice());
             args.push(kv.1.as_slice());
         }
-        args_to_bytes(args)
+        encode_args(out, &args)
     }
 }
@@ -0,0 +1,106 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+pub struct ObjectEncoding {
+    key: Vec<u8>,
+}
+
+impl ObjectEncoding {
+    pub fn new(key: Vec<u8>) -> Self {
+        ObjectEncoding { key }
+    }
+}
+
+impl Command for ObjectEncoding {
+    fn name(&self) -> &'static str {
+        "OBJECT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(
+            out,
+            &[
+                "OBJECT".as_bytes(),
+                "ENCODING".as_bytes(),
+                self.key.as_slice(),
+            ],
+        )
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+}
+
+pub struct ObjectFreq {
+    key: Vec<u8>,
+}
+
+impl ObjectFreq {
+    pub fn new(key: Vec<u8>) -> Self {
+        ObjectFreq { key }
+    }
+}
+
+impl Command for ObjectFreq {
+    fn name(&self) -> &'static str {
+        "OBJECT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(
+            out,
+            &["OBJECT".as_bytes(), "FREQ".as_bytes(), self.key.as_slice()],
+        )
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+}
+
+pub struct ObjectIdleTime {
+    key: Vec<u8>,
+}
+
+impl ObjectIdleTime {
+    pub fn new(key: Vec<u8>) -> Self {
+        ObjectIdleTime { key }
+    }
+}
+
+impl Command for ObjectIdleTime {
+    fn name(&self) -> &'static str {
+        "OBJECT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(
+            out,
+            &[
+                "OBJECT".as_bytes(),
+                "IDLETIME".as_bytes(),
+                self.key.as_slice(),
+            ],
+        )
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+pub struct PExpireTime {
+    key: Vec<u8>,
+}
+
+impl PExpireTime {
+    pub fn new(key: Vec<u8>) -> Self {
+        PExpireTime { key }
+    }
+}
+
+impl Command for PExpireTime {
+    fn name(&self) -> &'static str {
+        "PEXPIRETIME"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["PEXPIRETIME".as_bytes(), self.key.as_slice()])
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+}
@@ -12,12 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::{args_to_bytes, Command};
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
 
 pub struct Ping;
 
 impl Command for Ping {
-    fn as_bytes(&self) -> Vec<u8> {
-        args_to_bytes(vec!["PING".as_bytes()])
+    fn name(&self) -> &'static str {
+        "PING"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["PING".as_bytes()])
     }
 }
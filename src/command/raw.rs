@@ -0,0 +1,49 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command, COMMAND_NAMES, UNKNOWN_COMMAND};
+
+/// Any command, given as its name followed by its arguments.
+pub struct Raw {
+    name: &'static str,
+    args: Vec<Vec<u8>>,
+}
+
+impl Raw {
+    pub fn new(args: Vec<Vec<u8>>) -> Self {
+        let name = args
+            .first()
+            .map(|name| name.to_ascii_uppercase())
+            .and_then(|name| {
+                COMMAND_NAMES
+                    .binary_search_by(|known| known.as_bytes().cmp(&name))
+                    .ok()
+            })
+            .map_or(UNKNOWN_COMMAND, |i| COMMAND_NAMES[i]);
+        Raw { name, args }
+    }
+}
+
+impl Command for Raw {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let args: Vec<&[u8]> = self.args.iter().map(Vec::as_slice).collect();
+        encode_args(out, &args)
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, SystemTime};
+
+use bytes::BytesMut;
+
+use crate::command::{ceil_secs, encode_args, unix_millis, Command};
+
+pub struct Restore {
+    key: Vec<u8>,
+    serialized: Vec<u8>,
+    ttl: RestoreTtl,
+    replace: bool,
+    idle_time: Option<Duration>,
+    freq: Option<u64>,
+}
+
+impl Command for Restore {
+    fn name(&self) -> &'static str {
+        "RESTORE"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let ttl = match self.ttl {
+            RestoreTtl::None => 0,
+            RestoreTtl::Ttl(ttl) => ttl.as_millis(),
+            RestoreTtl::At(at) => unix_millis(at),
+        }
+        .to_string();
+        let mut args = vec![
+            "RESTORE".as_bytes(),
+            self.key.as_slice(),
+            ttl.as_bytes(),
+            self.serialized.as_slice(),
+        ];
+
+        if self.replace {
+            args.push("REPLACE".as_bytes());
+        }
+
+        if let RestoreTtl::At(_) = self.ttl {
+            args.push("ABSTTL".as_bytes());
+        }
+
+        let idle_time; // lift idle time lifetime
+        if let Some(d) = self.idle_time {
+            idle_time = ceil_secs(d).to_string();
+            args.push("IDLETIME".as_bytes());
+            args.push(idle_time.as_bytes());
+        }
+
+        let freq; // lift frequency lifetime
+        if let Some(n) = self.freq {
+            freq = n.to_string();
+            args.push("FREQ".as_bytes());
+            args.push(freq.as_bytes());
+        }
+
+        encode_args(out, &args)
+    }
+}
+
+pub struct RestoreOption {
+    ttl: RestoreTtl,
+    replace: bool,
+    idle_time: Option<Duration>,
+    freq: Option<u64>,
+}
+
+enum RestoreTtl {
+    None,
+    Ttl(Duration),
+    At(SystemTime),
+}
+
+impl Default for RestoreOption {
+    fn default() -> Self {
+        RestoreOption {
+            ttl: RestoreTtl::None,
+            replace: false,
+            idle_time: None,
+            freq: None,
+        }
+    }
+}
+
+impl RestoreOption {
+    pub fn ttl(self, ttl: Duration) -> Self {
+        RestoreOption {
+            ttl: RestoreTtl::Ttl(ttl),
+            ..self
+        }
+    }
+
+    pub fn expire_at(self, at: SystemTime) -> Self {
+        RestoreOption {
+            ttl: RestoreTtl::At(at),
+            ..self
+        }
+    }
+
+    pub fn replace(self) -> Self {
+        RestoreOption {
+            replace: true,
+            ..self
+        }
+    }
+
+    pub fn idle_time(self, idle_time: Duration) -> Self {
+        RestoreOption {
+            idle_time: Some(idle_time),
+            ..self
+        }
+    }
+
+    pub fn freq(self, freq: u64) -> Self {
+        RestoreOption {
+            freq: Some(freq),
+            ..self
+        }
+    }
+
+    pub(crate) fn build(self, key: Vec<u8>, serialized: Vec<u8>) -> Restore {
+        Restore {
+            key,
+            serialized,
+            ttl: self.ttl,
+            replace: self.replace,
+            idle_time: self.idle_time,
+            freq: self.freq,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command, KeyType};
+
+// The SCAN family is not `read_only`: a cursor is only valid on the server that
+// returned it, so every page of a scan goes to the primary rather than to
+// whichever replica is next.
+
+pub struct Scan {
+    cursor: u64,
+    option: ScanOption,
+}
+
+impl Scan {
+    pub fn new(cursor: u64, option: ScanOption) -> Self {
+        Scan { cursor, option }
+    }
+}
+
+impl Command for Scan {
+    fn name(&self) -> &'static str {
+        "SCAN"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let cursor = self.cursor.to_string();
+        let mut args = vec!["SCAN".as_bytes(), cursor.as_bytes()];
+        let count = self.option.count.map(|n| n.to_string());
+        self.option.push(&mut args, &count);
+        if let Some(key_type) = &self.option.key_type {
+            args.push("TYPE".as_bytes());
+            args.push(key_type.as_str().as_bytes());
+        }
+        encode_args(out, &args)
+    }
+}
+
+pub struct HScan {
+    key: Vec<u8>,
+    cursor: u64,
+    option: ScanOption,
+}
+
+impl HScan {
+    pub fn new(key: Vec<u8>, cursor: u64, option: ScanOption) -> Self {
+        HScan {
+            key,
+            cursor,
+            option,
+        }
+    }
+}
+
+impl Command for HScan {
+    fn name(&self) -> &'static str {
+        "HSCAN"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let cursor = self.cursor.to_string();
+        let mut args = vec!["HSCAN".as_bytes(), self.key.as_slice(), cursor.as_bytes()];
+        let count = self.option.count.map(|n| n.to_string());
+        self.option.push(&mut args, &count);
+        encode_args(out, &args)
+    }
+}
+
+pub struct SScan {
+    key: Vec<u8>,
+    cursor: u64,
+    option: ScanOption,
+}
+
+impl SScan {
+    pub fn new(key: Vec<u8>, cursor: u64, option: ScanOption) -> Self {
+        SScan {
+            key,
+            cursor,
+            option,
+        }
+    }
+}
+
+impl Command for SScan {
+    fn name(&self) -> &'static str {
+        "SSCAN"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let cursor = self.cursor.to_string();
+        let mut args = vec!["SSCAN".as_bytes(), self.key.as_slice(), cursor.as_bytes()];
+        let count = self.option.count.map(|n| n.to_string());
+        self.option.push(&mut args, &count);
+        encode_args(out, &args)
+    }
+}
+
+pub struct ZScan {
+    key: Vec<u8>,
+    cursor: u64,
+    option: ScanOption,
+}
+
+impl ZScan {
+    pub fn new(key: Vec<u8>, cursor: u64, option: ScanOption) -> Self {
+        ZScan {
+            key,
+            cursor,
+            option,
+        }
+    }
+}
+
+impl Command for ZScan {
+    fn name(&self) -> &'static str {
+        "ZSCAN"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let cursor = self.cursor.to_string();
+        let mut args = vec!["ZSCAN".as_bytes(), self.key.as_slice(), cursor.as_bytes()];
+        let count = self.option.count.map(|n| n.to_string());
+        self.option.push(&mut args, &count);
+        encode_args(out, &args)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct ScanOption {
+    pattern: Option<Vec<u8>>,
+    count: Option<u64>,
+    key_type: Option<KeyType>,
+    dedupe: bool,
+}
+
+impl ScanOption {
+    pub fn pattern<P: Into<Vec<u8>>>(self, pattern: P) -> Self {
+        ScanOption {
+            pattern: Some(pattern.into()),
+            ..self
+        }
+    }
+
+    pub fn count(self, count: u64) -> Self {
+        ScanOption {
+            count: Some(count),
+            ..self
+        }
+    }
+
+    /// Only applies to SCAN; HSCAN, SSCAN and ZSCAN do not filter by type.
+    pub fn key_type(self, key_type: KeyType) -> Self {
+        ScanOption {
+            key_type: Some(key_type),
+            ..self
+        }
+    }
+
+    /// Drop elements already yielded, since a full iteration may return an
+    /// element more than once. Costs memory proportional to the elements seen.
+    pub fn dedupe(self) -> Self {
+        ScanOption {
+            dedupe: true,
+            ..self
+        }
+    }
+
+    pub(crate) fn is_dedupe(&self) -> bool {
+        self.dedupe
+    }
+
+    fn push<'a>(&'a self, args: &mut Vec<&'a [u8]>, count: &'a Option<String>) {
+        if let Some(pattern) = &self.pattern {
+            args.push("MATCH".as_bytes());
+            args.push(pattern.as_slice());
+        }
+
+        if let Some(count) = count {
+            args.push("COUNT".as_bytes());
+            args.push(count.as_bytes());
+        }
+    }
+}
@@ -12,7 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::{args_to_bytes, Command};
+use bytes::BytesMut;
+
+use crate::{
+    command::{encode_args, encode_args_streamed, encode_args_vectored, Command},
+    Model,
+};
 
 pub struct Set {
     key: Vec<u8>,
@@ -23,7 +28,33 @@ pub struct Set {
 }
 
 impl Command for Set {
-    fn as_bytes(&self) -> Vec<u8> {
+    fn name(&self) -> &'static str {
+        "SET"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        self.with_args(|args| encode_args(out, args))
+    }
+
+    fn encode_vectored<'a>(&'a self, out: &mut BytesMut) -> Vec<(usize, &'a [u8])> {
+        let large = self.with_args(|args| encode_args_vectored(out, args));
+        // options are never large
+        let arg = |i| match i {
+            1 => self.key.as_slice(),
+            _ => self.value.as_slice(),
+        };
+        large.into_iter().map(|(at, i)| (at, arg(i))).collect()
+    }
+}
+
+impl Set {
+    /// Encodes the command for its value to be streamed, see
+    /// [`encode_args_streamed`].
+    pub(crate) fn encode_streamed(&self, out: &mut BytesMut, len: u64) -> usize {
+        self.with_args(|args| encode_args_streamed(out, args, 2, len))
+    }
+
+    fn with_args<R>(&self, f: impl FnOnce(&[&[u8]]) -> R) -> R {
         let mut args = vec!["SET".as_bytes(), self.key.as_slice(), self.value.as_slice()];
 
         let expire; // lift expire duration/timestamp lifetime
@@ -62,13 +93,26 @@ impl Command for Set {
             args.push("GET".as_bytes())
         }
 
-        args_to_bytes(args)
+        f(&args)
+    }
+
+    /// Whether the value was written, judged from the reply. With the GET flag
+    /// the reply is the old value instead of OK, so the outcome depends on the
+    /// NX/XX condition.
+    pub(crate) fn is_written(&self, model: &Model) -> Option<bool> {
+        match model {
+            Model::Status(status) if status.eq_ignore_ascii_case("OK") => Some(true),
+            Model::Nil => Some(self.get && !matches!(self.exist, ExistOption::Xx)),
+            Model::String(_) if self.get => Some(!matches!(self.exist, ExistOption::Nx)),
+            _ => None,
+        }
     }
 }
 
 pub struct SetOption {
     expire: ExpireOption,
     exist: ExistOption,
+    get: bool,
 }
 
 pub enum ExpireOption {
@@ -91,6 +135,7 @@ impl Default for SetOption {
         SetOption {
             expire: ExpireOption::None,
             exist: ExistOption::None,
+            get: false,
         }
     }
 }
@@ -145,13 +190,17 @@ impl SetOption {
         }
     }
 
-    pub(crate) fn build(self, key: Vec<u8>, value: Vec<u8>, get: bool) -> Set {
+    pub fn get(self) -> Self {
+        SetOption { get: true, ..self }
+    }
+
+    pub(crate) fn build(self, key: Vec<u8>, value: Vec<u8>) -> Set {
         Set {
             key,
             value,
             expire: self.expire,
             exist: self.exist,
-            get,
+            get: self.get,
         }
     }
 }
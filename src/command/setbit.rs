@@ -0,0 +1,47 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+pub struct SetBit {
+    key: Vec<u8>,
+    offset: u64,
+    value: bool,
+}
+
+impl SetBit {
+    pub fn new(key: Vec<u8>, offset: u64, value: bool) -> Self {
+        SetBit { key, offset, value }
+    }
+}
+
+impl Command for SetBit {
+    fn name(&self) -> &'static str {
+        "SETBIT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(
+            out,
+            &[
+                "SETBIT".as_bytes(),
+                self.key.as_slice(),
+                self.offset.to_string().as_bytes(),
+                if self.value { "1" } else { "0" }.as_bytes(),
+            ],
+        )
+    }
+}
@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::{args_to_bytes, Command};
+use bytes::BytesMut;
+
+use crate::command::{encode_args, encode_args_vectored, Command};
 
 pub struct SetRange {
     key: Vec<u8>,
@@ -30,9 +32,9 @@ impl SetRange {
     }
 }
 
-impl Command for SetRange {
-    fn as_bytes(&self) -> Vec<u8> {
-        args_to_bytes(vec![
+impl SetRange {
+    fn with_args<R>(&self, f: impl FnOnce(&[&[u8]]) -> R) -> R {
+        f(&[
             "SETRANGE".as_bytes(),
             self.key.as_slice(),
             self.index.to_string().as_bytes(),
@@ -40,3 +42,23 @@ impl Command for SetRange {
         ])
     }
 }
+
+impl Command for SetRange {
+    fn name(&self) -> &'static str {
+        "SETRANGE"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        self.with_args(|args| encode_args(out, args))
+    }
+
+    fn encode_vectored<'a>(&'a self, out: &mut BytesMut) -> Vec<(usize, &'a [u8])> {
+        let large = self.with_args(|args| encode_args_vectored(out, args));
+        // the index is never large
+        let arg = |i| match i {
+            1 => self.key.as_slice(),
+            _ => self.substitute.as_slice(),
+        };
+        large.into_iter().map(|(at, i)| (at, arg(i))).collect()
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, SystemTime};
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+pub struct SlowLogGet {
+    count: Option<i64>,
+}
+
+impl SlowLogGet {
+    /// Fetch the latest `count` entries; -1 fetches all of them.
+    pub fn new(count: Option<i64>) -> Self {
+        SlowLogGet { count }
+    }
+}
+
+impl Command for SlowLogGet {
+    fn name(&self) -> &'static str {
+        "SLOWLOG"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec!["SLOWLOG".as_bytes(), "GET".as_bytes()];
+        let count = self.count.map(|n| n.to_string());
+        if let Some(count) = &count {
+            args.push(count.as_bytes());
+        }
+        encode_args(out, &args)
+    }
+}
+
+pub struct SlowLogLen;
+
+impl Command for SlowLogLen {
+    fn name(&self) -> &'static str {
+        "SLOWLOG"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["SLOWLOG".as_bytes(), "LEN".as_bytes()])
+    }
+}
+
+pub struct SlowLogReset;
+
+impl Command for SlowLogReset {
+    fn name(&self) -> &'static str {
+        "SLOWLOG"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["SLOWLOG".as_bytes(), "RESET".as_bytes()])
+    }
+}
+
+#[derive(Debug)]
+pub struct SlowLogEntry {
+    pub id: u64,
+    pub time: SystemTime,
+    pub duration: Duration,
+    pub args: Vec<Vec<u8>>,
+    /// Only reported by Redis 4.0 and later.
+    pub client_addr: Option<String>,
+    /// Only reported by Redis 4.0 and later.
+    pub client_name: Option<String>,
+}
@@ -0,0 +1,154 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+pub struct Sort {
+    key: Vec<u8>,
+    option: SortOption,
+    store: Option<Vec<u8>>,
+}
+
+impl Command for Sort {
+    fn name(&self) -> &'static str {
+        "SORT"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec!["SORT".as_bytes(), self.key.as_slice()];
+        let limit = self
+            .option
+            .limit
+            .map(|(o, c)| (o.to_string(), c.to_string()));
+        self.option.push(&mut args, &limit);
+        if let Some(destination) = &self.store {
+            args.push("STORE".as_bytes());
+            args.push(destination.as_slice());
+        }
+        encode_args(out, &args)
+    }
+}
+
+pub struct SortRo {
+    key: Vec<u8>,
+    option: SortOption,
+}
+
+impl Command for SortRo {
+    fn name(&self) -> &'static str {
+        "SORT_RO"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec!["SORT_RO".as_bytes(), self.key.as_slice()];
+        let limit = self
+            .option
+            .limit
+            .map(|(o, c)| (o.to_string(), c.to_string()));
+        self.option.push(&mut args, &limit);
+        encode_args(out, &args)
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Default)]
+pub struct SortOption {
+    by: Option<Vec<u8>>,
+    limit: Option<(u64, u64)>,
+    get: Vec<Vec<u8>>,
+    desc: bool,
+    alpha: bool,
+}
+
+impl SortOption {
+    pub fn by<P: Into<Vec<u8>>>(self, pattern: P) -> Self {
+        SortOption {
+            by: Some(pattern.into()),
+            ..self
+        }
+    }
+
+    pub fn limit(self, offset: u64, count: u64) -> Self {
+        SortOption {
+            limit: Some((offset, count)),
+            ..self
+        }
+    }
+
+    pub fn get<P: Into<Vec<u8>>>(mut self, pattern: P) -> Self {
+        self.get.push(pattern.into());
+        self
+    }
+
+    pub fn asc(self) -> Self {
+        SortOption {
+            desc: false,
+            ..self
+        }
+    }
+
+    pub fn desc(self) -> Self {
+        SortOption { desc: true, ..self }
+    }
+
+    pub fn alpha(self) -> Self {
+        SortOption {
+            alpha: true,
+            ..self
+        }
+    }
+
+    pub(crate) fn build(self, key: Vec<u8>, store: Option<Vec<u8>>) -> Sort {
+        Sort {
+            key,
+            option: self,
+            store,
+        }
+    }
+
+    pub(crate) fn build_ro(self, key: Vec<u8>) -> SortRo {
+        SortRo { key, option: self }
+    }
+
+    fn push<'a>(&'a self, args: &mut Vec<&'a [u8]>, limit: &'a Option<(String, String)>) {
+        if let Some(pattern) = &self.by {
+            args.push("BY".as_bytes());
+            args.push(pattern.as_slice());
+        }
+
+        if let Some((offset, count)) = limit {
+            args.push("LIMIT".as_bytes());
+            args.push(offset.as_bytes());
+            args.push(count.as_bytes());
+        }
+
+        for pattern in &self.get {
+            args.push("GET".as_bytes());
+            args.push(pattern.as_slice());
+        }
+
+        if self.desc {
+            args.push("DESC".as_bytes());
+        }
+
+        if self.alpha {
+            args.push("ALPHA".as_bytes());
+        }
+    }
+}
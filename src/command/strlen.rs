@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::{args_to_bytes, Command};
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
 
 pub struct Strlen {
     key: Vec<u8>,
@@ -25,7 +27,15 @@ impl Strlen {
 }
 
 impl Command for Strlen {
-    fn as_bytes(&self) -> Vec<u8> {
-        args_to_bytes(vec!["STRLEN".as_bytes(), self.key.as_slice()])
+    fn name(&self) -> &'static str {
+        "STRLEN"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["STRLEN".as_bytes(), self.key.as_slice()])
+    }
+
+    fn read_only(&self) -> bool {
+        true
     }
 }
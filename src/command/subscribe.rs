@@ -0,0 +1,61 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+pub struct Subscribe {
+    channels: Vec<Vec<u8>>,
+}
+
+impl Subscribe {
+    pub fn new(channels: Vec<Vec<u8>>) -> Self {
+        Subscribe { channels }
+    }
+}
+
+impl Command for Subscribe {
+    fn name(&self) -> &'static str {
+        "SUBSCRIBE"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec!["SUBSCRIBE".as_bytes()];
+        args.extend(self.channels.iter().map(|channel| channel.as_slice()));
+        encode_args(out, &args)
+    }
+}
+
+pub struct PSubscribe {
+    patterns: Vec<Vec<u8>>,
+}
+
+impl PSubscribe {
+    pub fn new(patterns: Vec<Vec<u8>>) -> Self {
+        PSubscribe { patterns }
+    }
+}
+
+impl Command for PSubscribe {
+    fn name(&self) -> &'static str {
+        "PSUBSCRIBE"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec!["PSUBSCRIBE".as_bytes()];
+        args.extend(self.patterns.iter().map(|pattern| pattern.as_slice()));
+        encode_args(out, &args)
+    }
+}
@@ -0,0 +1,45 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+pub struct Touch {
+    keys: Vec<Vec<u8>>,
+}
+
+impl Touch {
+    pub fn new(keys: Vec<Vec<u8>>) -> Self {
+        Touch { keys }
+    }
+}
+
+impl Command for Touch {
+    fn name(&self) -> &'static str {
+        "TOUCH"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        let mut args = vec!["TOUCH".as_bytes()];
+        for key in &self.keys {
+            args.push(key.as_slice());
+        }
+        encode_args(out, &args)
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+}
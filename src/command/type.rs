@@ -0,0 +1,83 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+
+use crate::command::{encode_args, Command};
+
+pub struct Type {
+    key: Vec<u8>,
+}
+
+impl Type {
+    pub fn new(key: Vec<u8>) -> Self {
+        Type { key }
+    }
+}
+
+impl Command for Type {
+    fn name(&self) -> &'static str {
+        "TYPE"
+    }
+
+    fn encode(&self, out: &mut BytesMut) {
+        encode_args(out, &["TYPE".as_bytes(), self.key.as_slice()])
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeyType {
+    None,
+    String,
+    List,
+    Set,
+    ZSet,
+    Hash,
+    Stream,
+    Other(String),
+}
+
+impl From<String> for KeyType {
+    fn from(t: String) -> Self {
+        match t.as_str() {
+            "none" => KeyType::None,
+            "string" => KeyType::String,
+            "list" => KeyType::List,
+            "set" => KeyType::Set,
+            "zset" => KeyType::ZSet,
+            "hash" => KeyType::Hash,
+            "stream" => KeyType::Stream,
+            _ => KeyType::Other(t),
+        }
+    }
+}
+
+impl KeyType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            KeyType::None => "none",
+            KeyType::String => "string",
+            KeyType::List => "list",
+            KeyType::Set => "set",
+            KeyType::ZSet => "zset",
+            KeyType::Hash => "hash",
+            KeyType::Stream => "stream",
+            KeyType::Other(t) => t.as_str(),
+        }
+    }
+}
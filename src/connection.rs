@@ -12,43 +12,243 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::io::Cursor;
+use std::{
+    collections::VecDeque,
+    io::{self, IoSlice},
+    net::SocketAddr,
+    sync::Arc,
+    time::Instant,
+};
+
+use bytes::{Buf, Bytes, BytesMut};
+use futures::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    FutureExt,
+};
 
-use bytes::{Buf, BytesMut};
-use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt, BufWriter},
-    net::TcpStream,
+use crate::{
+    command::Command,
+    model::{Limits, Model, Scanner},
+    observer::{ClientObserver, Outcome, ServerErrorKind},
+    runtime::Transport,
+    Error, Result,
+};
+#[cfg(feature = "tracing")]
+use crate::{
+    trace::{error_kind, Tracer},
+    TraceKeys,
 };
 
-use crate::{command::Command, error::ParseError, model, model::Model, Error, Result};
+/// How much to read at once, and so how much spare room the buffer keeps.
+const READ_SIZE: usize = 64 * 1024;
 
 pub struct Connection {
-    stream: BufWriter<TcpStream>,
+    stream: Box<dyn Transport>,
     buffer: BytesMut,
+    scanner: Scanner,
+    output: BytesMut,
+    parsed: VecDeque<Model>,
+    /// The bytes of every parsed frame, alongside it, if kept.
+    frames: Option<VecDeque<Bytes>>,
+    pushes: Vec<Vec<Model>>,
+    limits: Limits,
+    /// Set once a command may have gone out in part, after which the stream
+    /// no longer frames and every call fails.
+    broken: bool,
+    observer: Option<Arc<dyn ClientObserver>>,
+    /// The name and start of every command awaiting its reply, if observed.
+    in_flight: VecDeque<(&'static str, Instant)>,
+    #[cfg(feature = "tracing")]
+    tracer: Tracer,
+}
+
+/// What [`Connection::recv_to`] got: either a bulk string streamed into the
+/// writer, of the given length, or any other reply.
+pub enum Streamed {
+    Written(u64),
+    Reply(Option<Model>),
 }
 
 impl Connection {
-    pub fn new(socket: TcpStream) -> Self {
+    pub fn new<T: Transport + 'static>(stream: T) -> Self {
         Self {
-            stream: BufWriter::new(socket),
-            buffer: BytesMut::with_capacity(4 * 1024),
+            stream: Box::new(stream),
+            buffer: BytesMut::with_capacity(READ_SIZE),
+            scanner: Scanner::default(),
+            output: BytesMut::with_capacity(4 * 1024),
+            parsed: VecDeque::new(),
+            frames: None,
+            pushes: vec![],
+            limits: Limits::default(),
+            broken: false,
+            observer: None,
+            in_flight: VecDeque::new(),
+            #[cfg(feature = "tracing")]
+            tracer: Tracer::new(),
+        }
+    }
+
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    pub fn set_observer(&mut self, observer: Arc<dyn ClientObserver>) {
+        self.observer = Some(observer);
+    }
+
+    /// Notes the server address, which command spans record with the
+    /// `tracing` feature.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub fn connected(&mut self, peer: SocketAddr) {
+        #[cfg(feature = "tracing")]
+        self.tracer.connected(peer.to_string());
+    }
+
+    #[cfg(feature = "tracing")]
+    pub fn set_trace_keys(&mut self, keys: TraceKeys) {
+        self.tracer.set_keys(keys);
+    }
+
+    /// Sends the command along with any written before. Large arguments are
+    /// not copied into the output buffer but written in place.
+    pub async fn send(&mut self, cmd: &impl Command) -> Result<()> {
+        self.usable()?;
+        let start = self.output.len();
+        let large = cmd.encode_vectored(&mut self.output);
+        self.started(cmd.name(), start);
+        if large.is_empty() {
+            return self.flush().await;
+        }
+        let written = {
+            let mut slices = Vec::with_capacity(2 * large.len() + 1);
+            let mut start = 0;
+            for (at, arg) in large {
+                slices.push(IoSlice::new(&self.output[start..at]));
+                slices.push(IoSlice::new(arg));
+                start = at;
+            }
+            slices.push(IoSlice::new(&self.output[start..]));
+            write_all_vectored(&mut self.stream, &mut slices).await
+        };
+        let result = match written {
+            Ok(written) => {
+                self.sent(written);
+                self.stream.flush().await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            return Err(self.break_off(e.into()));
+        }
+        self.output.clear();
+        Ok(())
+    }
+
+    /// Sends a frame, for serving rather than issuing commands.
+    #[cfg(feature = "testing")]
+    pub async fn send_model(&mut self, model: &Model) -> Result<()> {
+        model.encode(&mut self.output);
+        self.flush().await
+    }
+
+    /// Sends bytes as they are, such as a frame relayed from another
+    /// connection.
+    #[cfg(feature = "proxy")]
+    pub async fn send_raw(&mut self, frame: &[u8]) -> Result<()> {
+        self.output.extend_from_slice(frame);
+        self.flush().await
+    }
+
+    /// Buffers the command to be sent with the next [`Connection::send`] or
+    /// [`Connection::flush`], so that a pipeline goes out in one write.
+    pub fn write(&mut self, cmd: &impl Command) {
+        let start = self.output.len();
+        cmd.encode(&mut self.output);
+        self.started(cmd.name(), start);
+    }
+
+    pub async fn flush(&mut self) -> Result<()> {
+        self.usable()?;
+        let result = match self.stream.write_all(&self.output).await {
+            Ok(()) => {
+                self.sent(self.output.len());
+                self.stream.flush().await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            return Err(self.break_off(e.into()));
+        }
+        self.output.clear();
+        Ok(())
+    }
+
+    /// Sends a command one argument of which is `len` bytes copied from
+    /// `reader` as they are written. `encode` returns the offset it belongs at.
+    /// Should anything fail, the connection is left broken, since part of the
+    /// command may have gone out.
+    pub async fn send_from<R>(
+        &mut self,
+        name: &'static str,
+        encode: impl FnOnce(&mut BytesMut) -> usize,
+        reader: &mut R,
+        len: u64,
+    ) -> Result<()>
+    where
+        R: AsyncRead + Unpin + ?Sized,
+    {
+        self.usable()?;
+        let start = self.output.len();
+        let at = encode(&mut self.output);
+        self.started(name, start);
+        match self.write_from(at, reader, len).await {
+            Ok(()) => {
+                self.output.clear();
+                Ok(())
+            }
+            Err(e) => Err(self.break_off(e)),
         }
     }
 
-    pub async fn send(&mut self, cmd: impl Command) -> Result<()> {
-        let req = cmd.as_bytes();
-        self.stream.write_all(req.as_slice()).await?;
+    async fn write_from<R>(&mut self, at: usize, reader: &mut R, len: u64) -> Result<()>
+    where
+        R: AsyncRead + Unpin + ?Sized,
+    {
+        self.stream.write_all(&self.output[..at]).await?;
+        let copied = futures::io::copy(reader.take(len), &mut self.stream).await?;
+        if copied < len {
+            return Err(Error::Internal(format!(
+                "source ended after {} of {} bytes",
+                copied, len
+            )));
+        }
+        self.stream.write_all(&self.output[at..]).await?;
+        self.sent(self.output.len() + copied as usize);
         self.stream.flush().await?;
         Ok(())
     }
 
+    /// Receives the next reply. RESP3 push frames are out of band and set
+    /// aside for [`Connection::take_pushes`].
     pub async fn recv(&mut self) -> Result<Option<Model>> {
+        let result = loop {
+            match self.recv_frame().await {
+                Ok(Some(Model::Push(push))) => self.pushes.push(push),
+                result => break result,
+            }
+        };
+        self.replied(result.as_ref().map(Option::as_ref));
+        result
+    }
+
+    /// Receives the next frame, push frames included. Cancel safe.
+    pub async fn recv_frame(&mut self) -> Result<Option<Model>> {
+        self.usable()?;
         loop {
             if let Some(model) = self.parse()? {
                 break Ok(Some(model));
             }
-
-            if 0 == self.stream.read_buf(&mut self.buffer).await? {
+            if 0 == self.fill().await? {
                 break if self.buffer.is_empty() {
                     Ok(None)
                 } else {
@@ -58,18 +258,325 @@ impl Connection {
         }
     }
 
+    /// Keeps the bytes of the frames parsed from now on, for
+    /// [`Connection::recv_raw_frame`].
+    #[cfg(feature = "proxy")]
+    pub fn keep_frames(&mut self) {
+        self.frames.get_or_insert_with(VecDeque::new);
+    }
+
+    /// Like [`Connection::recv_frame`], but also returns the frame as it was
+    /// received, which re-encoding the model may not reproduce.
+    #[cfg(feature = "proxy")]
+    pub async fn recv_raw_frame(&mut self) -> Result<Option<(Model, Bytes)>> {
+        let model = self.recv_frame().await?;
+        Ok(model.map(|model| {
+            let frame = self.frames.as_mut().and_then(VecDeque::pop_front);
+            (model, frame.unwrap_or_default())
+        }))
+    }
+
+    /// Like [`Connection::recv`], but a bulk string reply is streamed into
+    /// `writer` chunk by chunk instead of being buffered whole. Should writing
+    /// fail, the rest of the reply is still consumed before the error returns.
+    pub async fn recv_to<W>(&mut self, writer: &mut W) -> Result<Streamed>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let result = self.recv_streamed(writer).await;
+        let written = Model::String(Default::default());
+        self.replied(result.as_ref().map(|streamed| match streamed {
+            Streamed::Written(_) => Some(&written),
+            Streamed::Reply(model) => model.as_ref(),
+        }));
+        result
+    }
+
+    async fn recv_streamed<W>(&mut self, writer: &mut W) -> Result<Streamed>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        self.usable()?;
+        loop {
+            if self.parsed.is_empty() && self.buffer.first() == Some(&b'$') {
+                if let Some(end) = self.buffer.windows(2).position(|w| w == b"\r\n") {
+                    let len = atoi::atoi::<i64>(&self.buffer[1..end])
+                        .ok_or_else(|| Error::Internal("malformed bulk length".to_string()))?;
+                    if len > 0 && len as u64 > self.limits.max_bulk_len as u64 {
+                        return Err(self.protocol_error(Error::Protocol(format!(
+                            "bulk length {} exceeds {}",
+                            len, self.limits.max_bulk_len
+                        ))));
+                    }
+                    self.buffer.advance(end + 2);
+                    self.scanner = Scanner::default();
+                    return match u64::try_from(len) {
+                        Ok(len) => self.stream_bulk(len, writer).await.map(Streamed::Written),
+                        Err(_) => Ok(Streamed::Reply(Some(Model::Nil))),
+                    };
+                }
+            } else {
+                match self.parse()? {
+                    Some(Model::Push(push)) => {
+                        self.pushes.push(push);
+                        continue;
+                    }
+                    // parsed along with an earlier frame, so written whole
+                    Some(Model::String(value)) => {
+                        writer.write_all(&value).await?;
+                        writer.flush().await?;
+                        return Ok(Streamed::Written(value.len() as u64));
+                    }
+                    Some(model) => return Ok(Streamed::Reply(Some(model))),
+                    None => {}
+                }
+            }
+            if 0 == self.fill().await? {
+                return if self.buffer.is_empty() {
+                    Ok(Streamed::Reply(None))
+                } else {
+                    Err(Error::Internal("connection reset by peer".to_string()))
+                };
+            }
+        }
+    }
+
+    async fn stream_bulk<W>(&mut self, len: u64, writer: &mut W) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let mut failure = None;
+        let mut remaining = len;
+        let mut chunk = vec![0; 64 * 1024];
+        loop {
+            let take = remaining.min(self.buffer.len() as u64) as usize;
+            if failure.is_none() {
+                failure = writer.write_all(&self.buffer[..take]).await.err();
+            }
+            self.buffer.advance(take);
+            remaining -= take as u64;
+            if remaining == 0 {
+                break;
+            }
+            match self.stream.read(&mut chunk).await? {
+                0 => return Err(Error::Internal("connection reset by peer".to_string())),
+                n => {
+                    self.received(n);
+                    self.buffer.extend_from_slice(&chunk[..n]);
+                }
+            }
+        }
+        while self.buffer.len() < 2 {
+            if 0 == self.fill().await? {
+                return Err(Error::Internal("connection reset by peer".to_string()));
+            }
+        }
+        if &self.buffer[..2] != b"\r\n" {
+            return Err(Error::Internal("bulk string not terminated".to_string()));
+        }
+        self.buffer.advance(2);
+        match failure {
+            None => writer.flush().await?,
+            Some(e) => return Err(e.into()),
+        }
+        Ok(len)
+    }
+
+    /// Returns a frame, push frames included, only if it can be read without
+    /// waiting, so that messages arriving while no command is in flight are
+    /// picked up too.
+    pub fn try_recv(&mut self) -> Result<Option<Model>> {
+        self.usable()?;
+        loop {
+            if let Some(model) = self.parse()? {
+                return Ok(Some(model));
+            }
+            match self.fill().now_or_never() {
+                None => return Ok(None),
+                Some(Ok(0)) => return Err(Error::Internal("connection closed".to_string())),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e),
+            }
+        }
+    }
+
+    /// Reads into the spare room of the buffer. Cancel safe: nothing is
+    /// buffered unless the read completes.
+    async fn fill(&mut self) -> Result<usize> {
+        let mut spare = Spare::new(&mut self.buffer, READ_SIZE);
+        let len = self.stream.read(spare.as_mut()).await?;
+        spare.commit(len);
+        drop(spare);
+        self.received(len);
+        if self.buffer.len() > self.limits.max_buffered {
+            return Err(self.protocol_error(Error::Protocol(format!(
+                "reply exceeds {} buffered bytes",
+                self.limits.max_buffered
+            ))));
+        }
+        Ok(len)
+    }
+
+    pub fn take_pushes(&mut self) -> Vec<Vec<Model>> {
+        std::mem::take(&mut self.pushes)
+    }
+
+    /// Parses every complete frame buffered so far. Frames are only parsed
+    /// once the scanner has seen them complete, and their payloads share the
+    /// bytes split off the buffer.
     pub fn parse(&mut self) -> Result<Option<Model>> {
-        let mut cursor = Cursor::new(&self.buffer[..]);
-        match model::check(&mut cursor) {
-            Ok(()) => {
-                let len = cursor.position() as usize;
-                cursor.set_position(0);
-                let model = model::parse(&mut cursor)?;
-                self.buffer.advance(len);
-                Ok(Some(model))
+        if self.parsed.is_empty() && !self.buffer.is_empty() {
+            let end = self
+                .scanner
+                .scan(&self.buffer, &self.limits)
+                .map_err(|e| self.protocol_error(e))?;
+            if end > 0 {
+                let bytes = self.buffer.split_to(end).freeze();
+                self.scanner.consume(end);
+                let mut pos = 0;
+                while pos < end {
+                    match Model::parse_with_limits(&bytes, pos, &self.limits)
+                        .map_err(|e| self.protocol_error(e))?
+                    {
+                        Some((model, next)) => {
+                            if let Some(frames) = self.frames.as_mut() {
+                                frames.push_back(bytes.slice(pos..next));
+                            }
+                            self.parsed.push_back(model);
+                            pos = next;
+                        }
+                        None => return Err(Error::Internal("incomplete frame".to_string())),
+                    }
+                }
+            }
+        }
+        Ok(self.parsed.pop_front())
+    }
+
+    /// Notes a command, encoded in the output buffer from `start`, as in
+    /// flight until its reply.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn started(&mut self, name: &'static str, start: usize) {
+        #[cfg(feature = "tracing")]
+        self.tracer.start(name, &self.output[start..]);
+        if let Some(observer) = self.observer.as_ref() {
+            observer.on_command_start(name);
+            self.in_flight.push_back((name, Instant::now()));
+        }
+    }
+
+    /// Ends the oldest command in flight with its reply or, failing that,
+    /// every command in flight, since their replies are lost.
+    fn replied(&mut self, reply: std::result::Result<Option<&Model>, &Error>) {
+        #[cfg(feature = "tracing")]
+        match reply {
+            Ok(Some(model)) => self.tracer.finish(model),
+            Ok(None) => self.tracer.fail("closed"),
+            Err(e) => self.tracer.fail(error_kind(e)),
+        }
+        let observer = match self.observer.as_ref() {
+            Some(observer) => observer,
+            None => return,
+        };
+        match reply {
+            Ok(Some(model)) => {
+                let outcome = match model {
+                    Model::Error(e) => Outcome::ServerError(ServerErrorKind::of(e)),
+                    _ => Outcome::Ok,
+                };
+                if let Some((name, start)) = self.in_flight.pop_front() {
+                    observer.on_command_end(name, start.elapsed(), &outcome);
+                }
+            }
+            _ => {
+                for (name, start) in self.in_flight.drain(..) {
+                    observer.on_command_end(name, start.elapsed(), &Outcome::Failed);
+                }
+            }
+        }
+    }
+
+    fn sent(&self, len: usize) {
+        if let Some(observer) = self.observer.as_ref() {
+            observer.on_bytes_sent(len);
+        }
+    }
+
+    fn received(&self, len: usize) {
+        if let Some(observer) = self.observer.as_ref() {
+            observer.on_bytes_received(len);
+        }
+    }
+
+    fn usable(&self) -> Result<()> {
+        if self.broken {
+            return Err(Error::Internal(
+                "connection broken by an earlier failed send".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Leaves the connection broken after a failed send and fails the
+    /// commands in flight, whose replies would never line up.
+    fn break_off(&mut self, e: Error) -> Error {
+        self.broken = true;
+        self.output.clear();
+        self.replied(Err(&e));
+        e
+    }
+
+    /// Reports a protocol error, with the `tracing` feature, on its way out.
+    fn protocol_error(&self, e: Error) -> Error {
+        #[cfg(feature = "tracing")]
+        self.tracer.protocol_error(&e);
+        e
+    }
+}
+
+async fn write_all_vectored(
+    stream: &mut Box<dyn Transport>,
+    mut slices: &mut [IoSlice<'_>],
+) -> io::Result<usize> {
+    let mut written = 0;
+    while !slices.is_empty() {
+        match stream.write_vectored(slices).await? {
+            0 => return Err(io::Error::from(io::ErrorKind::WriteZero)),
+            n => {
+                IoSlice::advance_slices(&mut slices, n);
+                written += n;
             }
-            Err(ParseError::EndOfStream) => Ok(None),
-            Err(error) => Err(error.into()),
         }
     }
+    Ok(written)
+}
+
+/// Room past the end of a buffer to read into, given back unless committed.
+struct Spare<'a> {
+    buffer: &'a mut BytesMut,
+    len: usize,
+}
+
+impl<'a> Spare<'a> {
+    fn new(buffer: &'a mut BytesMut, size: usize) -> Self {
+        let len = buffer.len();
+        buffer.resize(len + size, 0);
+        Spare { buffer, len }
+    }
+
+    fn commit(&mut self, n: usize) {
+        self.len += n;
+    }
+}
+
+impl AsMut<[u8]> for Spare<'_> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer[self.len..]
+    }
+}
+
+impl Drop for Spare<'_> {
+    fn drop(&mut self) {
+        self.buffer.truncate(self.len);
+    }
 }
@@ -22,6 +22,8 @@ pub enum ParseError {
     EndOfStream,
     #[error("OtherParseError({0})")]
     Other(String),
+    #[error("ProtocolError({0})")]
+    Protocol(String),
 }
 
 #[derive(Debug, Error)]
@@ -32,6 +34,11 @@ pub enum Error {
     Internal(String),
     #[error("ServerError({0})")]
     Server(String),
+    #[error("ProtocolError({0})")]
+    Protocol(String),
+    /// An argument the caller passed is out of range, caught before sending.
+    #[error("InvalidArgument({0})")]
+    InvalidArgument(String),
 }
 
 impl From<std::string::FromUtf8Error> for ParseError {
@@ -51,6 +58,7 @@ impl<E: Into<ParseError>> From<E> for Error {
         match e.into() {
             ParseError::EndOfStream => unreachable!("EndOfStream should be handled internally."),
             ParseError::Other(reason) => Self::Internal(reason),
+            ParseError::Protocol(reason) => Self::Protocol(reason),
         }
     }
 }
@@ -0,0 +1,173 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures::{stream, Stream};
+
+use crate::{
+    client::match_failure,
+    command::{ConfigSet, PSubscribe},
+    runtime, Error, Model, Result,
+};
+
+/// Which of the two notification channels to listen on.
+pub enum EventChannel {
+    /// `__keyspace@<db>__:<key>`, whose messages are event names.
+    Keyspace,
+    /// `__keyevent@<db>__:<event>`, whose messages are key names.
+    Keyevent,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum KeyspaceEvent {
+    Set(Vec<u8>),
+    Del(Vec<u8>),
+    Expired(Vec<u8>),
+    Other { event: String, key: Vec<u8> },
+}
+
+impl KeyspaceEvent {
+    fn new(event: &[u8], key: Vec<u8>) -> Result<Self> {
+        Ok(match event {
+            b"set" => KeyspaceEvent::Set(key),
+            b"del" => KeyspaceEvent::Del(key),
+            b"expired" => KeyspaceEvent::Expired(key),
+            event => KeyspaceEvent::Other {
+                event: String::from_utf8(event.to_vec())?,
+                key,
+            },
+        })
+    }
+
+    pub fn key(&self) -> &[u8] {
+        match self {
+            KeyspaceEvent::Set(key)
+            | KeyspaceEvent::Del(key)
+            | KeyspaceEvent::Expired(key)
+            | KeyspaceEvent::Other { key, .. } => key,
+        }
+    }
+}
+
+/// Subscribes to keyspace notifications of one database.
+///
+/// Servers publish nothing until `notify-keyspace-events` is configured, which
+/// [`KeyspaceEvents::notify`] does on connect; its flags must include `K` or
+/// `E` to match the chosen [`EventChannel`].
+pub struct KeyspaceEvents {
+    addr: String,
+    db: u64,
+    channel: EventChannel,
+    pattern: Vec<u8>,
+    notify: Option<String>,
+}
+
+impl KeyspaceEvents {
+    pub fn new<A: Into<String>>(addr: A) -> Self {
+        KeyspaceEvents {
+            addr: addr.into(),
+            db: 0,
+            channel: EventChannel::Keyevent,
+            pattern: b"*".to_vec(),
+            notify: None,
+        }
+    }
+
+    pub fn db(self, db: u64) -> Self {
+        KeyspaceEvents { db, ..self }
+    }
+
+    pub fn channel(self, channel: EventChannel) -> Self {
+        KeyspaceEvents { channel, ..self }
+    }
+
+    /// Narrows the subscription to keys, or event names on
+    /// [`EventChannel::Keyevent`], matching a glob-style pattern.
+    pub fn pattern<P: Into<Vec<u8>>>(self, pattern: P) -> Self {
+        KeyspaceEvents {
+            pattern: pattern.into(),
+            ..self
+        }
+    }
+
+    /// Sets `notify-keyspace-events` to `flags`, such as `"Ex"` or `"KEA"`.
+    pub fn notify<F: Into<String>>(self, flags: F) -> Self {
+        KeyspaceEvents {
+            notify: Some(flags.into()),
+            ..self
+        }
+    }
+
+    pub async fn subscribe(self) -> Result<impl Stream<Item = Result<KeyspaceEvent>>> {
+        let mut connection = runtime::connect(self.addr.as_str()).await?;
+        if let Some(flags) = self.notify {
+            let parameters = vec![(b"notify-keyspace-events".to_vec(), flags.into_bytes())];
+            connection.send(&ConfigSet::new(parameters)).await?;
+            match connection.recv().await? {
+                Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => {}
+                model => return match_failure(model),
+            }
+        }
+
+        let prefix = match self.channel {
+            EventChannel::Keyspace => format!("__keyspace@{}__:", self.db),
+            EventChannel::Keyevent => format!("__keyevent@{}__:", self.db),
+        };
+        let mut pattern = prefix.clone().into_bytes();
+        pattern.extend(self.pattern);
+        connection.send(&PSubscribe::new(vec![pattern])).await?;
+        match connection.recv().await? {
+            Some(Model::Array(_)) => {}
+            model => return match_failure(model),
+        }
+
+        let keyspace = matches!(self.channel, EventChannel::Keyspace);
+        // the connection is dropped after an error, which ends the stream
+        Ok(stream::unfold(Some(connection), move |connection| {
+            let prefix = prefix.clone();
+            async move {
+                let mut connection = connection?;
+                match connection.recv().await {
+                    Ok(None) => None,
+                    Ok(Some(model)) => {
+                        let event = to_event(model, prefix.as_bytes(), keyspace);
+                        Some((event, Some(connection)))
+                    }
+                    Err(e) => Some((Err(e), None)),
+                }
+            }
+        }))
+    }
+}
+
+/// Reads a `[pmessage, pattern, channel, payload]` message.
+fn to_event(model: Model, prefix: &[u8], keyspace: bool) -> Result<KeyspaceEvent> {
+    if let Model::Array(models) = model {
+        if let Ok([_, _, Model::String(channel), Model::String(payload)]) =
+            <[Model; 4]>::try_from(models)
+        {
+            let name = channel
+                .strip_prefix(prefix)
+                .ok_or_else(|| Error::Internal("unexpected channel".to_string()))?;
+            return if keyspace {
+                KeyspaceEvent::new(&payload, name.to_vec())
+            } else {
+                KeyspaceEvent::new(name, payload.into())
+            };
+        }
+        return Err(Error::Internal(
+            "malformed keyspace notification".to_string(),
+        ));
+    }
+    match_failure(Some(model))
+}
@@ -12,15 +12,34 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
 mod client;
 pub mod command;
 mod connection;
 mod error;
+mod keyspace;
 mod model;
+pub mod observer;
+#[cfg(feature = "proxy")]
+pub mod proxy;
+mod replicated;
+mod runtime;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "tracing")]
+mod trace;
+mod tracking;
 
 pub use client::Client;
 pub use error::Error;
-pub use model::Model;
+pub use keyspace::{EventChannel, KeyspaceEvent, KeyspaceEvents};
+pub use model::{Limits, Model};
+pub use replicated::{ReadFrom, ReplicatedClient};
+pub use runtime::{ToSocketAddrs, Transport};
+#[cfg(feature = "tracing")]
+pub use trace::TraceKeys;
+pub use tracking::{CachingClient, Protocol};
 
 use crate::connection::Connection;
 
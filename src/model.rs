@@ -12,12 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{cmp::Ordering, io::Cursor};
+use std::cmp::Ordering;
 
-use bytes::Buf;
+use bytes::{BufMut, Bytes, BytesMut};
 use num::BigInt;
 
-use crate::error::{ParseError, ParseResult};
+use crate::{
+    error::{ParseError, ParseResult},
+    Result,
+};
 
 #[derive(Debug)]
 pub enum Model {
@@ -26,84 +29,372 @@ pub enum Model {
     Integer(i64),
     Double(f64),
     Nil,
-    String(Vec<u8>),
+    String(Bytes),
     Array(Vec<Model>),
     Map(Vec<(Model, Model)>),
     Set(Vec<Model>),
     Bool(bool),
-    Verb(Vec<u8>, Vec<u8>),
+    Verb(Bytes, Bytes),
     Push(Vec<Model>),
     BigNum(num::BigInt),
 }
 
-pub fn check(cursor: &mut Cursor<&[u8]>) -> ParseResult<()> {
-    if !cursor.has_remaining() {
-        return Err(ParseError::EndOfStream);
+/// Bounds on what a server may send, so that a hostile or buggy one cannot
+/// exhaust memory or the stack. Exceeding any fails with a protocol error.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub(crate) max_bulk_len: usize,
+    pub(crate) max_aggregate_len: usize,
+    pub(crate) max_depth: usize,
+    pub(crate) max_buffered: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_bulk_len: 512 * 1024 * 1024,
+            max_aggregate_len: u32::MAX as usize,
+            max_depth: 64,
+            max_buffered: 1024 * 1024 * 1024,
+        }
+    }
+}
+
+impl Limits {
+    /// The longest bulk or verbatim string, 512 MiB by default as in Redis.
+    pub fn max_bulk_len(self, max_bulk_len: usize) -> Self {
+        Limits {
+            max_bulk_len,
+            ..self
+        }
+    }
+
+    /// The most elements of an array, set, push or map.
+    pub fn max_aggregate_len(self, max_aggregate_len: usize) -> Self {
+        Limits {
+            max_aggregate_len,
+            ..self
+        }
     }
 
-    match cursor.get_u8() {
-        b'-' | b'+' | b':' | b',' | b'(' | b'_' | b'#' => {
-            readline(cursor)?;
+    /// How deep aggregates may nest, 64 by default.
+    pub fn max_depth(self, max_depth: usize) -> Self {
+        Limits { max_depth, ..self }
+    }
+
+    /// The most bytes buffered while waiting for a reply to complete, 1 GiB
+    /// by default. Values streamed into a writer do not count.
+    pub fn max_buffered(self, max_buffered: usize) -> Self {
+        Limits {
+            max_buffered,
+            ..self
+        }
+    }
+}
+
+impl Model {
+    /// Parses the frame starting at `buf[pos..]` and returns it along with the
+    /// position right after it, or `None` if the frame is not complete yet.
+    /// String payloads are slices of `buf` rather than copies.
+    pub fn parse(buf: &Bytes, pos: usize) -> Result<Option<(Model, usize)>> {
+        Model::parse_with_limits(buf, pos, &Limits::default())
+    }
+
+    /// Like [`Model::parse`], but within the given limits.
+    pub fn parse_with_limits(
+        buf: &Bytes,
+        pos: usize,
+        limits: &Limits,
+    ) -> Result<Option<(Model, usize)>> {
+        let mut reader = Reader {
+            buf,
+            pos,
+            limits,
+            depth: 0,
+        };
+        match parse(&mut reader) {
+            Ok(model) => Ok(Some((model, reader.pos))),
+            Err(ParseError::EndOfStream) => Ok(None),
+            Err(e) => Err(e.into()),
         }
-        b'$' | b'=' => {
-            let len = readline(cursor)?;
-            let len = parse_int::<i64>(len)?;
-            if len >= 0 {
-                readline(cursor)?;
+    }
+
+    /// Appends the RESP encoding of the model to `out`, so that parsing it
+    /// back yields the same model. `Nil` is written the RESP2 way, which RESP3
+    /// readers accept too.
+    pub fn encode(&self, out: &mut BytesMut) {
+        match self {
+            Model::Error(e) => line(out, b'-', e.as_bytes()),
+            Model::Status(status) => line(out, b'+', status.as_bytes()),
+            Model::Integer(i) => line(out, b':', itoa::Buffer::new().format(*i).as_bytes()),
+            Model::Double(d) if d.is_nan() => line(out, b',', b"nan"),
+            Model::Double(d) => line(out, b',', d.to_string().as_bytes()),
+            Model::Nil => out.put_slice(b"$-1\r\n"),
+            Model::String(s) => bulk(out, b'$', &[s]),
+            Model::Verb(format, text) => bulk(out, b'=', &[format, b":", text]),
+            Model::Bool(b) => line(out, b'#', if *b { b"t" } else { b"f" }),
+            Model::BigNum(i) => line(out, b'(', i.to_string().as_bytes()),
+            Model::Array(models) => aggregate(out, b'*', models),
+            Model::Set(models) => aggregate(out, b'~', models),
+            Model::Push(models) => aggregate(out, b'>', models),
+            Model::Map(pairs) => {
+                header(out, b'%', pairs.len());
+                for (k, v) in pairs {
+                    k.encode(out);
+                    v.encode(out);
+                }
             }
         }
-        b'~' | b'*' | b'>' => {
-            let len = readline(cursor)?;
-            let len = parse_int::<i64>(len)?;
-            for _ in 0..len {
-                check(cursor)?;
+    }
+}
+
+/// Finds where frames end without building them, resuming where the last call
+/// stopped, so that a reply arriving over many reads is scanned only once.
+#[derive(Default)]
+pub(crate) struct Scanner {
+    /// Where the next header starts, relative to the buffer.
+    pos: usize,
+    /// Where to resume looking for the end of a header line not complete yet.
+    searched: usize,
+    /// How many elements each aggregate being scanned still lacks, innermost
+    /// last.
+    remaining: Vec<u64>,
+}
+
+impl Scanner {
+    /// Scans `buf` past the frames scanned before and returns where the last
+    /// complete frame ends, or `0` if none has completed. Applies the same
+    /// limits as [`Model::parse_with_limits`] to headers as they arrive.
+    pub(crate) fn scan(&mut self, buf: &[u8], limits: &Limits) -> Result<usize> {
+        let mut complete = 0;
+        loop {
+            let start = self.pos;
+            let from = self.searched.max(start);
+            let end = match buf[from..].windows(2).position(|w| w == b"\r\n") {
+                Some(n) => from + n,
+                None => {
+                    self.searched = buf.len().saturating_sub(1).max(start);
+                    return Ok(complete);
+                }
+            };
+            self.searched = 0;
+            let mut next = end + 2;
+            match buf[start] {
+                b'$' | b'=' => {
+                    let len = parse_int::<i64>(&buf[start + 1..end])?;
+                    if len > 0 && len as u64 > limits.max_bulk_len as u64 {
+                        return Err(ParseError::Protocol(format!(
+                            "bulk length {} exceeds {}",
+                            len, limits.max_bulk_len
+                        ))
+                        .into());
+                    }
+                    if len >= 0 {
+                        next = next.saturating_add(len as usize + 2);
+                        if buf.len() < next {
+                            return Ok(complete);
+                        }
+                    }
+                }
+                t @ (b'*' | b'~' | b'>' | b'%') => {
+                    let len = parse_int::<i64>(&buf[start + 1..end])?;
+                    if len > 0 && len as u64 > limits.max_aggregate_len as u64 {
+                        return Err(ParseError::Protocol(format!(
+                            "aggregate length {} exceeds {}",
+                            len, limits.max_aggregate_len
+                        ))
+                        .into());
+                    }
+                    if len > 0 {
+                        if self.remaining.len() >= limits.max_depth {
+                            return Err(ParseError::Protocol(format!(
+                                "nesting exceeds depth {}",
+                                limits.max_depth
+                            ))
+                            .into());
+                        }
+                        let len = len as u64;
+                        self.remaining.push(if t == b'%' { 2 * len } else { len });
+                        self.pos = next;
+                        continue;
+                    }
+                }
+                // anything else is a single line, or malformed, which is left
+                // for the parser to report
+                _ => {}
+            }
+            self.pos = next;
+            // the element completes every aggregate it is the last of
+            loop {
+                match self.remaining.last_mut() {
+                    Some(n) if *n > 1 => {
+                        *n -= 1;
+                        break;
+                    }
+                    Some(_) => {
+                        self.remaining.pop();
+                    }
+                    None => {
+                        complete = self.pos;
+                        break;
+                    }
+                }
             }
         }
-        b'%' => {
-            let len = readline(cursor)?;
-            let len = parse_int::<i64>(len)?;
-            let len = len * 2;
-            for _ in 0..len {
-                check(cursor)?;
+    }
+
+    /// Accounts for `n` bytes taken off the front of the buffer.
+    pub(crate) fn consume(&mut self, n: usize) {
+        self.pos -= n;
+        self.searched = self.searched.saturating_sub(n);
+    }
+}
+
+fn header(out: &mut BytesMut, prefix: u8, len: usize) {
+    line(out, prefix, itoa::Buffer::new().format(len).as_bytes())
+}
+
+fn line(out: &mut BytesMut, prefix: u8, content: &[u8]) {
+    out.reserve(content.len() + 3);
+    out.put_u8(prefix);
+    out.put_slice(content);
+    out.put_slice(b"\r\n");
+}
+
+fn bulk(out: &mut BytesMut, prefix: u8, parts: &[&[u8]]) {
+    let len = parts.iter().map(|part| part.len()).sum();
+    header(out, prefix, len);
+    out.reserve(len + 2);
+    parts.iter().for_each(|part| out.put_slice(part));
+    out.put_slice(b"\r\n");
+}
+
+fn aggregate(out: &mut BytesMut, prefix: u8, models: &[Model]) {
+    header(out, prefix, models.len());
+    models.iter().for_each(|model| model.encode(out));
+}
+
+struct Reader<'a> {
+    buf: &'a Bytes,
+    pos: usize,
+    limits: &'a Limits,
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn get_u8(&mut self) -> ParseResult<u8> {
+        match self.buf.get(self.pos) {
+            Some(b) => {
+                self.pos += 1;
+                Ok(*b)
+            }
+            None => Err(ParseError::EndOfStream),
+        }
+    }
+
+    fn readline(&mut self) -> ParseResult<&'a [u8]> {
+        let buf: &'a [u8] = self.buf;
+        let start = self.pos;
+        match buf[start..].windows(2).position(|w| w == b"\r\n") {
+            Some(len) => {
+                self.pos = start + len + 2;
+                Ok(&buf[start..start + len])
             }
+            None => Err(ParseError::EndOfStream),
         }
-        b => return Err(ParseError::Other(format!("unknown type: {}", b))),
     }
-    Ok(())
+
+    fn readbulk(&mut self, len: usize) -> ParseResult<Bytes> {
+        let start = self.pos;
+        let end = start.checked_add(len).ok_or(ParseError::EndOfStream)?;
+        if self.buf.len() < end.saturating_add(2) {
+            return Err(ParseError::EndOfStream);
+        }
+        if &self.buf[end..end + 2] != b"\r\n" {
+            return Err(ParseError::Other(format!(
+                "malformed bulk string: {:?}",
+                &self.buf[start..end + 2]
+            )));
+        }
+        self.pos = end + 2;
+        Ok(self.buf.slice(start..end))
+    }
+
+    fn readlen(&mut self) -> ParseResult<i64> {
+        parse_int::<i64>(self.readline()?)
+    }
+
+    /// Reads the length of a bulk or verbatim string.
+    fn readbulklen(&mut self) -> ParseResult<i64> {
+        let len = self.readlen()?;
+        if len > 0 && len as u64 > self.limits.max_bulk_len as u64 {
+            return Err(ParseError::Protocol(format!(
+                "bulk length {} exceeds {}",
+                len, self.limits.max_bulk_len
+            )));
+        }
+        Ok(len)
+    }
+
+    /// Reads the length of an aggregate and returns it along with a capacity
+    /// to reserve, which is bounded by what could possibly be buffered since
+    /// every element takes at least three bytes.
+    fn readaggregatelen(&mut self) -> ParseResult<(i64, usize)> {
+        let len = self.readlen()?;
+        if len > 0 && len as u64 > self.limits.max_aggregate_len as u64 {
+            return Err(ParseError::Protocol(format!(
+                "aggregate length {} exceeds {}",
+                len, self.limits.max_aggregate_len
+            )));
+        }
+        let capacity = (len.max(0) as usize).min((self.buf.len() - self.pos) / 3);
+        Ok((len, capacity))
+    }
+
+    fn descend(&mut self) -> ParseResult<()> {
+        self.depth += 1;
+        if self.depth > self.limits.max_depth {
+            return Err(ParseError::Protocol(format!(
+                "nesting exceeds depth {}",
+                self.limits.max_depth
+            )));
+        }
+        Ok(())
+    }
 }
 
-pub fn parse(cursor: &mut Cursor<&[u8]>) -> ParseResult<Model> {
-    let model = match cursor.get_u8() {
+fn parse(reader: &mut Reader) -> ParseResult<Model> {
+    match reader.get_u8()? {
         b'-' => {
-            let error = readline(cursor)?;
+            let error = reader.readline()?;
             let error = String::from_utf8(error.to_vec())?;
             Ok(Model::Error(error))
         }
         b'+' => {
-            let status = readline(cursor)?;
+            let status = reader.readline()?;
             let status = String::from_utf8(status.to_vec())?;
             Ok(Model::Status(status))
         }
         b':' => {
-            let integer = readline(cursor)?;
+            let integer = reader.readline()?;
             let integer = parse_int::<i64>(integer)?;
             Ok(Model::Integer(integer))
         }
         b',' => {
-            let double = readline(cursor)?;
+            let double = reader.readline()?;
             let double = String::from_utf8(double.to_vec())?;
             let double = double.parse::<f64>()?;
             Ok(Model::Double(double))
         }
         b'(' => {
-            let bignum = readline(cursor)?;
+            let bignum = reader.readline()?;
             match BigInt::parse_bytes(bignum, 10) {
                 None => Err(ParseError::Other(format!("malformed bignum: {:?}", bignum))),
                 Some(i) => Ok(Model::BigNum(i)),
             }
         }
         b'_' => {
-            let remaining = readline(cursor)?;
+            let remaining = reader.readline()?;
             if remaining.is_empty() {
                 Ok(Model::Nil)
             } else {
@@ -111,57 +402,55 @@ pub fn parse(cursor: &mut Cursor<&[u8]>) -> ParseResult<Model> {
             }
         }
         b'#' => {
-            let b = readline(cursor)?;
+            let b = reader.readline()?;
             if b.len() != 1 {
                 Err(ParseError::Other(format!("malformed bool: {:?}", b)))
-            } else if b[0].to_ascii_lowercase() == b't' {
+            } else if b[0].eq_ignore_ascii_case(&b't') {
                 Ok(Model::Bool(true))
-            } else if b[0].to_ascii_lowercase() == b'f' {
+            } else if b[0].eq_ignore_ascii_case(&b'f') {
                 Ok(Model::Bool(false))
             } else {
                 Err(ParseError::Other(format!("malformed bool: {:?}", b)))
             }
         }
         b'$' => {
-            let len = readline(cursor)?;
-            let len = parse_int::<i64>(len)?;
+            let len = reader.readbulklen()?;
             match len.cmp(&-1) {
                 Ordering::Less => Err(ParseError::Other(format!("malformed len: {:?}", len))),
                 Ordering::Equal => Ok(Model::Nil),
-                Ordering::Greater => Ok(Model::String(readline(cursor)?.to_vec())),
+                Ordering::Greater => Ok(Model::String(reader.readbulk(len as usize)?)),
             }
         }
         b'=' => {
-            let len = readline(cursor)?;
-            let len = parse_int::<i64>(len)?;
+            let len = reader.readbulklen()?;
             if len == -1 {
                 Ok(Model::Nil)
             } else if len < 4 {
                 Err(ParseError::Other(format!("malformed len: {:?}", len)))
             } else {
-                let next = readline(cursor)?;
+                let next = reader.readbulk(len as usize)?;
                 if next[3] != b':' {
                     Err(ParseError::Other(format!(
                         "malformed verbatim string: {:?}",
                         next
                     )))
                 } else {
-                    let (format, text) = next.split_at(3);
-                    Ok(Model::Verb(format.to_vec(), text[1..].to_vec()))
+                    Ok(Model::Verb(next.slice(..3), next.slice(4..)))
                 }
             }
         }
         t @ (b'~' | b'*' | b'>') => {
-            let len = readline(cursor)?;
-            let len = parse_int::<i64>(len)?;
+            let (len, capacity) = reader.readaggregatelen()?;
             match len.cmp(&-1) {
                 Ordering::Less => Err(ParseError::Other(format!("malformed len: {:?}", len))),
                 Ordering::Equal => Ok(Model::Nil),
                 Ordering::Greater => {
-                    let mut vec = Vec::with_capacity(len as usize);
+                    reader.descend()?;
+                    let mut vec = Vec::with_capacity(capacity);
                     for _ in 0..len {
-                        vec.push(parse(cursor)?);
+                        vec.push(parse(reader)?);
                     }
+                    reader.depth -= 1;
                     match t {
                         b'~' => Ok(Model::Set(vec)),
                         b'*' => Ok(Model::Array(vec)),
@@ -172,41 +461,25 @@ pub fn parse(cursor: &mut Cursor<&[u8]>) -> ParseResult<Model> {
             }
         }
         b'%' => {
-            let len = readline(cursor)?;
-            let len = parse_int::<i64>(len)?;
+            let (len, capacity) = reader.readaggregatelen()?;
             match len.cmp(&-1) {
                 Ordering::Less => Err(ParseError::Other(format!("malformed len: {:?}", len))),
                 Ordering::Equal => Ok(Model::Nil),
                 Ordering::Greater => {
-                    let mut vec = Vec::with_capacity((2 * len) as usize);
+                    reader.descend()?;
+                    let mut vec = Vec::with_capacity(capacity / 2);
                     for _ in 0..len {
-                        let k = parse(cursor)?;
-                        let v = parse(cursor)?;
+                        let k = parse(reader)?;
+                        let v = parse(reader)?;
                         vec.push((k, v));
                     }
+                    reader.depth -= 1;
                     Ok(Model::Map(vec))
                 }
             }
         }
         b => Err(ParseError::Other(format!("unknown type: {}", b))),
-    }?;
-
-    Ok(model)
-}
-
-fn readline<'a>(cursor: &mut Cursor<&'a [u8]>) -> ParseResult<&'a [u8]> {
-    let start = cursor.position() as usize;
-    let end = cursor.get_ref().len() - 1;
-
-    for i in start..end {
-        let inner = cursor.get_ref();
-        if inner[i] == b'\r' && inner[i + 1] == b'\n' {
-            cursor.set_position((i + 2) as u64);
-            return Ok(&cursor.get_ref()[start..i]);
-        }
     }
-
-    Err(ParseError::EndOfStream)
 }
 
 fn parse_int<T: atoi::FromRadix10SignedChecked>(i: &[u8]) -> ParseResult<T> {
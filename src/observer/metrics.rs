@@ -0,0 +1,55 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use metrics::{counter, histogram};
+
+use crate::observer::{ClientObserver, Outcome};
+
+/// Records to the global [`metrics`] recorder:
+///
+/// - `aredis_command_duration_seconds`, a histogram by `command`;
+/// - `aredis_command_errors_total`, by `command` and `kind`, which is the
+///   [`ServerErrorKind`](crate::observer::ServerErrorKind) or `FAILED`;
+/// - `aredis_bytes_sent_total` and `aredis_bytes_received_total`;
+/// - `aredis_reconnects_total`, by `addr`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MetricsObserver;
+
+impl ClientObserver for MetricsObserver {
+    fn on_command_end(&self, name: &str, duration: Duration, outcome: &Outcome) {
+        let command = name.to_string();
+        histogram!("aredis_command_duration_seconds", "command" => command.clone())
+            .record(duration.as_secs_f64());
+        let kind = match outcome {
+            Outcome::Ok => return,
+            Outcome::ServerError(kind) => kind.as_str(),
+            Outcome::Failed => "FAILED",
+        };
+        counter!("aredis_command_errors_total", "command" => command, "kind" => kind).increment(1);
+    }
+
+    fn on_bytes_sent(&self, len: usize) {
+        counter!("aredis_bytes_sent_total").increment(len as u64);
+    }
+
+    fn on_bytes_received(&self, len: usize) {
+        counter!("aredis_bytes_received_total").increment(len as u64);
+    }
+
+    fn on_reconnect(&self, addr: &str) {
+        counter!("aredis_reconnects_total", "addr" => addr.to_string()).increment(1);
+    }
+}
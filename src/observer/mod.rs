@@ -0,0 +1,124 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Hooks into what a [`Client`](crate::Client) does, e.g. to export metrics.
+
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "prometheus")]
+mod prometheus;
+
+use std::time::Duration;
+
+#[cfg(feature = "metrics")]
+pub use self::metrics::MetricsObserver;
+#[cfg(feature = "prometheus")]
+pub use self::prometheus::PrometheusObserver;
+
+/// Called as a client runs commands. Every method does nothing by default.
+///
+/// A command starts once it is written and ends once its reply is read, so
+/// the duration is the latency as the client sees it, and pipelined commands
+/// overlap. Names are those of
+/// [`Command::name`](crate::command::Command::name), a bounded set, so they can
+/// label metrics.
+pub trait ClientObserver: Send + Sync {
+    fn on_command_start(&self, _name: &str) {}
+
+    fn on_command_end(&self, _name: &str, _duration: Duration, _outcome: &Outcome) {}
+
+    fn on_bytes_sent(&self, _len: usize) {}
+
+    fn on_bytes_received(&self, _len: usize) {}
+
+    /// A replica that was down is connected again.
+    fn on_reconnect(&self, _addr: &str) {}
+}
+
+/// How a command ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Any reply but an error.
+    Ok,
+    /// An error reply.
+    ServerError(ServerErrorKind),
+    /// No reply, since the connection failed or closed.
+    Failed,
+}
+
+/// The kind of an error reply, by the prefix of its message. Unlike the
+/// message, there are few enough to label metrics with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ServerErrorKind {
+    Err,
+    WrongType,
+    NoAuth,
+    NoPerm,
+    WrongPass,
+    Busy,
+    Loading,
+    ReadOnly,
+    Oom,
+    ExecAbort,
+    NoScript,
+    Moved,
+    Ask,
+    TryAgain,
+    ClusterDown,
+    Other,
+}
+
+impl ServerErrorKind {
+    pub fn of(message: &str) -> Self {
+        match message.split_whitespace().next().unwrap_or_default() {
+            "ERR" => ServerErrorKind::Err,
+            "WRONGTYPE" => ServerErrorKind::WrongType,
+            "NOAUTH" => ServerErrorKind::NoAuth,
+            "NOPERM" => ServerErrorKind::NoPerm,
+            "WRONGPASS" => ServerErrorKind::WrongPass,
+            "BUSY" => ServerErrorKind::Busy,
+            "LOADING" => ServerErrorKind::Loading,
+            "READONLY" => ServerErrorKind::ReadOnly,
+            "OOM" => ServerErrorKind::Oom,
+            "EXECABORT" => ServerErrorKind::ExecAbort,
+            "NOSCRIPT" => ServerErrorKind::NoScript,
+            "MOVED" => ServerErrorKind::Moved,
+            "ASK" => ServerErrorKind::Ask,
+            "TRYAGAIN" => ServerErrorKind::TryAgain,
+            "CLUSTERDOWN" => ServerErrorKind::ClusterDown,
+            _ => ServerErrorKind::Other,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ServerErrorKind::Err => "ERR",
+            ServerErrorKind::WrongType => "WRONGTYPE",
+            ServerErrorKind::NoAuth => "NOAUTH",
+            ServerErrorKind::NoPerm => "NOPERM",
+            ServerErrorKind::WrongPass => "WRONGPASS",
+            ServerErrorKind::Busy => "BUSY",
+            ServerErrorKind::Loading => "LOADING",
+            ServerErrorKind::ReadOnly => "READONLY",
+            ServerErrorKind::Oom => "OOM",
+            ServerErrorKind::ExecAbort => "EXECABORT",
+            ServerErrorKind::NoScript => "NOSCRIPT",
+            ServerErrorKind::Moved => "MOVED",
+            ServerErrorKind::Ask => "ASK",
+            ServerErrorKind::TryAgain => "TRYAGAIN",
+            ServerErrorKind::ClusterDown => "CLUSTERDOWN",
+            ServerErrorKind::Other => "OTHER",
+        }
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use prometheus::{HistogramOpts, HistogramVec, IntCounter, IntCounterVec, Opts, Registry};
+
+use crate::observer::{ClientObserver, Outcome};
+
+/// Records the same metrics as
+/// [`MetricsObserver`](crate::observer::MetricsObserver) does, but to a
+/// Prometheus [`Registry`].
+#[derive(Clone)]
+pub struct PrometheusObserver {
+    duration: HistogramVec,
+    errors: IntCounterVec,
+    sent: IntCounter,
+    received: IntCounter,
+    reconnects: IntCounterVec,
+}
+
+impl PrometheusObserver {
+    /// Creates the metrics and registers them with `registry`.
+    pub fn new(registry: &Registry) -> prometheus::Result<Self> {
+        let observer = PrometheusObserver {
+            duration: HistogramVec::new(
+                HistogramOpts::new(
+                    "aredis_command_duration_seconds",
+                    "Command latency as the client sees it.",
+                ),
+                &["command"],
+            )?,
+            errors: IntCounterVec::new(
+                Opts::new("aredis_command_errors_total", "Commands that failed."),
+                &["command", "kind"],
+            )?,
+            sent: IntCounter::new("aredis_bytes_sent_total", "Bytes written to servers.")?,
+            received: IntCounter::new("aredis_bytes_received_total", "Bytes read from servers.")?,
+            reconnects: IntCounterVec::new(
+                Opts::new("aredis_reconnects_total", "Reconnections to servers."),
+                &["addr"],
+            )?,
+        };
+        registry.register(Box::new(observer.duration.clone()))?;
+        registry.register(Box::new(observer.errors.clone()))?;
+        registry.register(Box::new(observer.sent.clone()))?;
+        registry.register(Box::new(observer.received.clone()))?;
+        registry.register(Box::new(observer.reconnects.clone()))?;
+        Ok(observer)
+    }
+}
+
+impl ClientObserver for PrometheusObserver {
+    fn on_command_end(&self, name: &str, duration: Duration, outcome: &Outcome) {
+        self.duration
+            .with_label_values(&[name])
+            .observe(duration.as_secs_f64());
+        let kind = match outcome {
+            Outcome::Ok => return,
+            Outcome::ServerError(kind) => kind.as_str(),
+            Outcome::Failed => "FAILED",
+        };
+        self.errors.with_label_values(&[name, kind]).inc();
+    }
+
+    fn on_bytes_sent(&self, len: usize) {
+        self.sent.inc_by(len as u64);
+    }
+
+    fn on_bytes_received(&self, len: usize) {
+        self.received.inc_by(len as u64);
+    }
+
+    fn on_reconnect(&self, addr: &str) {
+        self.reconnects.with_label_values(&[addr]).inc();
+    }
+}
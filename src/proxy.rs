@@ -0,0 +1,354 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A proxy which relays RESP frames between clients and an upstream server,
+//! recording what goes through so that sessions can be inspected and
+//! replayed later.
+
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufWriter, Write},
+    iter,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use futures::{
+    channel::oneshot,
+    future::{self, Either},
+    pin_mut,
+};
+use tokio::{net::TcpListener, task::JoinHandle};
+
+#[cfg(feature = "testing")]
+use crate::testing::MockServer;
+use crate::{runtime, Connection, Error, Model, Result};
+
+/// A request relayed upstream along with its reply.
+#[derive(Debug)]
+pub struct Exchange {
+    /// Numbers the client connection the exchange happened on.
+    pub connection: u64,
+    pub at: SystemTime,
+    /// From relaying the request until the reply came back.
+    pub elapsed: Duration,
+    /// `None` for frames the server sent unasked, such as pushes.
+    pub request: Option<Model>,
+    pub reply: Model,
+}
+
+type Inspect = Arc<dyn Fn(&Exchange) + Send + Sync>;
+
+pub struct Proxy {
+    upstream: String,
+    listen: String,
+    record: Option<PathBuf>,
+    inspect: Option<Inspect>,
+}
+
+impl Proxy {
+    pub fn new<A: Into<String>>(upstream: A) -> Self {
+        Proxy {
+            upstream: upstream.into(),
+            listen: "127.0.0.1:0".to_string(),
+            record: None,
+            inspect: None,
+        }
+    }
+
+    /// Where to listen, a free local port by default.
+    pub fn listen<A: Into<String>>(self, addr: A) -> Self {
+        Proxy {
+            listen: addr.into(),
+            ..self
+        }
+    }
+
+    /// Records every exchange to a file, which [`Recording::load`] reads.
+    pub fn record<P: AsRef<Path>>(self, path: P) -> Self {
+        Proxy {
+            record: Some(path.as_ref().to_path_buf()),
+            ..self
+        }
+    }
+
+    /// Calls `inspect` with every exchange, to log it for example.
+    pub fn inspect<F>(self, inspect: F) -> Self
+    where
+        F: Fn(&Exchange) + Send + Sync + 'static,
+    {
+        Proxy {
+            inspect: Some(Arc::new(inspect)),
+            ..self
+        }
+    }
+
+    pub async fn start(self) -> Result<ProxyServer> {
+        let records = match self.record {
+            Some(path) => {
+                let file = File::create(path)?;
+                let (records, receiver) = mpsc::channel();
+                thread::Builder::new()
+                    .name("aredis-proxy-record".to_string())
+                    .spawn(move || write(file, receiver))?;
+                Some(records)
+            }
+            None => None,
+        };
+        let sink = Arc::new(Sink {
+            records,
+            inspect: self.inspect,
+        });
+        let listener = TcpListener::bind(self.listen).await?;
+        let addr = listener.local_addr()?;
+        let upstream = self.upstream;
+        let accept = tokio::spawn({
+            let sink = sink.clone();
+            runtime::serve(listener, move |client, id| {
+                relay(client, upstream.clone(), id, sink.clone())
+            })
+        });
+        Ok(ProxyServer { addr, accept, sink })
+    }
+}
+
+/// A running [`Proxy`], which stops once dropped.
+pub struct ProxyServer {
+    addr: SocketAddr,
+    accept: JoinHandle<()>,
+    sink: Arc<Sink>,
+}
+
+impl ProxyServer {
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Waits until the exchanges recorded so far are written to the file.
+    pub async fn flush(&self) {
+        self.sink.flush().await
+    }
+}
+
+impl Drop for ProxyServer {
+    fn drop(&mut self) {
+        self.accept.abort();
+    }
+}
+
+struct Sink {
+    records: Option<mpsc::Sender<Record>>,
+    inspect: Option<Inspect>,
+}
+
+enum Record {
+    Exchange(BytesMut),
+    Flush(oneshot::Sender<()>),
+}
+
+impl Sink {
+    /// Records an exchange, leaving the file to the thread writing it.
+    fn record(&self, exchange: &Exchange) {
+        if let Some(inspect) = &self.inspect {
+            inspect(exchange);
+        }
+        if let Some(records) = &self.records {
+            let mut out = BytesMut::new();
+            encode(exchange, &mut out);
+            let _ = records.send(Record::Exchange(out));
+        }
+    }
+
+    async fn flush(&self) {
+        if let Some(records) = &self.records {
+            let (done, written) = oneshot::channel();
+            if records.send(Record::Flush(done)).is_ok() {
+                let _ = written.await;
+            }
+        }
+    }
+}
+
+/// Writes records to the file on a thread of its own, so that relaying never
+/// waits for the disk. Failing to write does not fail the sessions relayed.
+fn write(file: File, records: mpsc::Receiver<Record>) {
+    let mut file = BufWriter::new(file);
+    while let Ok(record) = records.recv() {
+        // whatever else is queued goes out before flushing
+        for record in iter::once(record).chain(records.try_iter()) {
+            match record {
+                Record::Exchange(out) => {
+                    let _ = file.write_all(&out);
+                }
+                Record::Flush(done) => {
+                    let _ = file.flush();
+                    let _ = done.send(());
+                }
+            }
+        }
+        let _ = file.flush();
+    }
+}
+
+/// Relays frames both ways, byte for byte, until either side closes. Replies
+/// are paired with requests in order, as RESP servers answer them.
+async fn relay(mut client: Connection, upstream: String, id: u64, sink: Arc<Sink>) -> Result<()> {
+    let mut upstream = runtime::connect(upstream.as_str()).await?;
+    client.keep_frames();
+    upstream.keep_frames();
+    let mut pending = VecDeque::new();
+    loop {
+        let frame = {
+            let request = client.recv_raw_frame();
+            let reply = upstream.recv_raw_frame();
+            pin_mut!(request, reply);
+            match future::select(request, reply).await {
+                Either::Left((request, _)) => Either::Left(request?),
+                Either::Right((reply, _)) => Either::Right(reply?),
+            }
+        };
+        match frame {
+            Either::Left(Some((request, frame))) => {
+                upstream.send_raw(&frame).await?;
+                pending.push_back((request, SystemTime::now(), Instant::now()));
+            }
+            Either::Right(Some((reply, frame))) => {
+                let request = match reply {
+                    Model::Push(_) => None,
+                    _ => pending.pop_front(),
+                };
+                let exchange = match request {
+                    Some((request, at, start)) => Exchange {
+                        connection: id,
+                        at,
+                        elapsed: start.elapsed(),
+                        request: Some(request),
+                        reply,
+                    },
+                    None => Exchange {
+                        connection: id,
+                        at: SystemTime::now(),
+                        elapsed: Duration::ZERO,
+                        request: None,
+                        reply,
+                    },
+                };
+                sink.record(&exchange);
+                client.send_raw(&frame).await?;
+            }
+            Either::Left(None) | Either::Right(None) => return Ok(()),
+        }
+    }
+}
+
+/// Writes an exchange as a RESP array of the connection, the time and the
+/// elapsed time in microseconds, the request or nil, and the reply.
+fn encode(exchange: &Exchange, out: &mut BytesMut) {
+    let at = exchange.at.duration_since(UNIX_EPOCH).unwrap_or_default();
+    out.put_slice(b"*5\r\n");
+    Model::Integer(exchange.connection as i64).encode(out);
+    Model::Integer(at.as_micros() as i64).encode(out);
+    Model::Integer(exchange.elapsed.as_micros() as i64).encode(out);
+    match &exchange.request {
+        Some(request) => request.encode(out),
+        None => Model::Nil.encode(out),
+    }
+    exchange.reply.encode(out);
+}
+
+fn decode(model: Model) -> Option<Exchange> {
+    let Model::Array(fields) = model else {
+        return None;
+    };
+    let fields: [Model; 5] = fields.try_into().ok()?;
+    match fields {
+        [Model::Integer(connection), Model::Integer(at), Model::Integer(elapsed), request, reply] => {
+            Some(Exchange {
+                connection: connection as u64,
+                at: UNIX_EPOCH + Duration::from_micros(at as u64),
+                elapsed: Duration::from_micros(elapsed as u64),
+                request: match request {
+                    Model::Nil => None,
+                    request => Some(request),
+                },
+                reply,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// The exchanges a [`Proxy`] recorded to a file.
+pub struct Recording {
+    exchanges: Vec<Exchange>,
+}
+
+impl Recording {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let buf = Bytes::from(std::fs::read(path)?);
+        let mut exchanges = vec![];
+        let mut pos = 0;
+        while pos < buf.len() {
+            let exchange = match Model::parse(&buf, pos)? {
+                Some((model, next)) => {
+                    pos = next;
+                    decode(model)
+                }
+                None => None,
+            };
+            match exchange {
+                Some(exchange) => exchanges.push(exchange),
+                None => return Err(Error::Internal("malformed recording".to_string())),
+            }
+        }
+        Ok(Recording { exchanges })
+    }
+
+    pub fn exchanges(&self) -> &[Exchange] {
+        &self.exchanges
+    }
+
+    /// Serves the recorded replies: each command is answered with the reply
+    /// to the first recorded request equal to it which was not replayed yet.
+    #[cfg(feature = "testing")]
+    pub async fn replay(self) -> Result<MockServer> {
+        let mut replies: Vec<(Bytes, Model)> = self
+            .exchanges
+            .into_iter()
+            .filter_map(|exchange| {
+                let mut request = BytesMut::new();
+                exchange.request?.encode(&mut request);
+                Some((request.freeze(), exchange.reply))
+            })
+            .collect();
+        MockServer::handler(move |args| {
+            let mut request = BytesMut::new();
+            let args = args.iter().cloned().map(Model::String).collect();
+            Model::Array(args).encode(&mut request);
+            match replies
+                .iter()
+                .position(|(recorded, _)| *recorded == request)
+            {
+                Some(index) => replies.remove(index).1,
+                None => Model::Error("ERR no recorded reply left for the command".to_string()),
+            }
+        })
+        .await
+    }
+}
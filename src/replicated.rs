@@ -0,0 +1,248 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    client::{identify, match_failure},
+    command::{Command, Ping, ReadOnly},
+    observer::ClientObserver,
+    runtime, Client, Connection, Model, Result,
+};
+
+const RETRY_BACKOFF: Duration = Duration::from_secs(1);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How read-only commands choose among the available replicas.
+pub enum ReadFrom {
+    RoundRobin,
+    LowestLatency,
+}
+
+/// Connects a [`Client`] to one primary and a number of replicas.
+///
+/// Commands whose [`Command::read_only`] is `true` are served by replicas, and
+/// the rest go to the primary. A read falls back to the primary when no
+/// replica can serve it, in time or at all, such as one still loading its
+/// data; a failed replica is retried after a short backoff.
+pub struct ReplicatedClient {
+    primary: String,
+    replicas: Vec<String>,
+    read_from: ReadFrom,
+    readonly: bool,
+    timeout: Duration,
+}
+
+impl ReplicatedClient {
+    pub fn new<A: Into<String>>(primary: A) -> Self {
+        ReplicatedClient {
+            primary: primary.into(),
+            replicas: vec![],
+            read_from: ReadFrom::RoundRobin,
+            readonly: false,
+            timeout: READ_TIMEOUT,
+        }
+    }
+
+    pub fn replica<A: Into<String>>(mut self, addr: A) -> Self {
+        self.replicas.push(addr.into());
+        self
+    }
+
+    pub fn read_from(self, read_from: ReadFrom) -> Self {
+        ReplicatedClient { read_from, ..self }
+    }
+
+    /// Issue READONLY on every replica connection, as cluster replicas require.
+    pub fn readonly(self, readonly: bool) -> Self {
+        ReplicatedClient { readonly, ..self }
+    }
+
+    /// How long a replica may take to answer a read before it is marked down
+    /// and the read goes to the primary instead. One second by default.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        ReplicatedClient { timeout, ..self }
+    }
+
+    pub async fn connect(self) -> Result<Client> {
+        let mut connection = runtime::connect(self.primary.as_str()).await?;
+        identify(&mut connection).await?;
+        let mut replicas = Replicas {
+            replicas: self.replicas.into_iter().map(Replica::new).collect(),
+            read_from: self.read_from,
+            readonly: self.readonly,
+            timeout: self.timeout,
+            next: 0,
+            observer: None,
+        };
+        for replica in replicas.replicas.iter_mut() {
+            replica.reconnect(replicas.readonly).await;
+        }
+        Ok(Client::with_replicas(connection, replicas))
+    }
+}
+
+pub(crate) struct Replicas {
+    replicas: Vec<Replica>,
+    read_from: ReadFrom,
+    readonly: bool,
+    timeout: Duration,
+    next: usize,
+    observer: Option<Arc<dyn ClientObserver>>,
+}
+
+impl Replicas {
+    pub(crate) fn set_observer(&mut self, observer: Arc<dyn ClientObserver>) {
+        for replica in self.replicas.iter_mut() {
+            if let Some(connection) = replica.connection.as_mut() {
+                connection.set_observer(observer.clone());
+            }
+        }
+        self.observer = Some(observer);
+    }
+
+    /// Returns `None` if no replica could serve the command.
+    pub(crate) async fn execute(&mut self, cmd: &impl Command) -> Option<Model> {
+        let n = self.replicas.len();
+        let mut candidates: Vec<usize> = (0..n).map(|i| (self.next + i) % n).collect();
+        match self.read_from {
+            ReadFrom::RoundRobin => self.next = (self.next + 1) % n.max(1),
+            ReadFrom::LowestLatency => candidates.sort_by_key(|i| self.replicas[*i].latency),
+        }
+
+        for i in candidates {
+            let replica = &mut self.replicas[i];
+            if replica.connection.is_none() {
+                if !replica.reconnect(self.readonly).await {
+                    continue;
+                }
+                #[cfg(feature = "tracing")]
+                tracing::info!(server.address = %replica.addr, "reconnected to replica");
+                if let (Some(observer), Some(connection)) =
+                    (self.observer.as_ref(), replica.connection.as_mut())
+                {
+                    observer.on_reconnect(&replica.addr);
+                    connection.set_observer(observer.clone());
+                }
+            }
+            if let Some(model) = replica.execute(cmd, self.timeout).await {
+                return Some(model);
+            }
+        }
+        None
+    }
+}
+
+struct Replica {
+    addr: String,
+    connection: Option<Connection>,
+    latency: Duration,
+    retry_at: Instant,
+}
+
+impl Replica {
+    fn new(addr: String) -> Self {
+        Replica {
+            addr,
+            connection: None,
+            latency: Duration::MAX,
+            retry_at: Instant::now(),
+        }
+    }
+
+    async fn reconnect(&mut self, readonly: bool) -> bool {
+        if Instant::now() < self.retry_at {
+            return false;
+        }
+        let connect = Replica::try_connect(&self.addr, readonly);
+        match runtime::timeout(CONNECT_TIMEOUT, connect).await {
+            Ok((connection, latency)) => {
+                self.connection = Some(connection);
+                self.latency = latency;
+                true
+            }
+            Err(_) => {
+                self.mark_down();
+                false
+            }
+        }
+    }
+
+    /// Takes the address rather than `&self`, which holds a `Connection` that
+    /// is not `Sync`, so that the future is `Send`. Callers bound it with
+    /// [`CONNECT_TIMEOUT`] so that an unresponsive replica cannot stall reads.
+    async fn try_connect(addr: &str, readonly: bool) -> Result<(Connection, Duration)> {
+        let mut connection = runtime::connect(addr).await?;
+        identify(&mut connection).await?;
+        if readonly {
+            connection.send(&ReadOnly).await?;
+            match connection.recv().await? {
+                Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => {}
+                model => return match_failure(model),
+            }
+        }
+        let start = Instant::now();
+        connection.send(&Ping).await?;
+        match connection.recv().await? {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("PONG") => {
+                Ok((connection, start.elapsed()))
+            }
+            model => match_failure(model),
+        }
+    }
+
+    async fn execute(&mut self, cmd: &impl Command, timeout: Duration) -> Option<Model> {
+        let connection = self.connection.as_mut()?;
+        let start = Instant::now();
+        let round_trip = async {
+            connection.send(cmd).await?;
+            connection.recv().await
+        };
+        match runtime::timeout(timeout, round_trip).await {
+            Ok(Some(Model::Error(e))) if unavailable(&e) => {
+                self.mark_down();
+                None
+            }
+            Ok(Some(model)) => {
+                self.latency = (self.latency * 7 + start.elapsed()) / 8;
+                Some(model)
+            }
+            _ => {
+                self.mark_down();
+                None
+            }
+        }
+    }
+
+    fn mark_down(&mut self) {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(server.address = %self.addr, "replica is down");
+        self.connection = None;
+        self.latency = Duration::MAX;
+        self.retry_at = Instant::now() + RETRY_BACKOFF;
+    }
+}
+
+/// Whether an error reply means the replica cannot serve reads for now, rather
+/// than that the command failed.
+fn unavailable(message: &str) -> bool {
+    matches!(
+        message.split_whitespace().next(),
+        Some("LOADING" | "MASTERDOWN" | "BUSY")
+    )
+}
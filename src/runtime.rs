@@ -0,0 +1,100 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{future::Future, io, time::Duration};
+
+use futures::io::{AsyncRead, AsyncWrite};
+
+use crate::{Connection, Result};
+
+#[cfg(not(any(feature = "tokio-comp", feature = "async-std-comp")))]
+compile_error!("aredis requires either the `tokio-comp` or the `async-std-comp` feature");
+
+#[cfg(all(feature = "async-std-comp", not(feature = "tokio-comp")))]
+pub use async_std::net::ToSocketAddrs;
+#[cfg(feature = "tokio-comp")]
+pub use tokio::net::ToSocketAddrs;
+
+/// A byte stream to a server, such as a TCP or TLS stream of any runtime.
+pub trait Transport: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Transport for T {}
+
+#[cfg(feature = "tokio-comp")]
+pub(crate) async fn connect<A: ToSocketAddrs>(addr: A) -> Result<Connection> {
+    use tokio_util::compat::TokioAsyncReadCompatExt;
+
+    let socket = tokio::net::TcpStream::connect(addr).await?;
+    // commands go out whole, so Nagle's algorithm could only delay them
+    socket.set_nodelay(true)?;
+    let peer = socket.peer_addr()?;
+    let mut connection = Connection::new(socket.compat());
+    connection.connected(peer);
+    Ok(connection)
+}
+
+#[cfg(all(feature = "async-std-comp", not(feature = "tokio-comp")))]
+pub(crate) async fn connect<A: ToSocketAddrs>(addr: A) -> Result<Connection> {
+    let socket = async_std::net::TcpStream::connect(addr).await?;
+    socket.set_nodelay(true)?;
+    let peer = socket.peer_addr()?;
+    let mut connection = Connection::new(socket);
+    connection.connected(peer);
+    Ok(connection)
+}
+
+/// Accepts connections until aborted, serving each on a task of its own with
+/// `serve`, which is also given the number of the connection, counting from 1.
+#[cfg(any(feature = "testing", feature = "proxy"))]
+pub(crate) async fn serve<F, Fut>(listener: tokio::net::TcpListener, mut serve: F)
+where
+    F: FnMut(Connection, u64) -> Fut,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    use tokio_util::compat::TokioAsyncReadCompatExt;
+
+    // connections are aborted along with this set once this task is
+    let mut connections = tokio::task::JoinSet::new();
+    let mut id = 0;
+    while let Ok((stream, _)) = listener.accept().await {
+        id += 1;
+        if stream.set_nodelay(true).is_ok() {
+            connections.spawn(serve(Connection::new(stream.compat()), id));
+        }
+    }
+}
+
+/// Fails with [`std::io::ErrorKind::TimedOut`] if `future` does not complete in
+/// `duration`.
+#[cfg(feature = "tokio-comp")]
+pub(crate) async fn timeout<T>(
+    duration: Duration,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    match tokio::time::timeout(duration, future).await {
+        Ok(result) => result,
+        Err(_) => Err(io::Error::from(io::ErrorKind::TimedOut).into()),
+    }
+}
+
+#[cfg(all(feature = "async-std-comp", not(feature = "tokio-comp")))]
+pub(crate) async fn timeout<T>(
+    duration: Duration,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    match async_std::future::timeout(duration, future).await {
+        Ok(result) => result,
+        Err(_) => Err(io::Error::from(io::ErrorKind::TimedOut).into()),
+    }
+}
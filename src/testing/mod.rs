@@ -0,0 +1,129 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! In-process servers to test code using aredis without Redis.
+
+mod server;
+
+use std::{
+    collections::VecDeque,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
+pub use server::InMemoryServer;
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use crate::{runtime, Connection, Model, Result};
+
+type Commands = Arc<Mutex<Vec<Vec<Bytes>>>>;
+type Handler = Arc<Mutex<dyn FnMut(&[Bytes]) -> Model + Send>>;
+
+/// A server on a local port which answers each command with a [`Model`],
+/// either scripted up front or computed by a handler, and records the
+/// commands it receives. The handshake a client performs on connect is
+/// answered by the server itself and not recorded.
+pub struct MockServer {
+    addr: SocketAddr,
+    commands: Commands,
+    accept: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Answers commands with `replies` in order, and with an error once they
+    /// run out.
+    pub async fn scripted(replies: impl IntoIterator<Item = Model>) -> Result<Self> {
+        let mut replies: VecDeque<Model> = replies.into_iter().collect();
+        MockServer::handler(move |_| {
+            replies
+                .pop_front()
+                .unwrap_or_else(|| Model::Error("ERR no scripted reply left".to_string()))
+        })
+        .await
+    }
+
+    /// Answers each command, given as its arguments, with what `handler`
+    /// returns.
+    pub async fn handler<F>(handler: F) -> Result<Self>
+    where
+        F: FnMut(&[Bytes]) -> Model + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let commands = Commands::default();
+        let handler: Handler = Arc::new(Mutex::new(handler));
+        let accept = tokio::spawn({
+            let commands = commands.clone();
+            runtime::serve(listener, move |connection, _| {
+                serve(connection, handler.clone(), commands.clone())
+            })
+        });
+        Ok(MockServer {
+            addr,
+            commands,
+            accept,
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The commands received so far, from all connections, in order.
+    pub fn commands(&self) -> Vec<Vec<Bytes>> {
+        self.commands.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.accept.abort();
+    }
+}
+
+async fn serve(mut connection: Connection, handler: Handler, commands: Commands) -> Result<()> {
+    while let Some(model) = connection.recv().await? {
+        let reply = match command_args(model) {
+            Some(args) if is_handshake(&args) => Model::Status("OK".to_string()),
+            Some(args) if !args.is_empty() => {
+                commands.lock().unwrap().push(args.clone());
+                (handler.lock().unwrap())(&args)
+            }
+            _ => Model::Error("ERR Protocol error: expected an array of bulk strings".to_string()),
+        };
+        connection.send_model(&reply).await?;
+    }
+    Ok(())
+}
+
+/// The arguments of a command, which clients send as an array of bulk strings.
+fn command_args(model: Model) -> Option<Vec<Bytes>> {
+    match model {
+        Model::Array(args) => args
+            .into_iter()
+            .map(|arg| match arg {
+                Model::String(arg) => Some(arg),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+fn is_handshake(args: &[Bytes]) -> bool {
+    args.len() > 1
+        && args[0].eq_ignore_ascii_case(b"CLIENT")
+        && args[1].eq_ignore_ascii_case(b"SETINFO")
+}
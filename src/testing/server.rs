@@ -0,0 +1,1136 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    net::SocketAddr,
+    str::FromStr,
+    sync::{Arc, Mutex, PoisonError},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bytes::{Bytes, BytesMut};
+use tokio::{
+    net::{TcpListener, ToSocketAddrs},
+    task::JoinHandle,
+};
+
+use super::command_args;
+use crate::{runtime, Connection, Model, Result};
+
+/// A Redis-compatible server keeping a single database in memory. It speaks
+/// RESP2 and, after `HELLO 3`, RESP3, and implements the string, key, expiry,
+/// hash and list commands.
+pub struct InMemoryServer {
+    addr: SocketAddr,
+    accept: JoinHandle<()>,
+}
+
+impl InMemoryServer {
+    /// Serves on a free local port.
+    pub async fn start() -> Result<Self> {
+        InMemoryServer::bind("127.0.0.1:0").await
+    }
+
+    pub async fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let db = Arc::<Mutex<Db>>::default();
+        let accept = tokio::spawn(runtime::serve(listener, move |connection, id| {
+            serve(connection, db.clone(), id as i64)
+        }));
+        Ok(InMemoryServer { addr, accept })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for InMemoryServer {
+    fn drop(&mut self) {
+        self.accept.abort();
+    }
+}
+
+async fn serve(mut connection: Connection, db: Arc<Mutex<Db>>, id: i64) -> Result<()> {
+    let mut session = Session {
+        id,
+        resp3: false,
+        name: None,
+    };
+    while let Some(model) = connection.recv().await? {
+        let args = match command_args(model) {
+            Some(args) if !args.is_empty() => args,
+            _ => {
+                let e = "ERR Protocol error: expected an array of bulk strings";
+                connection.send_model(&Model::Error(e.to_string())).await?;
+                continue;
+            }
+        };
+        let quit = args[0].eq_ignore_ascii_case(b"QUIT");
+        let reply = {
+            // a command that panicked must not take the others down with it
+            let mut db = db.lock().unwrap_or_else(PoisonError::into_inner);
+            execute(&mut db, &mut session, &args).unwrap_or_else(|e| e)
+        };
+        let reply = if session.resp3 { reply } else { resp2(reply) };
+        connection.send_model(&reply).await?;
+        if quit {
+            break;
+        }
+    }
+    Ok(())
+}
+
+struct Session {
+    id: i64,
+    resp3: bool,
+    name: Option<Bytes>,
+}
+
+/// The longest string value, 512 MiB as in Redis.
+const MAX_STRING_LEN: usize = 512 * 1024 * 1024;
+
+/// Either reply is sent back, the error one being returned early with `?`.
+type Outcome = std::result::Result<Model, Model>;
+
+#[derive(Default)]
+struct Db {
+    entries: HashMap<Bytes, Entry>,
+}
+
+struct Entry {
+    value: Value,
+    /// Unix time in milliseconds.
+    expire_at: Option<i64>,
+}
+
+enum Value {
+    String(Bytes),
+    Hash(HashMap<Bytes, Bytes>),
+    List(VecDeque<Bytes>),
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::String(_) => "string",
+            Value::Hash(_) => "hash",
+            Value::List(_) => "list",
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            Value::String(_) => false,
+            Value::Hash(hash) => hash.is_empty(),
+            Value::List(list) => list.is_empty(),
+        }
+    }
+}
+
+impl Db {
+    /// Looks a key up, dropping it first if it has expired.
+    fn entry(&mut self, key: &[u8]) -> Option<&mut Entry> {
+        let now = now();
+        if let Some(Entry {
+            expire_at: Some(at),
+            ..
+        }) = self.entries.get(key)
+        {
+            if *at <= now {
+                self.entries.remove(key);
+            }
+        }
+        self.entries.get_mut(key)
+    }
+
+    fn contains(&mut self, key: &[u8]) -> bool {
+        self.entry(key).is_some()
+    }
+
+    fn remove(&mut self, key: &[u8]) -> Option<Entry> {
+        self.entry(key)?;
+        self.entries.remove(key)
+    }
+
+    fn insert(&mut self, key: Bytes, value: Value, expire_at: Option<i64>) {
+        self.entries.insert(key, Entry { value, expire_at });
+    }
+
+    /// Live keys in a stable order, so that cursors can index into them.
+    fn keys(&mut self) -> Vec<Bytes> {
+        let now = now();
+        self.entries
+            .retain(|_, entry| entry.expire_at.is_none_or(|at| at > now));
+        let mut keys: Vec<Bytes> = self.entries.keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    fn string(&mut self, key: &[u8]) -> std::result::Result<Option<&Bytes>, Model> {
+        match self.entry(key) {
+            None => Ok(None),
+            Some(Entry {
+                value: Value::String(s),
+                ..
+            }) => Ok(Some(s)),
+            Some(_) => Err(wrong_type()),
+        }
+    }
+
+    fn hash(
+        &mut self,
+        key: &[u8],
+    ) -> std::result::Result<Option<&mut HashMap<Bytes, Bytes>>, Model> {
+        match self.entry(key) {
+            None => Ok(None),
+            Some(Entry {
+                value: Value::Hash(hash),
+                ..
+            }) => Ok(Some(hash)),
+            Some(_) => Err(wrong_type()),
+        }
+    }
+
+    fn hash_or_default(
+        &mut self,
+        key: &Bytes,
+    ) -> std::result::Result<&mut HashMap<Bytes, Bytes>, Model> {
+        if self.hash(key)?.is_none() {
+            self.insert(key.clone(), Value::Hash(HashMap::new()), None);
+        }
+        Ok(self.hash(key)?.unwrap())
+    }
+
+    fn list(&mut self, key: &[u8]) -> std::result::Result<Option<&mut VecDeque<Bytes>>, Model> {
+        match self.entry(key) {
+            None => Ok(None),
+            Some(Entry {
+                value: Value::List(list),
+                ..
+            }) => Ok(Some(list)),
+            Some(_) => Err(wrong_type()),
+        }
+    }
+
+    fn list_or_default(&mut self, key: &Bytes) -> std::result::Result<&mut VecDeque<Bytes>, Model> {
+        if self.list(key)?.is_none() {
+            self.insert(key.clone(), Value::List(VecDeque::new()), None);
+        }
+        Ok(self.list(key)?.unwrap())
+    }
+
+    /// Drops a hash or list left empty, as Redis never keeps those around.
+    fn drop_if_empty(&mut self, key: &[u8]) {
+        if self
+            .entries
+            .get(key)
+            .is_some_and(|entry| entry.value.is_empty())
+        {
+            self.entries.remove(key);
+        }
+    }
+}
+
+fn execute(db: &mut Db, session: &mut Session, args: &[Bytes]) -> Outcome {
+    let name = args[0].to_ascii_uppercase();
+    match (name.as_slice(), &args[1..]) {
+        // connection
+        (b"PING", []) => Ok(status("PONG")),
+        (b"PING", [message]) | (b"ECHO", [message]) => Ok(Model::String(message.clone())),
+        (b"QUIT", []) => Ok(ok()),
+        (b"HELLO", rest) => hello(session, rest),
+        (b"SELECT", [index]) => match int::<i64>(index)? {
+            0 => Ok(ok()),
+            _ => Err(error("ERR DB index is out of range")),
+        },
+        (b"CLIENT", [sub, rest @ ..]) => client(session, &sub.to_ascii_uppercase(), rest),
+        (b"COMMAND", _) => Ok(Model::Array(vec![])),
+        // server
+        (b"FLUSHALL" | b"FLUSHDB", [] | [_]) => {
+            db.entries.clear();
+            Ok(ok())
+        }
+        (b"DBSIZE", []) => Ok(Model::Integer(db.keys().len() as i64)),
+        (b"TIME", []) => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            Ok(Model::Array(vec![
+                bulk(now.as_secs().to_string()),
+                bulk(now.subsec_micros().to_string()),
+            ]))
+        }
+        // strings
+        (b"GET", [key]) => Ok(optional(db.string(key)?.cloned())),
+        (b"SET", [key, value, options @ ..]) => set(db, key, value, options),
+        (b"SETNX", [key, value]) => {
+            let written = !db.contains(key);
+            if written {
+                db.insert(key.clone(), Value::String(value.clone()), None);
+            }
+            Ok(Model::Integer(written as i64))
+        }
+        (b"SETEX", [key, secs, value]) => set_with_ttl(db, key, value, int::<i64>(secs)?, 1000),
+        (b"PSETEX", [key, millis, value]) => set_with_ttl(db, key, value, int::<i64>(millis)?, 1),
+        (b"GETSET", [key, value]) => {
+            let old = db.string(key)?.cloned();
+            db.insert(key.clone(), Value::String(value.clone()), None);
+            Ok(optional(old))
+        }
+        (b"GETDEL", [key]) => {
+            let old = db.string(key)?.cloned();
+            db.remove(key);
+            Ok(optional(old))
+        }
+        (b"GETEX", [key, options @ ..]) => get_ex(db, key, options),
+        (b"MGET", keys) if !keys.is_empty() => Ok(Model::Array(
+            keys.iter()
+                .map(|key| optional(db.string(key).ok().flatten().cloned()))
+                .collect(),
+        )),
+        (b"MSET", pairs) if !pairs.is_empty() && pairs.len() % 2 == 0 => {
+            for pair in pairs.chunks(2) {
+                db.insert(pair[0].clone(), Value::String(pair[1].clone()), None);
+            }
+            Ok(ok())
+        }
+        (b"MSETNX", pairs) if !pairs.is_empty() && pairs.len() % 2 == 0 => {
+            if pairs.chunks(2).any(|pair| db.contains(&pair[0])) {
+                return Ok(Model::Integer(0));
+            }
+            for pair in pairs.chunks(2) {
+                db.insert(pair[0].clone(), Value::String(pair[1].clone()), None);
+            }
+            Ok(Model::Integer(1))
+        }
+        (b"STRLEN", [key]) => Ok(Model::Integer(db.string(key)?.map_or(0, |s| s.len()) as i64)),
+        (b"GETRANGE" | b"SUBSTR", [key, start, end]) => {
+            let (start, end) = (int(start)?, int(end)?);
+            let s = db.string(key)?.cloned().unwrap_or_default();
+            Ok(match range(s.len(), start, end) {
+                Some((start, end)) => Model::String(s.slice(start..=end)),
+                None => Model::String(Bytes::new()),
+            })
+        }
+        (b"SETRANGE", [key, offset, value]) => {
+            let offset = int::<usize>(offset)?;
+            let old = db.string(key)?;
+            if old.is_none() && value.is_empty() {
+                return Ok(Model::Integer(0));
+            }
+            let end = offset
+                .checked_add(value.len())
+                .filter(|end| *end <= MAX_STRING_LEN)
+                .ok_or_else(too_long)?;
+            let mut s = BytesMut::from(&old.cloned().unwrap_or_default()[..]);
+            if s.len() < end {
+                s.resize(end, 0);
+            }
+            s[offset..end].copy_from_slice(value);
+            let len = s.len();
+            update_string(db, key, s.freeze());
+            Ok(Model::Integer(len as i64))
+        }
+        (b"APPEND", [key, value]) => {
+            let mut s = BytesMut::from(&db.string(key)?.cloned().unwrap_or_default()[..]);
+            if s.len() + value.len() > MAX_STRING_LEN {
+                return Err(too_long());
+            }
+            s.extend_from_slice(value);
+            let len = s.len();
+            update_string(db, key, s.freeze());
+            Ok(Model::Integer(len as i64))
+        }
+        (b"INCR", [key]) => incr_by(db, key, 1),
+        (b"DECR", [key]) => incr_by(db, key, -1),
+        (b"INCRBY", [key, by]) => incr_by(db, key, int(by)?),
+        (b"DECRBY", [key, by]) => {
+            incr_by(db, key, int::<i64>(by)?.checked_neg().ok_or_else(overflow)?)
+        }
+        (b"INCRBYFLOAT", [key, by]) => {
+            let by = float(by)?;
+            let value = match db.string(key)? {
+                Some(s) => float(s)?,
+                None => 0.0,
+            };
+            let value = value + by;
+            if !value.is_finite() {
+                return Err(error("ERR increment would produce NaN or Infinity"));
+            }
+            let value = Bytes::from(value.to_string());
+            update_string(db, key, value.clone());
+            Ok(Model::String(value))
+        }
+        // keys
+        (b"DEL" | b"UNLINK", keys) if !keys.is_empty() => Ok(Model::Integer(
+            keys.iter().filter(|key| db.remove(key).is_some()).count() as i64,
+        )),
+        (b"EXISTS" | b"TOUCH", keys) if !keys.is_empty() => Ok(Model::Integer(
+            keys.iter().filter(|key| db.contains(key)).count() as i64,
+        )),
+        (b"TYPE", [key]) => Ok(status(
+            db.entry(key)
+                .map_or("none", |entry| entry.value.type_name()),
+        )),
+        (b"RENAME", [src, dst]) => {
+            let entry = db.remove(src).ok_or_else(|| error("ERR no such key"))?;
+            db.entries.insert(dst.clone(), entry);
+            Ok(ok())
+        }
+        (b"RENAMENX", [src, dst]) => {
+            if !db.contains(src) {
+                return Err(error("ERR no such key"));
+            }
+            if db.contains(dst) {
+                return Ok(Model::Integer(0));
+            }
+            let entry = db.remove(src).unwrap();
+            db.entries.insert(dst.clone(), entry);
+            Ok(Model::Integer(1))
+        }
+        (b"COPY", [src, dst, options @ ..]) => copy(db, src, dst, options),
+        (b"KEYS", [pattern]) => Ok(Model::Array(
+            db.keys()
+                .into_iter()
+                .filter(|key| glob(pattern, key))
+                .map(Model::String)
+                .collect(),
+        )),
+        (b"SCAN", [cursor, options @ ..]) => {
+            let (pattern, count, kind) = scan_options(options)?;
+            let keys: Vec<Bytes> = db
+                .keys()
+                .into_iter()
+                .filter(|key| match &kind {
+                    Some(kind) => db.entry(key).is_some_and(|entry| {
+                        kind.eq_ignore_ascii_case(entry.value.type_name().as_bytes())
+                    }),
+                    None => true,
+                })
+                .collect();
+            Ok(scan(&keys, int(cursor)?, count, |key, out| {
+                if pattern.is_none_or(|pattern| glob(pattern, key)) {
+                    out.push(Model::String(key.clone()));
+                }
+            }))
+        }
+        (b"RANDOMKEY", []) => Ok(optional(db.keys().into_iter().next())),
+        // expiry
+        (b"EXPIRE", [key, secs, options @ ..]) => {
+            let at = now().saturating_add(int::<i64>(secs)?.saturating_mul(1000));
+            expire(db, key, at, options)
+        }
+        (b"PEXPIRE", [key, millis, options @ ..]) => {
+            let at = now().saturating_add(int(millis)?);
+            expire(db, key, at, options)
+        }
+        (b"EXPIREAT", [key, secs, options @ ..]) => {
+            expire(db, key, int::<i64>(secs)?.saturating_mul(1000), options)
+        }
+        (b"PEXPIREAT", [key, millis, options @ ..]) => expire(db, key, int(millis)?, options),
+        (b"TTL", [key]) => Ok(Model::Integer(ttl(db, key, |ms| (ms + 500) / 1000))),
+        (b"PTTL", [key]) => Ok(Model::Integer(ttl(db, key, |ms| ms))),
+        (b"EXPIRETIME", [key]) => Ok(Model::Integer(expire_time(db, key, 1000))),
+        (b"PEXPIRETIME", [key]) => Ok(Model::Integer(expire_time(db, key, 1))),
+        (b"PERSIST", [key]) => Ok(Model::Integer(match db.entry(key) {
+            Some(entry) => entry.expire_at.take().is_some() as i64,
+            None => 0,
+        })),
+        // hashes
+        (b"HSET" | b"HMSET", [key, pairs @ ..]) if !pairs.is_empty() && pairs.len() % 2 == 0 => {
+            let hash = db.hash_or_default(key)?;
+            let mut added = 0;
+            for pair in pairs.chunks(2) {
+                if hash.insert(pair[0].clone(), pair[1].clone()).is_none() {
+                    added += 1;
+                }
+            }
+            Ok(match name.as_slice() {
+                b"HSET" => Model::Integer(added),
+                _ => ok(),
+            })
+        }
+        (b"HSETNX", [key, field, value]) => {
+            let hash = db.hash_or_default(key)?;
+            let added = !hash.contains_key(field);
+            if added {
+                hash.insert(field.clone(), value.clone());
+            }
+            Ok(Model::Integer(added as i64))
+        }
+        (b"HGET", [key, field]) => Ok(optional(
+            db.hash(key)?.and_then(|hash| hash.get(field).cloned()),
+        )),
+        (b"HMGET", [key, fields @ ..]) if !fields.is_empty() => {
+            let hash = db.hash(key)?;
+            Ok(Model::Array(
+                fields
+                    .iter()
+                    .map(|field| optional(hash.as_ref().and_then(|hash| hash.get(field).cloned())))
+                    .collect(),
+            ))
+        }
+        (b"HGETALL", [key]) => Ok(Model::Map(
+            sorted(db.hash(key)?)
+                .into_iter()
+                .map(|(field, value)| (Model::String(field), Model::String(value)))
+                .collect(),
+        )),
+        (b"HKEYS", [key]) => Ok(Model::Array(
+            sorted(db.hash(key)?)
+                .into_iter()
+                .map(|(field, _)| Model::String(field))
+                .collect(),
+        )),
+        (b"HVALS", [key]) => Ok(Model::Array(
+            sorted(db.hash(key)?)
+                .into_iter()
+                .map(|(_, value)| Model::String(value))
+                .collect(),
+        )),
+        (b"HDEL", [key, fields @ ..]) if !fields.is_empty() => {
+            let removed = match db.hash(key)? {
+                Some(hash) => fields
+                    .iter()
+                    .filter(|field| hash.remove(*field).is_some())
+                    .count(),
+                None => 0,
+            };
+            db.drop_if_empty(key);
+            Ok(Model::Integer(removed as i64))
+        }
+        (b"HLEN", [key]) => Ok(Model::Integer(
+            db.hash(key)?.map_or(0, |hash| hash.len()) as i64
+        )),
+        (b"HEXISTS", [key, field]) => Ok(Model::Integer(
+            db.hash(key)?.is_some_and(|hash| hash.contains_key(field)) as i64,
+        )),
+        (b"HSTRLEN", [key, field]) => Ok(Model::Integer(
+            db.hash(key)?
+                .and_then(|hash| hash.get(field))
+                .map_or(0, |value| value.len()) as i64,
+        )),
+        (b"HINCRBY", [key, field, by]) => {
+            let by = int::<i64>(by)?;
+            let hash = db.hash_or_default(key)?;
+            let value = match hash.get(field) {
+                Some(value) => {
+                    int::<i64>(value).map_err(|_| error("ERR hash value is not an integer"))?
+                }
+                None => 0,
+            };
+            let value = value.checked_add(by).ok_or_else(overflow)?;
+            hash.insert(field.clone(), Bytes::from(value.to_string()));
+            Ok(Model::Integer(value))
+        }
+        (b"HINCRBYFLOAT", [key, field, by]) => {
+            let by = float(by)?;
+            let hash = db.hash_or_default(key)?;
+            let value = match hash.get(field) {
+                Some(value) => float(value).map_err(|_| error("ERR hash value is not a float"))?,
+                None => 0.0,
+            };
+            let value = Bytes::from((value + by).to_string());
+            hash.insert(field.clone(), value.clone());
+            Ok(Model::String(value))
+        }
+        (b"HSCAN", [key, cursor, options @ ..]) => {
+            let (pattern, count, _) = scan_options(options)?;
+            let fields = sorted(db.hash(key)?);
+            Ok(scan(&fields, int(cursor)?, count, |(field, value), out| {
+                if pattern.is_none_or(|pattern| glob(pattern, field)) {
+                    out.push(Model::String(field.clone()));
+                    out.push(Model::String(value.clone()));
+                }
+            }))
+        }
+        // lists
+        (b"LPUSH" | b"RPUSH" | b"LPUSHX" | b"RPUSHX", [key, values @ ..]) if !values.is_empty() => {
+            let list = match name.as_slice() {
+                b"LPUSHX" | b"RPUSHX" => match db.list(key)? {
+                    Some(list) => list,
+                    None => return Ok(Model::Integer(0)),
+                },
+                _ => db.list_or_default(key)?,
+            };
+            for value in values {
+                match name[0] {
+                    b'L' => list.push_front(value.clone()),
+                    _ => list.push_back(value.clone()),
+                }
+            }
+            Ok(Model::Integer(list.len() as i64))
+        }
+        (b"LPOP" | b"RPOP", [key, count @ ..]) if count.len() <= 1 => {
+            let count = count.first().map(|count| int::<usize>(count)).transpose()?;
+            let Some(list) = db.list(key)? else {
+                return Ok(Model::Nil);
+            };
+            let mut popped = vec![];
+            while popped.len() < count.unwrap_or(1) {
+                let value = match name[0] {
+                    b'L' => list.pop_front(),
+                    _ => list.pop_back(),
+                };
+                match value {
+                    Some(value) => popped.push(Model::String(value)),
+                    None => break,
+                }
+            }
+            db.drop_if_empty(key);
+            Ok(match count {
+                Some(_) => Model::Array(popped),
+                None => popped.pop().unwrap_or(Model::Nil),
+            })
+        }
+        (b"LLEN", [key]) => Ok(Model::Integer(
+            db.list(key)?.map_or(0, |list| list.len()) as i64
+        )),
+        (b"LRANGE", [key, start, stop]) => {
+            let (start, stop) = (int(start)?, int(stop)?);
+            let list = db.list(key)?.cloned().unwrap_or_default();
+            Ok(Model::Array(match range(list.len(), start, stop) {
+                Some((start, stop)) => list
+                    .range(start..=stop)
+                    .cloned()
+                    .map(Model::String)
+                    .collect(),
+                None => vec![],
+            }))
+        }
+        (b"LINDEX", [key, index]) => {
+            let index = int(index)?;
+            Ok(optional(db.list(key)?.and_then(|list| {
+                let index = position(list.len(), index)?;
+                list.get(index).cloned()
+            })))
+        }
+        (b"LSET", [key, index, value]) => {
+            let index = int(index)?;
+            let list = db.list(key)?.ok_or_else(|| error("ERR no such key"))?;
+            let index =
+                position(list.len(), index).ok_or_else(|| error("ERR index out of range"))?;
+            list[index] = value.clone();
+            Ok(ok())
+        }
+        (b"LREM", [key, count, value]) => {
+            let count = int::<i64>(count)?;
+            let Some(list) = db.list(key)? else {
+                return Ok(Model::Integer(0));
+            };
+            let limit = if count == 0 {
+                usize::MAX
+            } else {
+                count.unsigned_abs() as usize
+            };
+            let mut indexes: Vec<usize> = (0..list.len()).filter(|i| list[*i] == value).collect();
+            if count < 0 {
+                indexes.reverse();
+            }
+            indexes.truncate(limit);
+            indexes.sort_unstable();
+            for index in indexes.iter().rev() {
+                list.remove(*index);
+            }
+            db.drop_if_empty(key);
+            Ok(Model::Integer(indexes.len() as i64))
+        }
+        (b"LTRIM", [key, start, stop]) => {
+            let (start, stop) = (int(start)?, int(stop)?);
+            if let Some(list) = db.list(key)? {
+                match range(list.len(), start, stop) {
+                    Some((start, stop)) => {
+                        list.truncate(stop + 1);
+                        list.drain(..start);
+                    }
+                    None => list.clear(),
+                }
+            }
+            db.drop_if_empty(key);
+            Ok(ok())
+        }
+        (b"LINSERT", [key, whence, pivot, value]) => {
+            let after = match whence.to_ascii_uppercase().as_slice() {
+                b"BEFORE" => false,
+                b"AFTER" => true,
+                _ => return Err(syntax_error()),
+            };
+            let Some(list) = db.list(key)? else {
+                return Ok(Model::Integer(0));
+            };
+            match list.iter().position(|v| v == pivot) {
+                Some(index) => {
+                    list.insert(index + after as usize, value.clone());
+                    Ok(Model::Integer(list.len() as i64))
+                }
+                None => Ok(Model::Integer(-1)),
+            }
+        }
+        (b"LMOVE", [src, dst, from, to]) => lmove(db, src, dst, from, to),
+        (b"RPOPLPUSH", [src, dst]) => {
+            lmove(db, src, dst, &Bytes::from("RIGHT"), &Bytes::from("LEFT"))
+        }
+        _ => Err(Model::Error(format!(
+            "ERR unknown command or wrong number of arguments for '{}'",
+            String::from_utf8_lossy(&args[0])
+        ))),
+    }
+}
+
+fn hello(session: &mut Session, args: &[Bytes]) -> Outcome {
+    if let Some(version) = args.first() {
+        match int::<i64>(version) {
+            Ok(2) => session.resp3 = false,
+            Ok(3) => session.resp3 = true,
+            _ => return Err(error("NOPROTO unsupported protocol version")),
+        }
+    }
+    let mut options = args.iter().skip(1);
+    while let Some(option) = options.next() {
+        match option.to_ascii_uppercase().as_slice() {
+            b"AUTH" => {
+                options.next();
+                options.next();
+            }
+            b"SETNAME" => session.name = options.next().cloned(),
+            _ => return Err(syntax_error()),
+        }
+    }
+    let field = |name: &str| Model::String(Bytes::from(name.to_string()));
+    Ok(Model::Map(vec![
+        (field("server"), field("redis")),
+        (field("version"), field("7.2.0")),
+        (
+            field("proto"),
+            Model::Integer(if session.resp3 { 3 } else { 2 }),
+        ),
+        (field("id"), Model::Integer(session.id)),
+        (field("mode"), field("standalone")),
+        (field("role"), field("master")),
+        (field("modules"), Model::Array(vec![])),
+    ]))
+}
+
+fn client(session: &mut Session, sub: &[u8], args: &[Bytes]) -> Outcome {
+    match (sub, args) {
+        (b"SETINFO", [_, _]) => Ok(ok()),
+        (b"SETNAME", [name]) => {
+            session.name = Some(name.clone()).filter(|name| !name.is_empty());
+            Ok(ok())
+        }
+        (b"GETNAME", []) => Ok(optional(session.name.clone())),
+        (b"ID", []) => Ok(Model::Integer(session.id)),
+        _ => Err(Model::Error(format!(
+            "ERR unknown subcommand '{}'",
+            String::from_utf8_lossy(sub)
+        ))),
+    }
+}
+
+fn set(db: &mut Db, key: &Bytes, value: &Bytes, options: &[Bytes]) -> Outcome {
+    let (mut nx, mut xx, mut get, mut keep_ttl, mut expire_at) = (false, false, false, false, None);
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let option = option.to_ascii_uppercase();
+        match option.as_slice() {
+            b"NX" => nx = true,
+            b"XX" => xx = true,
+            b"GET" => get = true,
+            b"KEEPTTL" => keep_ttl = true,
+            b"EX" | b"PX" | b"EXAT" | b"PXAT" => {
+                let n = int::<i64>(options.next().ok_or_else(syntax_error)?)?;
+                if n <= 0 {
+                    return Err(error("ERR invalid expire time in 'set' command"));
+                }
+                expire_at = Some(match option.as_slice() {
+                    b"EX" => now().saturating_add(n.saturating_mul(1000)),
+                    b"PX" => now().saturating_add(n),
+                    b"EXAT" => n.saturating_mul(1000),
+                    _ => n,
+                });
+            }
+            _ => return Err(syntax_error()),
+        }
+    }
+    if (nx && xx) || (keep_ttl && expire_at.is_some()) {
+        return Err(syntax_error());
+    }
+    let old = match get {
+        true => Some(optional(db.string(key)?.cloned())),
+        false => None,
+    };
+    let exists = db.entry(key);
+    if (nx && exists.is_some()) || (xx && exists.is_none()) {
+        return Ok(old.unwrap_or(Model::Nil));
+    }
+    if keep_ttl {
+        expire_at = exists.and_then(|entry| entry.expire_at);
+    }
+    db.insert(key.clone(), Value::String(value.clone()), expire_at);
+    Ok(old.unwrap_or_else(ok))
+}
+
+fn set_with_ttl(db: &mut Db, key: &Bytes, value: &Bytes, ttl: i64, unit: i64) -> Outcome {
+    if ttl <= 0 {
+        return Err(error("ERR invalid expire time"));
+    }
+    let at = now().saturating_add(ttl.saturating_mul(unit));
+    db.insert(key.clone(), Value::String(value.clone()), Some(at));
+    Ok(ok())
+}
+
+fn get_ex(db: &mut Db, key: &Bytes, options: &[Bytes]) -> Outcome {
+    let value = db.string(key)?.cloned();
+    let expire_at = match options {
+        [] => return Ok(optional(value)),
+        [persist] if persist.eq_ignore_ascii_case(b"PERSIST") => None,
+        [unit, n] => {
+            let n = int::<i64>(n)?;
+            if n <= 0 {
+                return Err(error("ERR invalid expire time in 'getex' command"));
+            }
+            Some(match unit.to_ascii_uppercase().as_slice() {
+                b"EX" => now().saturating_add(n.saturating_mul(1000)),
+                b"PX" => now().saturating_add(n),
+                b"EXAT" => n.saturating_mul(1000),
+                b"PXAT" => n,
+                _ => return Err(syntax_error()),
+            })
+        }
+        _ => return Err(syntax_error()),
+    };
+    if let Some(entry) = db.entry(key) {
+        entry.expire_at = expire_at;
+    }
+    Ok(optional(value))
+}
+
+/// Replaces a string value, keeping its time to live.
+fn update_string(db: &mut Db, key: &Bytes, value: Bytes) {
+    match db.entry(key) {
+        Some(entry) => entry.value = Value::String(value),
+        None => db.insert(key.clone(), Value::String(value), None),
+    }
+}
+
+fn incr_by(db: &mut Db, key: &Bytes, by: i64) -> Outcome {
+    let value = match db.string(key)? {
+        Some(s) => int::<i64>(s)?,
+        None => 0,
+    };
+    let value = value.checked_add(by).ok_or_else(overflow)?;
+    update_string(db, key, Bytes::from(value.to_string()));
+    Ok(Model::Integer(value))
+}
+
+fn copy(db: &mut Db, src: &Bytes, dst: &Bytes, options: &[Bytes]) -> Outcome {
+    let mut replace = false;
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        match option.to_ascii_uppercase().as_slice() {
+            b"REPLACE" => replace = true,
+            b"DB" => {
+                if int::<i64>(options.next().ok_or_else(syntax_error)?)? != 0 {
+                    return Err(error("ERR DB index is out of range"));
+                }
+            }
+            _ => return Err(syntax_error()),
+        }
+    }
+    if src == dst {
+        return Err(error("ERR source and destination objects are the same"));
+    }
+    if !replace && db.contains(dst) {
+        return Ok(Model::Integer(0));
+    }
+    let Some(entry) = db.entry(src) else {
+        return Ok(Model::Integer(0));
+    };
+    let value = match &entry.value {
+        Value::String(s) => Value::String(s.clone()),
+        Value::Hash(hash) => Value::Hash(hash.clone()),
+        Value::List(list) => Value::List(list.clone()),
+    };
+    let expire_at = entry.expire_at;
+    db.insert(dst.clone(), value, expire_at);
+    Ok(Model::Integer(1))
+}
+
+fn expire(db: &mut Db, key: &Bytes, at: i64, options: &[Bytes]) -> Outcome {
+    let condition = match options {
+        [] => None,
+        [condition] => Some(condition.to_ascii_uppercase()),
+        _ => return Err(syntax_error()),
+    };
+    let Some(entry) = db.entry(key) else {
+        return Ok(Model::Integer(0));
+    };
+    let applies = match (condition.as_deref(), entry.expire_at) {
+        (None, _) => true,
+        (Some(b"NX"), current) => current.is_none(),
+        (Some(b"XX"), current) => current.is_some(),
+        (Some(b"GT"), current) => current.is_some_and(|current| at > current),
+        (Some(b"LT"), current) => current.is_none_or(|current| at < current),
+        _ => return Err(syntax_error()),
+    };
+    if !applies {
+        return Ok(Model::Integer(0));
+    }
+    if at <= now() {
+        db.remove(key);
+    } else {
+        entry.expire_at = Some(at);
+    }
+    Ok(Model::Integer(1))
+}
+
+fn ttl(db: &mut Db, key: &[u8], unit: impl Fn(i64) -> i64) -> i64 {
+    match db.entry(key) {
+        None => -2,
+        Some(Entry {
+            expire_at: None, ..
+        }) => -1,
+        Some(Entry {
+            expire_at: Some(at),
+            ..
+        }) => unit((*at - now()).max(0)),
+    }
+}
+
+fn expire_time(db: &mut Db, key: &[u8], unit: i64) -> i64 {
+    match db.entry(key) {
+        None => -2,
+        Some(entry) => entry.expire_at.map_or(-1, |at| at / unit),
+    }
+}
+
+fn lmove(db: &mut Db, src: &Bytes, dst: &Bytes, from: &Bytes, to: &Bytes) -> Outcome {
+    let left = |side: &Bytes| match side.to_ascii_uppercase().as_slice() {
+        b"LEFT" => Ok(true),
+        b"RIGHT" => Ok(false),
+        _ => Err(syntax_error()),
+    };
+    let (from, to) = (left(from)?, left(to)?);
+    if db.list(dst)?.is_none() && db.contains(dst) {
+        return Err(wrong_type());
+    }
+    let Some(list) = db.list(src)? else {
+        return Ok(Model::Nil);
+    };
+    let Some(value) = (if from {
+        list.pop_front()
+    } else {
+        list.pop_back()
+    }) else {
+        return Ok(Model::Nil);
+    };
+    db.drop_if_empty(src);
+    let list = db.list_or_default(dst)?;
+    match to {
+        true => list.push_front(value.clone()),
+        false => list.push_back(value.clone()),
+    }
+    Ok(Model::String(value))
+}
+
+/// The MATCH pattern, COUNT and TYPE of a scan.
+type ScanOptions<'a> = (Option<&'a [u8]>, usize, Option<&'a [u8]>);
+
+fn scan_options(options: &[Bytes]) -> std::result::Result<ScanOptions<'_>, Model> {
+    let (mut pattern, mut count, mut kind) = (None, 10, None);
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let value = options.next().ok_or_else(syntax_error)?;
+        match option.to_ascii_uppercase().as_slice() {
+            b"MATCH" => pattern = Some(&value[..]),
+            b"COUNT" => count = int::<usize>(value)?.max(1),
+            b"TYPE" => kind = Some(&value[..]),
+            _ => return Err(syntax_error()),
+        }
+    }
+    Ok((pattern, count, kind))
+}
+
+/// Visits `count` items from `cursor` on and replies with the next cursor
+/// along with what `visit` collected.
+fn scan<T>(
+    items: &[T],
+    cursor: usize,
+    count: usize,
+    mut visit: impl FnMut(&T, &mut Vec<Model>),
+) -> Model {
+    let end = cursor.saturating_add(count).min(items.len());
+    let mut out = vec![];
+    for item in items.get(cursor..end).unwrap_or_default() {
+        visit(item, &mut out);
+    }
+    let next = if end >= items.len() { 0 } else { end };
+    Model::Array(vec![bulk(next.to_string()), Model::Array(out)])
+}
+
+fn sorted(hash: Option<&mut HashMap<Bytes, Bytes>>) -> Vec<(Bytes, Bytes)> {
+    let mut pairs: Vec<(Bytes, Bytes)> = hash
+        .map(|hash| hash.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+        .unwrap_or_default();
+    pairs.sort();
+    pairs
+}
+
+/// Resolves an inclusive range with negative indexes counting from the end,
+/// or `None` if it is empty.
+fn range(len: usize, start: i64, stop: i64) -> Option<(usize, usize)> {
+    let len = len as i64;
+    let start = if start < 0 {
+        len.saturating_add(start).max(0)
+    } else {
+        start
+    };
+    let stop = if stop < 0 {
+        len.saturating_add(stop)
+    } else {
+        stop.min(len - 1)
+    };
+    if start > stop || start >= len {
+        None
+    } else {
+        Some((start as usize, stop as usize))
+    }
+}
+
+fn position(len: usize, index: i64) -> Option<usize> {
+    let index = if index < 0 {
+        (len as i64).saturating_add(index)
+    } else {
+        index
+    };
+    (0..len as i64).contains(&index).then_some(index as usize)
+}
+
+/// Matches like Redis glob-style patterns: `*`, `?`, `[...]` and `\`.
+fn glob(pattern: &[u8], s: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => s.is_empty(),
+        Some((b'*', rest)) => (0..=s.len()).any(|i| glob(rest, &s[i..])),
+        Some((b'?', rest)) => !s.is_empty() && glob(rest, &s[1..]),
+        Some((b'[', rest)) => {
+            let Some(end) = rest.iter().position(|b| *b == b']') else {
+                return s.first() == Some(&b'[') && glob(rest, &s[1..]);
+            };
+            let Some((c, tail)) = s.split_first() else {
+                return false;
+            };
+            let (negate, class) = match rest[..end].split_first() {
+                Some((b'^', class)) => (true, class),
+                _ => (false, &rest[..end]),
+            };
+            let mut matched = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == b'-' {
+                    matched |= (class[i]..=class[i + 2]).contains(c);
+                    i += 3;
+                } else {
+                    matched |= class[i] == *c;
+                    i += 1;
+                }
+            }
+            matched != negate && glob(&rest[end + 1..], tail)
+        }
+        Some((b'\\', rest)) if !rest.is_empty() => {
+            s.first() == Some(&rest[0]) && glob(&rest[1..], &s[1..])
+        }
+        Some((c, rest)) => s.first() == Some(c) && glob(rest, &s[1..]),
+    }
+}
+
+/// Downgrades RESP3 only types for a RESP2 connection.
+fn resp2(model: Model) -> Model {
+    match model {
+        Model::Map(pairs) => Model::Array(
+            pairs
+                .into_iter()
+                .flat_map(|(k, v)| [resp2(k), resp2(v)])
+                .collect(),
+        ),
+        Model::Array(models) | Model::Set(models) | Model::Push(models) => {
+            Model::Array(models.into_iter().map(resp2).collect())
+        }
+        Model::Double(d) => bulk(d.to_string()),
+        Model::Bool(b) => Model::Integer(b as i64),
+        Model::Verb(_, text) => Model::String(text),
+        Model::BigNum(i) => bulk(i.to_string()),
+        model => model,
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+fn int<T: FromStr>(arg: &[u8]) -> std::result::Result<T, Model> {
+    std::str::from_utf8(arg)
+        .ok()
+        .and_then(|arg| arg.parse().ok())
+        .ok_or_else(|| error("ERR value is not an integer or out of range"))
+}
+
+fn float(arg: &[u8]) -> std::result::Result<f64, Model> {
+    std::str::from_utf8(arg)
+        .ok()
+        .and_then(|arg| arg.parse::<f64>().ok())
+        .filter(|f| !f.is_nan())
+        .ok_or_else(|| error("ERR value is not a valid float"))
+}
+
+fn ok() -> Model {
+    status("OK")
+}
+
+fn status(status: &str) -> Model {
+    Model::Status(status.to_string())
+}
+
+fn bulk(s: String) -> Model {
+    Model::String(Bytes::from(s))
+}
+
+fn optional(value: Option<Bytes>) -> Model {
+    value.map_or(Model::Nil, Model::String)
+}
+
+fn too_long() -> Model {
+    error("ERR string exceeds maximum allowed size (proto-max-bulk-len)")
+}
+
+fn error(e: &str) -> Model {
+    Model::Error(e.to_string())
+}
+
+fn syntax_error() -> Model {
+    error("ERR syntax error")
+}
+
+fn overflow() -> Model {
+    error("ERR increment or decrement would overflow")
+}
+
+fn wrong_type() -> Model {
+    error("WRONGTYPE Operation against a key holding the wrong kind of value")
+}
@@ -0,0 +1,291 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Spans and events for the `tracing` feature.
+//!
+//! Every command gets a span from when it is written until its reply is read,
+//! so pipelined commands overlap. Spans are not entered, and thus parented by
+//! whatever span is current where the command is issued.
+
+use std::{
+    collections::{hash_map::RandomState, VecDeque},
+    hash::BuildHasher,
+    sync::OnceLock,
+};
+
+use tracing::{field::Empty, Span};
+
+use crate::{command::leading_args, Error, Model};
+
+/// How command spans show the key a command operates on.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TraceKeys {
+    /// Keys are left out.
+    #[default]
+    Omit,
+    /// Keys are replaced by a hash keyed at random once per process, so that
+    /// spans on the same key can still be correlated without revealing it, nor
+    /// letting a guessed key be checked against it.
+    Redact,
+    /// Keys are recorded as they are.
+    Record,
+}
+
+pub(crate) struct Tracer {
+    addr: String,
+    db: u64,
+    keys: TraceKeys,
+    pending: VecDeque<Pending>,
+}
+
+struct Pending {
+    span: Span,
+    select: Option<u64>,
+}
+
+impl Tracer {
+    pub(crate) fn new() -> Self {
+        Tracer {
+            addr: String::new(),
+            db: 0,
+            keys: TraceKeys::default(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn connected(&mut self, addr: String) {
+        tracing::debug!(server.address = %addr, "connected");
+        self.addr = addr;
+    }
+
+    pub(crate) fn set_keys(&mut self, keys: TraceKeys) {
+        self.keys = keys;
+    }
+
+    /// Opens the span of the command `name`, encoded in `encoded`, which is
+    /// only decoded for the key or database it may have to record.
+    pub(crate) fn start(&mut self, name: &'static str, encoded: &[u8]) {
+        let keyed = self.keys != TraceKeys::Omit && KEYED.contains(&name);
+        let args = match keyed || name == "SELECT" {
+            true => leading_args(encoded, 2),
+            false => vec![],
+        };
+        let span = tracing::info_span!(
+            "redis.command",
+            otel.name = name,
+            otel.status_code = Empty,
+            db.system = "redis",
+            db.operation = name,
+            db.redis.database_index = self.db,
+            db.redis.key = Empty,
+            server.address = %self.addr,
+            reply.type = Empty,
+            error.kind = Empty,
+        );
+        let key = args.get(1).filter(|_| keyed);
+        match (self.keys, key) {
+            (TraceKeys::Redact, Some(key)) => {
+                span.record("db.redis.key", format!("{:016x}", redact(key)));
+            }
+            (TraceKeys::Record, Some(key)) => {
+                span.record("db.redis.key", String::from_utf8_lossy(key).as_ref());
+            }
+            _ => {}
+        }
+        let select = match (name, args.get(1)) {
+            ("SELECT", Some(db)) => atoi::atoi(db),
+            _ => None,
+        };
+        self.pending.push_back(Pending { span, select });
+    }
+
+    /// Closes the span of the oldest command with its reply.
+    pub(crate) fn finish(&mut self, reply: &Model) {
+        let pending = match self.pending.pop_front() {
+            Some(pending) => pending,
+            None => return,
+        };
+        pending.span.record("reply.type", reply_type(reply));
+        match reply {
+            Model::Error(e) => {
+                let kind = e.split_whitespace().next().unwrap_or_default();
+                pending.span.record("error.kind", kind);
+                pending.span.record("otel.status_code", "ERROR");
+            }
+            Model::Status(_) => {
+                if let Some(db) = pending.select {
+                    self.db = db;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Closes the spans of every command in flight, since their replies are
+    /// lost along with the connection.
+    pub(crate) fn fail(&mut self, kind: &str) {
+        for pending in self.pending.drain(..) {
+            pending.span.record("error.kind", kind);
+            pending.span.record("otel.status_code", "ERROR");
+        }
+    }
+
+    pub(crate) fn protocol_error(&self, e: &Error) {
+        if let Error::Protocol(reason) = e {
+            tracing::warn!(server.address = %self.addr, reason = %reason, "protocol error");
+        }
+    }
+}
+
+pub(crate) fn error_kind(e: &Error) -> &'static str {
+    match e {
+        Error::Io(_) => "io",
+        Error::Internal(_) => "internal",
+        Error::Server(_) => "server",
+        Error::Protocol(_) => "protocol",
+        Error::InvalidArgument(_) => "argument",
+    }
+}
+
+fn reply_type(model: &Model) -> &'static str {
+    match model {
+        Model::Error(_) => "error",
+        Model::Status(_) => "simple",
+        Model::Integer(_) => "integer",
+        Model::Double(_) => "double",
+        Model::Nil => "null",
+        Model::String(_) => "bulk",
+        Model::Array(_) => "array",
+        Model::Map(_) => "map",
+        Model::Set(_) => "set",
+        Model::Bool(_) => "boolean",
+        Model::Verb(..) => "verbatim",
+        Model::Push(_) => "push",
+        Model::BigNum(_) => "bignum",
+    }
+}
+
+fn redact(key: &[u8]) -> u64 {
+    static HASHER: OnceLock<RandomState> = OnceLock::new();
+    HASHER.get_or_init(RandomState::new).hash_one(key)
+}
+
+/// Commands whose first argument is a key.
+const KEYED: &[&str] = &[
+    "APPEND",
+    "BITCOUNT",
+    "BITFIELD",
+    "BITFIELD_RO",
+    "BITPOS",
+    "COPY",
+    "DECR",
+    "DECRBY",
+    "DEL",
+    "DUMP",
+    "EXISTS",
+    "EXPIRE",
+    "EXPIREAT",
+    "EXPIRETIME",
+    "GEOADD",
+    "GEODIST",
+    "GEOHASH",
+    "GEOPOS",
+    "GEOSEARCH",
+    "GEOSEARCHSTORE",
+    "GET",
+    "GETBIT",
+    "GETDEL",
+    "GETEX",
+    "GETRANGE",
+    "GETSET",
+    "HDEL",
+    "HEXISTS",
+    "HGET",
+    "HGETALL",
+    "HINCRBY",
+    "HINCRBYFLOAT",
+    "HKEYS",
+    "HLEN",
+    "HMGET",
+    "HMSET",
+    "HSCAN",
+    "HSET",
+    "HSETNX",
+    "HSTRLEN",
+    "HVALS",
+    "INCR",
+    "INCRBY",
+    "INCRBYFLOAT",
+    "LCS",
+    "LINDEX",
+    "LINSERT",
+    "LLEN",
+    "LPOP",
+    "LPOS",
+    "LPUSH",
+    "LPUSHX",
+    "LRANGE",
+    "LREM",
+    "LSET",
+    "LTRIM",
+    "MGET",
+    "MOVE",
+    "MSET",
+    "MSETNX",
+    "PERSIST",
+    "PEXPIRE",
+    "PEXPIREAT",
+    "PEXPIRETIME",
+    "PFADD",
+    "PFCOUNT",
+    "PFMERGE",
+    "PSETEX",
+    "PTTL",
+    "RENAME",
+    "RENAMENX",
+    "RESTORE",
+    "RPOP",
+    "RPUSH",
+    "RPUSHX",
+    "SADD",
+    "SCARD",
+    "SET",
+    "SETBIT",
+    "SETEX",
+    "SETNX",
+    "SETRANGE",
+    "SISMEMBER",
+    "SMEMBERS",
+    "SORT",
+    "SORT_RO",
+    "SPOP",
+    "SRANDMEMBER",
+    "SREM",
+    "SSCAN",
+    "STRLEN",
+    "SUBSTR",
+    "TOUCH",
+    "TTL",
+    "TYPE",
+    "UNLINK",
+    "ZADD",
+    "ZCARD",
+    "ZCOUNT",
+    "ZINCRBY",
+    "ZRANGE",
+    "ZRANK",
+    "ZREM",
+    "ZSCAN",
+    "ZSCORE",
+];
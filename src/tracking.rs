@@ -0,0 +1,316 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+
+use crate::{
+    client::{identify, match_failure},
+    command::{ClientId, ClientTracking, Hello, Subscribe},
+    runtime, Client, Connection, Error, Model, Result,
+};
+
+const INVALIDATE_CHANNEL: &str = "__redis__:invalidate";
+
+/// How the server delivers invalidation messages.
+pub enum Protocol {
+    /// Push frames on the tracked connection itself.
+    Resp3,
+    /// Messages on `__redis__:invalidate` through a second connection.
+    Resp2,
+}
+
+/// Connects a [`Client`] that serves GET and MGET from a local cache kept
+/// consistent by CLIENT TRACKING.
+///
+/// The cache holds at most `capacity` keys, evicting the least recently used,
+/// and an entry is dropped after `ttl` even without an invalidation. Losing the
+/// connection that carries invalidations flushes the cache and disables it.
+pub struct CachingClient {
+    addr: String,
+    protocol: Protocol,
+    capacity: usize,
+    ttl: Option<Duration>,
+    bcast: bool,
+    prefixes: Vec<Vec<u8>>,
+}
+
+impl CachingClient {
+    pub fn new<A: Into<String>>(addr: A) -> Self {
+        CachingClient {
+            addr: addr.into(),
+            protocol: Protocol::Resp3,
+            capacity: 10000,
+            ttl: None,
+            bcast: false,
+            prefixes: vec![],
+        }
+    }
+
+    pub fn protocol(self, protocol: Protocol) -> Self {
+        CachingClient { protocol, ..self }
+    }
+
+    pub fn capacity(self, capacity: usize) -> Self {
+        CachingClient { capacity, ..self }
+    }
+
+    pub fn ttl(self, ttl: Duration) -> Self {
+        CachingClient {
+            ttl: Some(ttl),
+            ..self
+        }
+    }
+
+    /// Be notified of every change to the tracked prefixes instead of only the
+    /// keys read through this client.
+    pub fn bcast(self, bcast: bool) -> Self {
+        CachingClient { bcast, ..self }
+    }
+
+    /// Only cache keys under `prefix`; implies BCAST mode.
+    pub fn prefix<P: Into<Vec<u8>>>(mut self, prefix: P) -> Self {
+        self.bcast = true;
+        self.prefixes.push(prefix.into());
+        self
+    }
+
+    pub async fn connect(self) -> Result<Client> {
+        let redirect = match self.protocol {
+            Protocol::Resp3 => None,
+            Protocol::Resp2 => {
+                let mut connection = runtime::connect(self.addr.as_str()).await?;
+                connection.send(&ClientId).await?;
+                let id = match connection.recv().await? {
+                    Some(Model::Integer(id)) if id >= 0 => id as u64,
+                    model => return match_failure(model),
+                };
+                connection
+                    .send(&Subscribe::new(vec![INVALIDATE_CHANNEL.into()]))
+                    .await?;
+                match connection.recv().await? {
+                    Some(Model::Array(_)) => {}
+                    model => return match_failure(model),
+                }
+                Some((id, connection))
+            }
+        };
+
+        let mut connection = runtime::connect(self.addr.as_str()).await?;
+        if let Protocol::Resp3 = self.protocol {
+            connection.send(&Hello::new(3)).await?;
+            match connection.recv().await? {
+                Some(Model::Map(_)) => {}
+                model => return match_failure(model),
+            }
+        }
+        identify(&mut connection).await?;
+        let redirect_id = redirect.as_ref().map(|(id, _)| *id);
+        let tracking = ClientTracking::new(true, redirect_id, self.bcast, self.prefixes.clone());
+        connection.send(&tracking).await?;
+        match connection.recv().await? {
+            Some(Model::Status(status)) if status.eq_ignore_ascii_case("OK") => {}
+            model => return match_failure(model),
+        }
+
+        let cache = Cache {
+            entries: Entries::new(self.capacity, self.ttl),
+            prefixes: self.prefixes,
+            resp2: redirect.is_some(),
+            redirect: redirect.map(|(_, connection)| connection),
+        };
+        Ok(Client::with_cache(connection, cache))
+    }
+}
+
+pub(crate) struct Cache {
+    entries: Entries,
+    prefixes: Vec<Vec<u8>>,
+    resp2: bool,
+    redirect: Option<Connection>,
+}
+
+impl Cache {
+    /// Applies the invalidations that have arrived so far.
+    pub(crate) fn sync(&mut self, connection: &mut Connection) -> Result<()> {
+        for push in connection.take_pushes() {
+            self.entries.on_push(push);
+        }
+        match self.redirect.as_mut() {
+            Some(redirect) => loop {
+                match redirect.try_recv() {
+                    // A message is [message, channel, keys], where keys is nil on FLUSHALL.
+                    Ok(Some(Model::Array(mut message))) if message.len() == 3 => {
+                        self.entries.invalidate(message.pop().unwrap());
+                    }
+                    Ok(Some(_)) => {}
+                    Ok(None) => break,
+                    Err(_) => {
+                        self.redirect = None;
+                        self.disable();
+                        break;
+                    }
+                }
+            },
+            None => loop {
+                match connection.try_recv() {
+                    Ok(Some(Model::Push(push))) => self.entries.on_push(push),
+                    Ok(Some(model)) => {
+                        self.disable();
+                        return Err(Error::Internal(format!("unexpected reply: {:?}", model)));
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        self.disable();
+                        return Err(e);
+                    }
+                }
+            },
+        }
+        Ok(())
+    }
+
+    pub(crate) fn disable(&mut self) {
+        self.entries.disable();
+    }
+
+    pub(crate) fn get(&mut self, key: &[u8]) -> Option<Option<Bytes>> {
+        self.entries.get(key)
+    }
+
+    /// A token to pass to [`Cache::insert`]. With a redirect connection, an
+    /// invalidation may race with the read, and then the value is discarded.
+    pub(crate) fn epoch(&self) -> u64 {
+        self.entries.epoch
+    }
+
+    pub(crate) fn insert(&mut self, epoch: u64, key: Vec<u8>, value: Option<Bytes>) {
+        let tracked = self.prefixes.is_empty() || self.prefixes.iter().any(|p| key.starts_with(p));
+        let raced = self.resp2 && epoch != self.entries.epoch;
+        if tracked && !raced {
+            self.entries.insert(key, value);
+        }
+    }
+}
+
+struct Entry {
+    value: Option<Bytes>,
+    expire_at: Option<Instant>,
+    tick: u64,
+}
+
+struct Entries {
+    capacity: usize,
+    ttl: Option<Duration>,
+    enabled: bool,
+    epoch: u64,
+    tick: u64,
+    map: HashMap<Vec<u8>, Entry>,
+    order: BTreeMap<u64, Vec<u8>>,
+}
+
+impl Entries {
+    fn new(capacity: usize, ttl: Option<Duration>) -> Self {
+        Entries {
+            capacity,
+            ttl,
+            enabled: true,
+            epoch: 0,
+            tick: 0,
+            map: HashMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    fn get(&mut self, key: &[u8]) -> Option<Option<Bytes>> {
+        let entry = self.map.get_mut(key)?;
+        if entry.expire_at.is_some_and(|at| at <= Instant::now()) {
+            let tick = entry.tick;
+            self.map.remove(key);
+            self.order.remove(&tick);
+            return None;
+        }
+        self.tick += 1;
+        let key = self.order.remove(&entry.tick).unwrap();
+        entry.tick = self.tick;
+        self.order.insert(self.tick, key);
+        Some(entry.value.clone())
+    }
+
+    fn insert(&mut self, key: Vec<u8>, value: Option<Bytes>) {
+        if !self.enabled || self.capacity == 0 {
+            return;
+        }
+        self.remove(&key);
+        while self.map.len() >= self.capacity {
+            match self.order.pop_first() {
+                Some((_, oldest)) => self.map.remove(&oldest),
+                None => break,
+            };
+        }
+        self.tick += 1;
+        let entry = Entry {
+            value,
+            expire_at: self.ttl.map(|ttl| Instant::now() + ttl),
+            tick: self.tick,
+        };
+        self.order.insert(self.tick, key.clone());
+        self.map.insert(key, entry);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        if let Some(entry) = self.map.remove(key) {
+            self.order.remove(&entry.tick);
+        }
+    }
+
+    fn on_push(&mut self, mut push: Vec<Model>) {
+        let is_invalidate = matches!(
+            push.first(),
+            Some(Model::String(kind)) if kind == &b"invalidate"[..]
+        );
+        if is_invalidate && push.len() == 2 {
+            self.invalidate(push.pop().unwrap());
+        }
+    }
+
+    fn invalidate(&mut self, keys: Model) {
+        self.epoch += 1;
+        match keys {
+            Model::Array(keys) => {
+                for key in keys {
+                    if let Model::String(key) = key {
+                        self.remove(&key);
+                    }
+                }
+            }
+            _ => self.clear(),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        self.order.clear();
+    }
+
+    fn disable(&mut self) {
+        self.enabled = false;
+        self.epoch += 1;
+        self.clear();
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use aredis::{
+    command::{BitFieldOffset, BitFieldOption, BitFieldType, BitOperation, BitRange, Overflow},
+    Error,
+};
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_bitmap() -> Result<()> {
+    let mut client = crate::client().await?;
+    let got = client.set_bit("key", 7, true).await?;
+    assert!(!got);
+    let got = client.get_bit("key", 7).await?;
+    assert!(got);
+    let got = client.get_bit("key", 100).await?;
+    assert!(!got);
+
+    client.set_bit("key", 9, true).await?;
+    let got = client.bit_count("key", BitRange::All).await?;
+    assert_eq!(got, 2);
+    let got = client.bit_count("key", BitRange::Byte(1, 1)).await?;
+    assert_eq!(got, 1);
+    let got = client.bit_count("key", BitRange::Bit(0, 7)).await?;
+    assert_eq!(got, 1);
+    let got = client.bit_pos("key", true, BitRange::All).await?;
+    assert_eq!(got, Some(7));
+    let got = client.bit_pos("key", true, BitRange::Bit(10, 15)).await?;
+    assert_eq!(got, None);
+
+    client.set_bit("other", 0, true).await?;
+    let got = client
+        .bit_op(BitOperation::Or, "dest", vec!["key", "other"])
+        .await?;
+    assert_eq!(got, 2);
+    let got: Option<Vec<u8>> = client.get("dest").await?;
+    assert_eq!(got.unwrap(), vec![0x81, 0x40]);
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_bitfield() -> Result<()> {
+    let mut client = crate::client().await?;
+    let option = BitFieldOption::default()
+        .incr_by(BitFieldType::signed(5)?, 100, 1)
+        .get(BitFieldType::unsigned(4)?, 0);
+    let got = client.bit_field("key", option).await?;
+    assert_eq!(got, vec![Some(1), Some(0)]);
+
+    let option = BitFieldOption::default()
+        .set(BitFieldType::unsigned(8)?, BitFieldOffset::Index(1), 250)
+        .overflow(Overflow::Sat)
+        .incr_by(BitFieldType::unsigned(8)?, BitFieldOffset::Index(1), 10)
+        .overflow(Overflow::Fail)
+        .incr_by(BitFieldType::unsigned(8)?, BitFieldOffset::Index(1), 10);
+    let got = client.bit_field("counters", option).await?;
+    assert_eq!(got, vec![Some(0), Some(255), None]);
+
+    let option = BitFieldOption::default().get(BitFieldType::unsigned(8)?, 8);
+    let got = client.bit_field_ro("counters", option).await?;
+    assert_eq!(got, vec![Some(255)]);
+
+    assert!(matches!(
+        BitFieldType::signed(0),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(BitFieldType::signed(64).is_ok());
+    assert!(matches!(
+        BitFieldType::signed(65),
+        Err(Error::InvalidArgument(_))
+    ));
+    match BitFieldType::unsigned(64) {
+        Err(Error::InvalidArgument(e)) => assert_eq!(e, "invalid bitfield type u64"),
+        got => panic!("unexpected: {:?}", got.map(|t| t.to_string())),
+    }
+    Ok(())
+}
@@ -0,0 +1,85 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use aredis::{
+    blocking::Client,
+    command::{ScanOption, SetOption},
+    Limits, Model,
+};
+
+#[test]
+#[serial_test::serial]
+fn test_blocking() -> Result<()> {
+    let mut client = Client::connect(crate::addr())?;
+    client.flush_all(true)?;
+    client.ping()?;
+
+    client.mset(vec![("a", "1"), ("b", "2")])?;
+    let got: Option<Vec<u8>> = client.get("a")?;
+    assert_eq!(got, Some(b"1".to_vec()));
+    let got = client.incr_by("b", 40)?;
+    assert_eq!(got, 42);
+
+    let mut got: Vec<Vec<u8>> = client
+        .scan(ScanOption::default().dedupe())
+        .collect::<aredis::Result<_>>()?;
+    got.sort();
+    assert_eq!(got, vec![b"a".to_vec(), b"b".to_vec()]);
+
+    let got = client.call(vec!["GET", "b"])?;
+    assert!(matches!(got, Model::String(value) if value == "42"));
+    let got = client.call_all(vec![vec!["DEL", "a"], vec!["GET", "a"]])?;
+    assert!(matches!(got.as_slice(), [Model::Integer(1), Model::Nil]));
+    Ok(())
+}
+
+#[test]
+#[serial_test::serial]
+fn test_blocking_from_stream() -> Result<()> {
+    let stream = std::net::TcpStream::connect(crate::addr())?;
+    let mut client = Client::from_stream(stream)?;
+    client.set("key", "value", SetOption::default())?;
+    let got: Option<Vec<u8>> = client.get("key")?;
+    assert_eq!(got, Some(b"value".to_vec()));
+    Ok(())
+}
+
+#[test]
+#[serial_test::serial]
+fn test_blocking_streaming() -> Result<()> {
+    let mut client = Client::connect(crate::addr())?;
+    client.flush_all(true)?;
+    let value = vec![7u8; 100 * 1024];
+    assert!(client.set_from(
+        "blob",
+        &mut &value[..],
+        value.len() as u64,
+        SetOption::default()
+    )?);
+    let len = client.append_from("blob", &mut &b"tail"[..], 4)?;
+    assert_eq!(len, value.len() as u64 + 4);
+
+    let mut got = vec![];
+    let len = client.get_to("blob", &mut got)?;
+    assert_eq!(len, Some(value.len() as u64 + 4));
+    assert_eq!(got, [&value[..], b"tail"].concat());
+    let mut dumped = vec![];
+    let len = client.dump_to("blob", &mut dumped)?;
+    assert_eq!(len, Some(dumped.len() as u64));
+
+    client.set_limits(Limits::default().max_buffered(64 * 1024));
+    assert!(client.get::<_, Vec<u8>>("blob").is_err());
+    Ok(())
+}
@@ -0,0 +1,119 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::process::Stdio;
+
+use anyhow::Result;
+use aredis::{testing::InMemoryServer, Client, Model};
+use tokio::{io::AsyncWriteExt, process::Command};
+
+/// Runs aredis-cli against `server` and returns whether it succeeded along
+/// with what it printed.
+async fn cli(server: &InMemoryServer, args: &[&str], stdin: &str) -> Result<(bool, String)> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_aredis-cli"))
+        .arg("-u")
+        .arg(format!("redis://{}/0", server.addr()))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .await?;
+    let output = child.wait_with_output().await?;
+    Ok((output.status.success(), String::from_utf8(output.stdout)?))
+}
+
+#[tokio::test]
+async fn test_call() -> Result<()> {
+    let server = InMemoryServer::start().await?;
+    let mut client = Client::connect(server.addr()).await?;
+    assert!(matches!(
+        client.call(vec!["SET", "key", "value"]).await?,
+        Model::Status(status) if status == "OK"
+    ));
+    assert!(matches!(
+        client.call(vec!["NOSUCH"]).await?,
+        Model::Error(_)
+    ));
+    let replies = client
+        .call_all(vec![
+            vec!["GET", "key"],
+            vec!["DEL", "key"],
+            vec!["GET", "key"],
+        ])
+        .await?;
+    assert!(matches!(
+        replies.as_slice(),
+        [Model::String(value), Model::Integer(1), Model::Nil] if value == "value"
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cli() -> Result<()> {
+    let server = InMemoryServer::start().await?;
+    assert_eq!(
+        cli(&server, &["SET", "key", "a b"], "").await?,
+        (true, "OK\n".to_string())
+    );
+    assert_eq!(
+        cli(&server, &["GET", "key"], "").await?,
+        (true, "\"a b\"\n".to_string())
+    );
+    assert_eq!(
+        cli(&server, &["--json", "GET", "key"], "").await?,
+        (true, "\"a b\"\n".to_string())
+    );
+    assert!(!cli(&server, &["INCR", "key"], "").await?.0);
+
+    let piped = "RPUSH list x \"y\\nz\"\nRPUSH list 'w'\nNOSUCH\n";
+    assert_eq!(
+        cli(&server, &["--pipe"], piped).await?,
+        (false, "errors: 1, replies: 3\n".to_string())
+    );
+    assert_eq!(
+        cli(&server, &["LRANGE", "list", "0", "-1"], "").await?,
+        (true, "1) \"x\"\n2) \"y\\nz\"\n3) \"w\"\n".to_string())
+    );
+    assert_eq!(
+        cli(&server, &["--json", "LRANGE", "list", "0", "-1"], "").await?,
+        (true, "[\"x\",\"y\\nz\",\"w\"]\n".to_string())
+    );
+    let (ok, hello) = cli(&server, &["-3", "--json", "HELLO", "3"], "").await?;
+    assert!(ok && hello.starts_with('{') && hello.contains("\"proto\":3"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cli_url_credentials() -> Result<()> {
+    use aredis::testing::MockServer;
+
+    let server = MockServer::handler(|_| Model::Status("OK".to_string())).await?;
+    let status = Command::new(env!("CARGO_BIN_EXE_aredis-cli"))
+        .arg("-u")
+        .arg(format!("redis://us%65r:p%40ss@{}", server.addr()))
+        .arg("PING")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await?;
+    assert!(status.success());
+    assert_eq!(server.commands()[0], vec!["AUTH", "user", "p@ss"]);
+    Ok(())
+}
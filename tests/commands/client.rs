@@ -0,0 +1,124 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use aredis::command::{ClientKillOption, ClientType, SetOption};
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_client_identity() -> Result<()> {
+    let mut client = crate::client().await?;
+    let got: Option<Vec<u8>> = client.client_get_name().await?;
+    assert_eq!(got, None);
+    client.client_set_name("aredis-test").await?;
+    let got: Option<Vec<u8>> = client.client_get_name().await?;
+    assert_eq!(got, Some(b"aredis-test".to_vec()));
+
+    let id = client.client_id().await?;
+    let got = client.client_info().await?;
+    assert_eq!(got.id, id);
+    assert_eq!(got.name.as_deref(), Some("aredis-test"));
+    assert_eq!(got.lib_name.as_deref(), Some(env!("CARGO_PKG_NAME")));
+    assert_eq!(got.lib_ver.as_deref(), Some(env!("CARGO_PKG_VERSION")));
+
+    let got = client.client_list(Some(ClientType::Normal)).await?;
+    assert!(got.iter().any(|info| info.id == id));
+    Ok(())
+}
+
+#[test]
+fn test_client_futures_are_send() {
+    fn send<T: Send>(_: T) {}
+    let _ = |client: &mut aredis::Client| {
+        send(client.ping());
+        send(client.get::<_, Vec<u8>>("key"));
+        send(client.set("key", "value", SetOption::default()));
+    };
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_client_kill() -> Result<()> {
+    let mut client = crate::client().await?;
+    let mut victim = crate::client().await?;
+    let id = victim.client_id().await?;
+
+    let got = client
+        .client_kill(ClientKillOption::default().id(id))
+        .await?;
+    assert_eq!(got, 1);
+    assert!(victim.ping().await.is_err());
+
+    let id = client.client_id().await?;
+    let got = client
+        .client_kill(ClientKillOption::default().id(id))
+        .await?;
+    assert_eq!(got, 0);
+    Ok(())
+}
+
+#[cfg(feature = "testing")]
+#[tokio::test]
+async fn test_client_kill_max_age() -> Result<()> {
+    use std::time::Duration;
+
+    use aredis::{testing::MockServer, Model};
+
+    let server = MockServer::scripted(vec![Model::Integer(0)]).await?;
+    let mut client = aredis::Client::connect(server.addr()).await?;
+    let option = ClientKillOption::default().max_age(Duration::from_millis(500));
+    assert_eq!(client.client_kill(option).await?, 0);
+    let commands = server.commands();
+    assert_eq!(
+        commands.last().unwrap(),
+        &vec!["CLIENT", "KILL", "MAXAGE", "1"]
+    );
+    Ok(())
+}
+
+#[cfg(feature = "testing")]
+#[tokio::test]
+async fn test_client_reply() -> Result<()> {
+    use aredis::{command::ClientReplyMode, testing::MockServer, Error, Model};
+
+    let server = MockServer::handler(|args| match &args[0][..] {
+        b"PING" => Model::Status("PONG".to_string()),
+        _ => Model::Status("OK".to_string()),
+    })
+    .await?;
+    let mut client = aredis::Client::connect(server.addr()).await?;
+    client.client_reply(ClientReplyMode::On).await?;
+    for mode in [ClientReplyMode::Off, ClientReplyMode::Skip] {
+        let got = client.client_reply(mode).await;
+        assert!(matches!(got, Err(Error::InvalidArgument(_))));
+    }
+    // nothing was sent, so the client still gets its replies
+    client.ping().await?;
+    let commands = server.commands();
+    assert_eq!(commands.len(), 2);
+    assert_eq!(commands[0], vec!["CLIENT", "REPLY", "ON"]);
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_client_spawn() -> Result<()> {
+    let mut client = crate::client().await?;
+    let handle = tokio::spawn(async move {
+        client.set("spawned", "value", SetOption::default()).await?;
+        client.get::<_, Vec<u8>>("spawned").await
+    });
+    assert_eq!(handle.await??, Some(b"value".to_vec()));
+    Ok(())
+}
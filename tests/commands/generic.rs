@@ -0,0 +1,132 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use aredis::command::{
+    ExpireCondition, Expiry, KeyType, RestoreOption, ScanOption, SetOption, SortOption,
+};
+use futures::TryStreamExt;
+
+use crate::Utf8String;
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_expire() -> Result<()> {
+    let mut client = crate::client().await?;
+    let got = client.ttl("key").await?;
+    assert_eq!(got, Expiry::Missing);
+
+    client.set("key", "value", SetOption::default()).await?;
+    let got = client.ttl("key").await?;
+    assert_eq!(got, Expiry::Persistent);
+
+    let ttl = Duration::from_secs(100);
+    let got = client.expire("key", ttl, ExpireCondition::Nx).await?;
+    assert!(got);
+    let got = client.expire("key", ttl, ExpireCondition::Nx).await?;
+    assert!(!got);
+    let got = client.ttl("key").await?;
+    assert_eq!(got, Expiry::Expire(ttl));
+
+    let at = SystemTime::now() + Duration::from_secs(1000);
+    let got = client.pexpire_at("key", at, ExpireCondition::Gt).await?;
+    assert!(got);
+    match client.pexpire_time("key").await? {
+        Expiry::Expire(got) => assert!(got <= at),
+        got => panic!("unexpected expiry: {:?}", got),
+    }
+
+    let got = client.persist("key").await?;
+    assert!(got);
+    let got = client.pttl("key").await?;
+    assert_eq!(got, Expiry::Persistent);
+
+    // a sub-second TTL rounds up rather than expiring the key at once
+    let got = client
+        .expire("key", Duration::from_millis(500), ExpireCondition::None)
+        .await?;
+    assert!(got);
+    let got = client.ttl("key").await?;
+    assert_eq!(got, Expiry::Expire(Duration::from_secs(1)));
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_rename() -> Result<()> {
+    let mut client = crate::client().await?;
+    client.set("k1", "hello", SetOption::default()).await?;
+    client.set("k2", "world", SetOption::default()).await?;
+    let got = client.rename_nx("k1", "k2").await?;
+    assert!(!got);
+    client.rename("k1", "k3").await?;
+    let got: Option<Utf8String> = client.get("k3").await?;
+    assert_eq!(got.unwrap(), "hello".into());
+    let got = client.key_type("k3").await?;
+    assert_eq!(got, KeyType::String);
+    let got = client.key_type("k1").await?;
+    assert_eq!(got, KeyType::None);
+    let got = client.copy("k3", "k2", None, true).await?;
+    assert!(got);
+    let got: Option<Utf8String> = client.get("k2").await?;
+    assert_eq!(got.unwrap(), "hello".into());
+    let mut got: Vec<Utf8String> = client.keys("k*").await?;
+    got.sort();
+    assert_eq!(got, vec!["k2".into(), "k3".into()]);
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_dump_restore() -> Result<()> {
+    let mut client = crate::client().await?;
+    client.set("key", "10", SetOption::default()).await?;
+    let got = client.dump("key").await?.unwrap();
+    client.del(vec!["key"]).await?;
+    let ttl = Duration::from_secs(100);
+    let option = RestoreOption::default().ttl(ttl);
+    client.restore("key", got, option).await?;
+    let got: Option<Utf8String> = client.get("key").await?;
+    assert_eq!(got.unwrap(), "10".into());
+    let got = client.ttl("key").await?;
+    assert_eq!(got, Expiry::Expire(ttl));
+
+    let got: Vec<Option<Utf8String>> = client.sort_ro("nonexisting", SortOption::default()).await?;
+    assert!(got.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_scan() -> Result<()> {
+    let mut client = crate::client().await?;
+    let kvs = (0..100).map(|i| (format!("key:{}", i), i.to_string()));
+    client.mset(kvs.collect()).await?;
+    client.set("other", "value", SetOption::default()).await?;
+
+    let option = ScanOption::default()
+        .pattern("key:*")
+        .count(10)
+        .key_type(KeyType::String)
+        .dedupe();
+    let mut got: Vec<Utf8String> = client.scan(option).try_collect().await?;
+    got.sort();
+    let mut expected: Vec<Utf8String> =
+        (0..100).map(|i| Utf8String(format!("key:{}", i))).collect();
+    expected.sort();
+    assert_eq!(got, expected);
+    Ok(())
+}
@@ -0,0 +1,97 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use aredis::command::{GeoAddOption, GeoCoord, GeoMember, GeoSearchOption, GeoUnit};
+
+use crate::Utf8String;
+
+async fn add_stores(client: &mut aredis::Client) -> Result<()> {
+    let stores = vec![
+        (GeoCoord::new(13.361389, 38.115556), "Palermo"),
+        (GeoCoord::new(15.087269, 37.502669), "Catania"),
+    ];
+    let got = client
+        .geo_add("Sicily", stores, GeoAddOption::default())
+        .await?;
+    assert_eq!(got, 2);
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_geo() -> Result<()> {
+    let mut client = crate::client().await?;
+    add_stores(&mut client).await?;
+
+    let moved = vec![(GeoCoord::new(13.361389, 38.115556), "Palermo")];
+    let got = client
+        .geo_add("Sicily", moved, GeoAddOption::default().xx().ch())
+        .await?;
+    assert_eq!(got, 0);
+
+    let got = client
+        .geo_dist("Sicily", "Palermo", "Catania", GeoUnit::Km)
+        .await?;
+    assert!((got.unwrap() - 166.2742).abs() < 0.01);
+    let got = client
+        .geo_dist("Sicily", "Palermo", "Nowhere", GeoUnit::Km)
+        .await?;
+    assert!(got.is_none());
+
+    let got = client.geo_pos("Sicily", vec!["Palermo", "Nowhere"]).await?;
+    let coord = got[0].unwrap();
+    assert!((coord.longitude - 13.361389).abs() < 0.0001);
+    assert!((coord.latitude - 38.115556).abs() < 0.0001);
+    assert!(got[1].is_none());
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_geo_search() -> Result<()> {
+    let mut client = crate::client().await?;
+    add_stores(&mut client).await?;
+
+    let option = GeoSearchOption::default()
+        .from_lonlat(GeoCoord::new(15.0, 37.0))
+        .by_radius(200.0, GeoUnit::Km)
+        .asc();
+    let got: Vec<GeoMember<Utf8String>> = client.geo_search("Sicily", option).await?;
+    let got: Vec<Utf8String> = got.into_iter().map(|m| m.name).collect();
+    assert_eq!(got, vec!["Catania".into(), "Palermo".into()]);
+
+    let option = GeoSearchOption::default()
+        .from_member("Palermo")
+        .by_box(400.0, 400.0, GeoUnit::Km)
+        .desc()
+        .count(1)
+        .with_dist()
+        .with_coord();
+    let got: Vec<GeoMember<Utf8String>> = client.geo_search("Sicily", option).await?;
+    assert_eq!(got.len(), 1);
+    assert_eq!(got[0].name, "Catania".into());
+    assert!((got[0].dist.unwrap() - 166.2742).abs() < 0.01);
+    assert!(got[0].coord.is_some());
+    assert!(got[0].hash.is_none());
+
+    let option = GeoSearchOption::default()
+        .from_member("Palermo")
+        .by_radius(10.0, GeoUnit::Km);
+    let got = client
+        .geo_search_store("nearby", "Sicily", option, false)
+        .await?;
+    assert_eq!(got, 1);
+    Ok(())
+}
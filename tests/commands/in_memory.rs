@@ -0,0 +1,156 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use aredis::{
+    command::{ExpireCondition, Expiry, ScanOption, SetOption},
+    testing::InMemoryServer,
+    Client, Model,
+};
+use bytes::{BufMut, Bytes, BytesMut};
+use futures::TryStreamExt;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+/// Sends a command the client has no method for and waits for the reply.
+async fn call(stream: &mut TcpStream, args: &[&str]) -> Result<Model> {
+    let mut out = BytesMut::new();
+    out.put_slice(format!("*{}\r\n", args.len()).as_bytes());
+    for arg in args {
+        out.put_slice(format!("${}\r\n{}\r\n", arg.len(), arg).as_bytes());
+    }
+    stream.write_all(&out).await?;
+    let mut buf = BytesMut::new();
+    loop {
+        stream.read_buf(&mut buf).await?;
+        if let Some((model, _)) = Model::parse(&buf.clone().freeze(), 0)? {
+            return Ok(model);
+        }
+    }
+}
+
+fn strings(model: Model) -> Vec<Bytes> {
+    match model {
+        Model::Array(models) => models
+            .into_iter()
+            .map(|model| match model {
+                Model::String(s) => s,
+                model => panic!("unexpected: {:?}", model),
+            })
+            .collect(),
+        model => panic!("unexpected: {:?}", model),
+    }
+}
+
+#[tokio::test]
+async fn test_in_memory_strings() -> Result<()> {
+    let server = InMemoryServer::start().await?;
+    let mut client = Client::connect(server.addr()).await?;
+    assert!(client.set("key", "v1", SetOption::default()).await?);
+    assert!(!client.set("key", "v2", SetOption::default().nx()).await?);
+    let got: Option<Vec<u8>> = client.get_set("key", "v2", SetOption::default()).await?;
+    assert_eq!(got, Some(b"v1".to_vec()));
+    assert_eq!(client.append("key", "v3").await?, 4);
+    assert_eq!(client.set_range("key", 1, "!").await?, 4);
+    let got: Vec<Option<Vec<u8>>> = client.mget(vec!["key", "missing"]).await?;
+    assert_eq!(got, vec![Some(b"v!v3".to_vec()), None]);
+    assert_eq!(client.incr_by("counter", 41).await?, 41);
+    assert_eq!(client.incr("counter").await?, 42);
+    assert!(client.incr("key").await.is_err());
+    assert_eq!(client.exists(vec!["key", "counter", "missing"]).await?, 2);
+    assert_eq!(client.del(vec!["key", "missing"]).await?, 1);
+
+    // out-of-range arguments fail the command rather than the server
+    let got = client
+        .call(vec!["SETRANGE", "key", &usize::MAX.to_string(), "x"])
+        .await?;
+    assert!(matches!(got, Model::Error(e) if e.contains("maximum allowed size")));
+    assert!(client
+        .set_range("key", 512 * 1024 * 1024, "x")
+        .await
+        .is_err());
+    client.call(vec!["RPUSH", "list", "a"]).await?;
+    let got = client
+        .call(vec!["LRANGE", "list", &i64::MIN.to_string(), "-1"])
+        .await?;
+    assert!(matches!(got, Model::Array(models) if models.len() == 1));
+    let got = client
+        .call(vec!["LINDEX", "list", &i64::MIN.to_string()])
+        .await?;
+    assert!(matches!(got, Model::Nil));
+    assert_eq!(client.incr("counter").await?, 43);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_in_memory_expiry() -> Result<()> {
+    let server = InMemoryServer::start().await?;
+    let mut client = Client::connect(server.addr()).await?;
+    client
+        .set("key", "value", SetOption::default().px(50))
+        .await?;
+    client.set("kept", "value", SetOption::default()).await?;
+    assert!(matches!(client.pttl("key").await?, Expiry::Expire(_)));
+    assert!(matches!(client.ttl("kept").await?, Expiry::Persistent));
+    assert!(
+        client
+            .pexpire("kept", Duration::from_secs(60), ExpireCondition::Nx)
+            .await?
+    );
+    assert!(client.persist("kept").await?);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let got: Option<Vec<u8>> = client.get("key").await?;
+    assert_eq!(got, None);
+    let keys: Vec<Vec<u8>> = client.keys("*").await?;
+    assert_eq!(keys, vec![b"kept".to_vec()]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_in_memory_hashes_and_lists() -> Result<()> {
+    let server = InMemoryServer::start().await?;
+    let mut stream = TcpStream::connect(server.addr()).await?;
+    let got = call(&mut stream, &["HSET", "hash", "a", "1", "b", "2"]).await?;
+    assert!(matches!(got, Model::Integer(2)));
+    let got = call(&mut stream, &["HINCRBY", "hash", "a", "10"]).await?;
+    assert!(matches!(got, Model::Integer(11)));
+    let got = call(&mut stream, &["HGETALL", "hash"]).await?;
+    assert_eq!(strings(got), vec!["a", "11", "b", "2"]);
+    let got = call(&mut stream, &["RPUSH", "list", "b", "c"]).await?;
+    assert!(matches!(got, Model::Integer(2)));
+    let got = call(&mut stream, &["LPUSH", "list", "a"]).await?;
+    assert!(matches!(got, Model::Integer(3)));
+    let got = call(&mut stream, &["LRANGE", "list", "0", "-1"]).await?;
+    assert_eq!(strings(got), vec!["a", "b", "c"]);
+    let got = call(&mut stream, &["GET", "list"]).await?;
+    assert!(matches!(got, Model::Error(e) if e.starts_with("WRONGTYPE")));
+
+    // RESP3 replies a map where RESP2 replies a flat array
+    let got = call(&mut stream, &["HELLO", "3"]).await?;
+    assert!(matches!(got, Model::Map(_)));
+    let got = call(&mut stream, &["HGETALL", "hash"]).await?;
+    assert!(matches!(got, Model::Map(pairs) if pairs.len() == 2));
+
+    let mut client = Client::connect(server.addr()).await?;
+    let fields: Vec<(Vec<u8>, Vec<u8>)> = client
+        .hscan("hash", ScanOption::default())
+        .try_collect()
+        .await?;
+    assert_eq!(fields.len(), 2);
+    Ok(())
+}
@@ -0,0 +1,98 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use aredis::{command::SetOption, EventChannel, KeyspaceEvent, KeyspaceEvents};
+use futures::{future, StreamExt};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_keyspace_events() -> Result<()> {
+    crate::restoring_config("notify-keyspace-events", keyspace_events).await
+}
+
+async fn keyspace_events() -> Result<()> {
+    let mut client = crate::client().await?;
+    client.flush_all(true).await?;
+    let events = KeyspaceEvents::new(crate::addr())
+        .notify("KEA")
+        .subscribe()
+        .await?;
+    let keys = KeyspaceEvents::new(crate::addr())
+        .channel(EventChannel::Keyspace)
+        .pattern("watched:*")
+        .subscribe()
+        .await?;
+    futures::pin_mut!(events, keys);
+
+    client.set("key", "value", SetOption::default()).await?;
+    client.del(vec!["key"]).await?;
+    client
+        .pset_ex("watched:key", Duration::from_millis(50), "value")
+        .await?;
+
+    let mut events =
+        events.filter(|event| future::ready(!matches!(event, Ok(KeyspaceEvent::Other { .. }))));
+    let mut got = vec![];
+    while got.len() < 4 {
+        got.push(events.next().await.unwrap()?);
+    }
+    assert_eq!(got[0], KeyspaceEvent::Set(b"key".to_vec()));
+    assert_eq!(got[1], KeyspaceEvent::Del(b"key".to_vec()));
+    assert_eq!(got[2], KeyspaceEvent::Set(b"watched:key".to_vec()));
+    assert_eq!(got[3], KeyspaceEvent::Expired(b"watched:key".to_vec()));
+
+    let got = keys.next().await.unwrap()?;
+    assert_eq!(got, KeyspaceEvent::Set(b"watched:key".to_vec()));
+    let got = keys.next().await.unwrap()?;
+    assert_eq!(
+        got,
+        KeyspaceEvent::Other {
+            event: "expire".to_string(),
+            key: b"watched:key".to_vec()
+        }
+    );
+    let got = keys.next().await.unwrap()?;
+    assert_eq!(got, KeyspaceEvent::Expired(b"watched:key".to_vec()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_keyspace_events_connection_lost() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await?;
+        let mut buf = vec![0; 1024];
+        let _ = stream.read(&mut buf).await?;
+        let subscribed = b"*3\r\n$10\r\npsubscribe\r\n$16\r\n__keyevent@0__:*\r\n:1\r\n";
+        stream.write_all(subscribed).await?;
+        // the connection drops halfway through a message
+        stream.write_all(b"*4\r\n$8\r\npmess").await?;
+        anyhow::Ok(())
+    });
+
+    let events = KeyspaceEvents::new(addr.to_string()).subscribe().await?;
+    futures::pin_mut!(events);
+    server.await??;
+    assert!(events.next().await.unwrap().is_err());
+    assert!(events.next().await.is_none());
+    Ok(())
+}
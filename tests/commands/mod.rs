@@ -12,4 +12,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod bitmap;
+#[cfg(feature = "blocking")]
+mod blocking;
+#[cfg(all(feature = "cli", feature = "testing"))]
+mod cli;
+mod client;
+mod generic;
+mod geo;
+#[cfg(feature = "testing")]
+mod in_memory;
+mod keyspace;
+mod model;
+#[cfg(feature = "testing")]
+mod observer;
+#[cfg(all(feature = "proxy", feature = "testing"))]
+mod proxy;
+mod replicated;
+#[cfg(feature = "async-std-comp")]
+mod runtime;
+mod server;
 mod string;
+#[cfg(feature = "testing")]
+mod testing;
+#[cfg(all(feature = "tracing", feature = "testing"))]
+mod trace;
+mod tracking;
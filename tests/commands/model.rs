@@ -0,0 +1,200 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::VecDeque,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use aredis::{Client, Error, Limits, Model};
+use bytes::{Bytes, BytesMut};
+use futures::io::{AsyncRead, AsyncWrite};
+
+fn parse(frame: &'static [u8], limits: Limits) -> aredis::Result<Option<(Model, usize)>> {
+    Model::parse_with_limits(&Bytes::from_static(frame), 0, &limits)
+}
+
+#[test]
+fn test_parse_limits() {
+    let got = parse(b"*2\r\n$3\r\nfoo\r\n:1\r\n", Limits::default()).unwrap();
+    assert!(matches!(got, Some((Model::Array(_), 17))));
+
+    // a huge header must neither be trusted nor wait for the rest forever
+    let got = parse(b"*999999999999\r\n", Limits::default());
+    assert!(matches!(got, Err(Error::Protocol(_))));
+    let got = parse(b"*1000\r\n:1\r\n", Limits::default()).unwrap();
+    assert!(got.is_none());
+    let got = parse(b"$1024\r\n", Limits::default().max_bulk_len(1023));
+    assert!(matches!(got, Err(Error::Protocol(_))));
+    let got = parse(b"%3\r\n", Limits::default().max_aggregate_len(2));
+    assert!(matches!(got, Err(Error::Protocol(_))));
+
+    let nested = b"*1\r\n*1\r\n*1\r\n:1\r\n";
+    assert!(parse(nested, Limits::default().max_depth(3))
+        .unwrap()
+        .is_some());
+    let got = parse(nested, Limits::default().max_depth(2));
+    assert!(matches!(got, Err(Error::Protocol(_))));
+    let deep = "*1\r\n".repeat(100_000);
+    let got = Model::parse(&Bytes::from(deep), 0);
+    assert!(matches!(got, Err(Error::Protocol(_))));
+}
+
+#[test]
+fn test_parse_bulk_with_crlf() {
+    // a bulk string is read by its declared length, not up to the first CRLF
+    let got = parse(b"$6\r\na\r\nb\r\n\r\n", Limits::default()).unwrap();
+    assert!(matches!(got, Some((Model::String(s), 12)) if s == b"a\r\nb\r\n"[..]));
+    let got = parse(b"=8\r\ntxt:a\r\nb\r\n", Limits::default()).unwrap();
+    assert!(matches!(got, Some((Model::Verb(f, s), 14)) if f == b"txt"[..] && s == b"a\r\nb"[..]));
+    let got = parse(b"$6\r\na\r\nb\r\n", Limits::default()).unwrap();
+    assert!(got.is_none());
+}
+
+#[test]
+fn test_encode_round_trip() {
+    let model = Model::Array(vec![
+        Model::Error("ERR boom".to_string()),
+        Model::Status("OK".to_string()),
+        Model::Integer(-42),
+        Model::Double(3.25),
+        Model::Double(f64::NEG_INFINITY),
+        Model::Nil,
+        Model::String(Bytes::from_static(b"bulk\r\nwith crlf")),
+        Model::Verb(Bytes::from_static(b"txt"), Bytes::from_static(b"verbatim")),
+        Model::Bool(true),
+        Model::BigNum(
+            "3492890328409238509324850943850943825024385"
+                .parse()
+                .unwrap(),
+        ),
+        Model::Set(vec![Model::Integer(1)]),
+        Model::Push(vec![Model::String(Bytes::from_static(b"invalidate"))]),
+        Model::Map(vec![(Model::Status("k".to_string()), Model::Array(vec![]))]),
+    ]);
+    let mut out = BytesMut::new();
+    model.encode(&mut out);
+    let encoded = out.freeze();
+    let (got, len) = Model::parse(&encoded, 0).unwrap().unwrap();
+    assert_eq!(len, encoded.len());
+    assert_eq!(format!("{:?}", got), format!("{:?}", model));
+
+    let mut out = BytesMut::new();
+    got.encode(&mut out);
+    assert_eq!(out.freeze(), encoded);
+}
+
+/// Replays `script` a byte a read and discards whatever is written.
+struct Dribble(Bytes);
+
+impl AsyncRead for Dribble {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let n = buf.len().min(self.0.len()).min(1);
+        buf[..n].copy_from_slice(&self.0.split_to(n));
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl AsyncWrite for Dribble {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn test_recv_in_pieces() -> aredis::Result<()> {
+    let script =
+        b"+OK\r\n+OK\r\n%1\r\n$3\r\nk\r\n\r\n*2\r\n*0\r\n$-1\r\n:7\r\n*1\r\n*1\r\n*1\r\n:1\r\n";
+    let mut client = Client::from_stream(Dribble(Bytes::from_static(script))).await?;
+    let got = client.call(vec!["HGETALL"]).await?;
+    assert!(matches!(got, Model::Map(pairs) if pairs.len() == 1));
+    let got = client.call(vec!["INCR"]).await?;
+    assert!(matches!(got, Model::Integer(7)));
+
+    client.set_limits(Limits::default().max_depth(2));
+    let got = client.call(vec!["DEEP"]).await;
+    assert!(matches!(got, Err(Error::Protocol(_))));
+    Ok(())
+}
+
+/// Reads one scripted piece at a time, each whole.
+struct Pieces(VecDeque<&'static [u8]>);
+
+impl AsyncRead for Pieces {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let piece = self.0.pop_front().unwrap_or_default();
+        buf[..piece.len()].copy_from_slice(piece);
+        Poll::Ready(Ok(piece.len()))
+    }
+}
+
+impl AsyncWrite for Pieces {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn test_recv_to_after_push() -> aredis::Result<()> {
+    // each reply follows an invalidation push in the same read
+    let pieces = VecDeque::from([
+        &b"+OK\r\n+OK\r\n"[..],
+        b">2\r\n$10\r\ninvalidate\r\n*1\r\n$3\r\nkey\r\n$5\r\nhello\r\n",
+        b">2\r\n$10\r\ninvalidate\r\n*1\r\n$3\r\nkey\r\n$3\r\nrdb\r\n",
+    ]);
+    let mut client = Client::from_stream(Pieces(pieces)).await?;
+    let mut got = vec![];
+    let len = client.get_to("key", &mut got).await?;
+    assert_eq!(len, Some(5));
+    assert_eq!(got, b"hello");
+    let mut got = vec![];
+    let len = client.dump_to("key", &mut got).await?;
+    assert_eq!(len, Some(3));
+    assert_eq!(got, b"rdb");
+    Ok(())
+}
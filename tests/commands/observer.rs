@@ -0,0 +1,130 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use anyhow::Result;
+use aredis::{
+    command::SetOption,
+    observer::{ClientObserver, Outcome, ServerErrorKind},
+    testing::MockServer,
+    Client, Model,
+};
+
+#[derive(Default)]
+struct Recorder {
+    started: Mutex<Vec<String>>,
+    ended: Mutex<Vec<(String, Outcome)>>,
+    sent: AtomicUsize,
+    received: AtomicUsize,
+}
+
+impl ClientObserver for Recorder {
+    fn on_command_start(&self, name: &str) {
+        self.started.lock().unwrap().push(name.to_string());
+    }
+
+    fn on_command_end(&self, name: &str, _: Duration, outcome: &Outcome) {
+        self.ended
+            .lock()
+            .unwrap()
+            .push((name.to_string(), *outcome));
+    }
+
+    fn on_bytes_sent(&self, len: usize) {
+        self.sent.fetch_add(len, Ordering::Relaxed);
+    }
+
+    fn on_bytes_received(&self, len: usize) {
+        self.received.fetch_add(len, Ordering::Relaxed);
+    }
+}
+
+async fn server() -> Result<MockServer> {
+    let server = MockServer::handler(|args| match &args[0].to_ascii_uppercase()[..] {
+        b"GET" => Model::Nil,
+        b"SET" => Model::Status("OK".to_string()),
+        b"INCR" => Model::Error("WRONGTYPE Operation against a key".to_string()),
+        _ => Model::Error("ERR unknown command".to_string()),
+    })
+    .await?;
+    Ok(server)
+}
+
+#[tokio::test]
+async fn test_observer() -> Result<()> {
+    let server = server().await?;
+    let mut client = Client::connect(server.addr()).await?;
+    let recorder = Arc::new(Recorder::default());
+    client.set_observer(recorder.clone());
+
+    client.set("key", "value", SetOption::default()).await?;
+    assert!(client.incr("key").await.is_err());
+    client
+        .call_all(vec![vec!["get", "key"], vec!["NOSUCH"]])
+        .await?;
+
+    let started = recorder.started.lock().unwrap().clone();
+    assert_eq!(started, vec!["SET", "INCR", "GET", "UNKNOWN"]);
+    let ended = recorder.ended.lock().unwrap().clone();
+    assert_eq!(
+        ended,
+        vec![
+            ("SET".to_string(), Outcome::Ok),
+            (
+                "INCR".to_string(),
+                Outcome::ServerError(ServerErrorKind::WrongType)
+            ),
+            ("GET".to_string(), Outcome::Ok),
+            (
+                "UNKNOWN".to_string(),
+                Outcome::ServerError(ServerErrorKind::Err)
+            ),
+        ]
+    );
+    assert!(recorder.sent.load(Ordering::Relaxed) > 0);
+    assert!(recorder.received.load(Ordering::Relaxed) > 0);
+    Ok(())
+}
+
+#[cfg(feature = "prometheus")]
+#[tokio::test]
+async fn test_prometheus_observer() -> Result<()> {
+    use aredis::observer::PrometheusObserver;
+    use prometheus::{Encoder, Registry, TextEncoder};
+
+    let server = server().await?;
+    let mut client = Client::connect(server.addr()).await?;
+    let registry = Registry::new();
+    client.set_observer(Arc::new(PrometheusObserver::new(&registry)?));
+    client.get::<_, Vec<u8>>("key").await?;
+    assert!(client.incr("key").await.is_err());
+    client.call(vec!["NOSUCH"]).await?;
+
+    let mut text = vec![];
+    TextEncoder::new().encode(&registry.gather(), &mut text)?;
+    let text = String::from_utf8(text)?;
+    assert!(text.contains("aredis_command_duration_seconds_count{command=\"GET\"} 1"));
+    assert!(text.contains("aredis_command_errors_total{command=\"INCR\",kind=\"WRONGTYPE\"} 1"));
+    assert!(text.contains("aredis_command_errors_total{command=\"UNKNOWN\",kind=\"ERR\"} 1"));
+    assert!(!text.contains("NOSUCH"));
+    assert!(!text.contains("aredis_bytes_sent_total 0"));
+    Ok(())
+}
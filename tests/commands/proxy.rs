@@ -0,0 +1,97 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use anyhow::Result;
+use aredis::{
+    command::SetOption,
+    proxy::{Proxy, Recording},
+    testing::InMemoryServer,
+    Client, Model,
+};
+
+#[tokio::test]
+async fn test_proxy_record_replay() -> Result<()> {
+    let upstream = InMemoryServer::start().await?;
+    let path = std::env::temp_dir().join(format!("aredis-proxy-{}.resp", std::process::id()));
+    let inspected = Arc::new(AtomicUsize::new(0));
+    let counter = inspected.clone();
+    let proxy = Proxy::new(upstream.addr().to_string())
+        .record(&path)
+        .inspect(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        })
+        .start()
+        .await?;
+
+    let mut client = Client::connect(proxy.addr()).await?;
+    client.set("key", "value", SetOption::default()).await?;
+    let got: Option<Vec<u8>> = client.get("key").await?;
+    assert_eq!(got, Some(b"value".to_vec()));
+    proxy.flush().await;
+    drop(client);
+    drop(proxy);
+
+    let recording = Recording::load(&path)?;
+    std::fs::remove_file(&path)?;
+    // the handshake on connect, then SET and GET
+    let exchanges = recording.exchanges();
+    assert_eq!(exchanges.len(), 4);
+    assert_eq!(inspected.load(Ordering::Relaxed), 4);
+    assert!(exchanges.iter().all(|exchange| exchange.connection == 1));
+    assert!(matches!(&exchanges[2].reply, Model::Status(status) if status == "OK"));
+    assert!(matches!(&exchanges[3].reply, Model::String(value) if value == "value"));
+
+    // replayed without the upstream
+    drop(upstream);
+    let server = recording.replay().await?;
+    let mut client = Client::connect(server.addr()).await?;
+    let got: Option<Vec<u8>> = client.get("key").await?;
+    assert_eq!(got, Some(b"value".to_vec()));
+    assert!(client.get::<_, Vec<u8>>("key").await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_proxy_relays_frames_as_they_are() -> Result<()> {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    // a null array would come back as a null bulk string if re-encoded
+    let request = b"*1\r\n$4\r\nPING\r\n";
+    let upstream = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = upstream.local_addr()?;
+    let upstream = tokio::spawn(async move {
+        let (mut stream, _) = upstream.accept().await?;
+        let mut got = vec![0; request.len()];
+        stream.read_exact(&mut got).await?;
+        stream.write_all(b"*-1\r\n").await?;
+        anyhow::Ok(got)
+    });
+
+    let proxy = Proxy::new(addr.to_string()).start().await?;
+    let mut client = TcpStream::connect(proxy.addr()).await?;
+    client.write_all(request).await?;
+    let mut got = vec![0; 5];
+    client.read_exact(&mut got).await?;
+    assert_eq!(got, b"*-1\r\n");
+    assert_eq!(upstream.await??, request);
+    Ok(())
+}
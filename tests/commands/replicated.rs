@@ -0,0 +1,144 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use aredis::{command::SetOption, ReadFrom, ReplicatedClient};
+
+use crate::Utf8String;
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_replicated() -> Result<()> {
+    let mut client = ReplicatedClient::new(crate::addr())
+        .replica(crate::addr())
+        .replica("localhost:1") // unreachable replica is skipped
+        .read_from(ReadFrom::LowestLatency)
+        .connect()
+        .await?;
+    client.flush_all(true).await?;
+    client.set("key", "value", SetOption::default()).await?;
+    for _ in 0..3 {
+        let got: Option<Utf8String> = client.get("key").await?;
+        assert_eq!(got.unwrap(), "value".into());
+    }
+
+    let mut client = ReplicatedClient::new(crate::addr())
+        .replica("localhost:1")
+        .connect()
+        .await?;
+    let got: Option<Utf8String> = client.get("key").await?;
+    assert_eq!(got.unwrap(), "value".into());
+    Ok(())
+}
+
+#[cfg(feature = "testing")]
+#[tokio::test]
+async fn test_replicated_scan() -> Result<()> {
+    use aredis::{command::ScanOption, testing::MockServer, Error, Model};
+    use bytes::Bytes;
+    use futures::TryStreamExt;
+
+    let page = |cursor: &str, elements: &[&str]| {
+        let elements = elements
+            .iter()
+            .map(|e| Model::String(Bytes::from(e.to_string())));
+        Model::Array(vec![
+            Model::String(Bytes::from(cursor.to_string())),
+            Model::Array(elements.collect()),
+        ])
+    };
+    let primary = MockServer::handler(move |args| match (&args[0][..], args.last()) {
+        (b"SCAN", Some(cursor)) if &cursor[..] == b"0" => page("7", &["k1"]),
+        (b"SCAN", _) => page("0", &["k2"]),
+        (b"HSCAN", _) => page("0", &["field", "value", "dangling"]),
+        _ => Model::Error("ERR unexpected".to_string()),
+    })
+    .await?;
+    let replica = MockServer::handler(|args| match &args[0][..] {
+        b"PING" => Model::Status("PONG".to_string()),
+        _ => Model::Error("ERR cursor unknown to this replica".to_string()),
+    })
+    .await?;
+
+    let mut client = ReplicatedClient::new(primary.addr().to_string())
+        .replica(replica.addr().to_string())
+        .connect()
+        .await?;
+    let got: Vec<Utf8String> = client.scan(ScanOption::default()).try_collect().await?;
+    assert_eq!(got, vec!["k1".into(), "k2".into()]);
+
+    let got: Result<Vec<(Utf8String, Utf8String)>, _> = client
+        .hscan("hash", ScanOption::default())
+        .try_collect()
+        .await;
+    assert!(matches!(got, Err(Error::Protocol(_))));
+    Ok(())
+}
+
+#[cfg(feature = "testing")]
+#[tokio::test]
+async fn test_replicated_fallback() -> Result<()> {
+    use std::time::{Duration, Instant};
+
+    use aredis::{testing::MockServer, Model};
+    use bytes::Bytes;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    let primary = MockServer::handler(|_| Model::String(Bytes::from("primary"))).await?;
+    let loading = MockServer::handler(|args| match &args[0][..] {
+        b"PING" => Model::Status("PONG".to_string()),
+        _ => Model::Error("LOADING Redis is loading the dataset in memory".to_string()),
+    })
+    .await?;
+    let mut client = ReplicatedClient::new(primary.addr().to_string())
+        .replica(loading.addr().to_string())
+        .connect()
+        .await?;
+    let got: Option<Utf8String> = client.get("key").await?;
+    assert_eq!(got.unwrap(), "primary".into());
+
+    // answers the handshake and PING, then never replies
+    let hung = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = hung.local_addr()?;
+    tokio::spawn(async move {
+        let (mut stream, _) = hung.accept().await?;
+        let mut buf = vec![0; 1024];
+        loop {
+            let n = stream.read(&mut buf).await?;
+            if n == 0 {
+                return anyhow::Ok(());
+            }
+            let received = String::from_utf8_lossy(&buf[..n]);
+            for _ in received.matches("SETINFO") {
+                stream.write_all(b"+OK\r\n").await?;
+            }
+            if received.contains("PING") {
+                stream.write_all(b"+PONG\r\n").await?;
+            }
+        }
+    });
+    let mut client = ReplicatedClient::new(primary.addr().to_string())
+        .replica(addr.to_string())
+        .timeout(Duration::from_millis(100))
+        .connect()
+        .await?;
+    let start = Instant::now();
+    let got: Option<Utf8String> = client.get("key").await?;
+    assert_eq!(got.unwrap(), "primary".into());
+    assert!(start.elapsed() < Duration::from_secs(1));
+    Ok(())
+}
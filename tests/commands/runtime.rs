@@ -0,0 +1,32 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use aredis::{command::SetOption, Client};
+
+#[test]
+#[serial_test::serial]
+fn test_async_std_stream() -> Result<()> {
+    async_std::task::block_on(async {
+        let stream = async_std::net::TcpStream::connect(crate::addr()).await?;
+        let mut client = Client::from_stream(stream).await?;
+        client.ping().await?;
+        client
+            .set("runtime", "async-std", SetOption::default())
+            .await?;
+        let got: Option<Vec<u8>> = client.get("runtime").await?;
+        assert_eq!(got, Some(b"async-std".to_vec()));
+        Ok(())
+    })
+}
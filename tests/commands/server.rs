@@ -0,0 +1,105 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use aredis::command::SetOption;
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_info() -> Result<()> {
+    let mut client = crate::client().await?;
+    let got = client.info(Vec::<&str>::new()).await?;
+    assert!(got.redis_version().is_some());
+    assert!(got.used_memory().unwrap() > 0);
+    assert!(got.connected_clients().unwrap() >= 1);
+    assert!(got.section("Server").is_some());
+
+    let got = client.info(vec!["clients"]).await?;
+    assert!(got.connected_clients().is_some());
+    assert!(got.redis_version().is_none());
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_config() -> Result<()> {
+    let mut client = crate::client().await?;
+    let got = client.config_get(vec!["maxmemory-policy"]).await?;
+    let policy = got.get("maxmemory-policy").unwrap().clone();
+
+    client
+        .config_set(vec![("maxmemory-policy", "allkeys-lru")])
+        .await?;
+    let got = client.config_get(vec!["maxmemory-*"]).await?;
+    assert_eq!(got.get("maxmemory-policy").unwrap(), "allkeys-lru");
+
+    client
+        .config_set(vec![("maxmemory-policy", policy.as_str())])
+        .await?;
+    client.config_reset_stat().await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_db_size() -> Result<()> {
+    let mut client = crate::client().await?;
+    client.set("k1", "v1", SetOption::default()).await?;
+    client.set("k2", "v2", SetOption::default()).await?;
+    let got = client.db_size().await?;
+    assert_eq!(got, 2);
+    client.flush_db(false).await?;
+    let got = client.db_size().await?;
+    assert_eq!(got, 0);
+
+    let got = client.time().await?;
+    let now = SystemTime::now();
+    let skew = now.duration_since(got).unwrap_or_else(|e| e.duration());
+    assert!(skew < Duration::from_secs(60));
+    let got = client.last_save().await?;
+    assert!(got <= now + Duration::from_secs(60));
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_slowlog() -> Result<()> {
+    let mut client = crate::client().await?;
+    let got = client.config_get(vec!["slowlog-log-slower-than"]).await?;
+    let threshold = got.get("slowlog-log-slower-than").unwrap().clone();
+    client
+        .config_set(vec![("slowlog-log-slower-than", "0")])
+        .await?;
+    client.slowlog_reset().await?;
+    client.set("key", "value", SetOption::default()).await?;
+
+    let got = client.slowlog_get(Some(-1)).await?;
+    client
+        .config_set(vec![("slowlog-log-slower-than", threshold.as_str())])
+        .await?;
+    let entry = got
+        .iter()
+        .find(|e| e.args.first().map(|arg| arg.as_slice()) == Some(b"SET"))
+        .unwrap();
+    assert_eq!(entry.args[1], b"key");
+    assert!(client.slowlog_len().await? >= 1);
+
+    let got = client.memory_usage("key", None).await?;
+    assert!(got.unwrap() > 0);
+    let got = client.memory_usage("nonexisting", Some(5)).await?;
+    assert!(got.is_none());
+    Ok(())
+}
@@ -12,8 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Duration;
+
 use anyhow::Result;
-use aredis::command::SetOption;
+use aredis::{
+    command::{Expiry, GetExOption, LcsMatch, LcsMatches, RestoreOption, SetOption},
+    Error, Limits,
+};
 
 use crate::Utf8String;
 
@@ -65,3 +70,168 @@ async fn test_exists() -> Result<()> {
     assert_eq!(got, 1);
     Ok(())
 }
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_set_get_flag() -> Result<()> {
+    let mut client = crate::client().await?;
+    let got = client
+        .set("key", "v1", SetOption::default().nx().get())
+        .await?;
+    assert!(got);
+    let got = client
+        .set("key", "v2", SetOption::default().nx().get())
+        .await?;
+    assert!(!got);
+    let got = client
+        .set("key", "v3", SetOption::default().xx().get())
+        .await?;
+    assert!(got);
+    let got: Option<Utf8String> = client.get_del("key").await?;
+    assert_eq!(got.unwrap(), "v3".into());
+    let got = client.exists(vec!["key"]).await?;
+    assert_eq!(got, 0);
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_set_nx() -> Result<()> {
+    let mut client = crate::client().await?;
+    let got = client.set_nx("key", "v1").await?;
+    assert!(got);
+    let got = client.set_nx("key", "v2").await?;
+    assert!(!got);
+    let got: Option<Utf8String> = client.get("key").await?;
+    assert_eq!(got.unwrap(), "v1".into());
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_get_ex() -> Result<()> {
+    let mut client = crate::client().await?;
+    let ttl = Duration::from_secs(100);
+    client.set_ex("key", ttl, "value").await?;
+    let got = client.ttl("key").await?;
+    assert_eq!(got, Expiry::Expire(ttl));
+    let got: Option<Utf8String> = client
+        .get_ex("key", GetExOption::default().persist())
+        .await?;
+    assert_eq!(got.unwrap(), "value".into());
+    let got = client.ttl("key").await?;
+    assert_eq!(got, Expiry::Persistent);
+
+    // sub-second TTLs round up rather than expiring the key at once
+    let ttl = Duration::from_millis(500);
+    client.set_ex("key", ttl, "value").await?;
+    let got = client.ttl("key").await?;
+    assert_eq!(got, Expiry::Expire(Duration::from_secs(1)));
+    let got: Option<Utf8String> = client.get_ex("key", GetExOption::default().ex(ttl)).await?;
+    assert_eq!(got.unwrap(), "value".into());
+    let got = client.ttl("key").await?;
+    assert_eq!(got, Expiry::Expire(Duration::from_secs(1)));
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_lcs() -> Result<()> {
+    let mut client = crate::client().await?;
+    client
+        .mset(vec![("key1", "ohmytext"), ("key2", "mynewtext")])
+        .await?;
+    let got: Utf8String = client.lcs("key1", "key2").await?;
+    assert_eq!(got, "mytext".into());
+    let got = client.lcs_len("key1", "key2").await?;
+    assert_eq!(got, 6);
+    let got = client.lcs_idx("key1", "key2", Some(4), true).await?;
+    let expected = LcsMatches {
+        matches: vec![LcsMatch {
+            key1: (4, 7),
+            key2: (5, 8),
+            len: Some(4),
+        }],
+        len: 6,
+    };
+    assert_eq!(got, expected);
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_large_value() -> Result<()> {
+    let mut client = crate::client().await?;
+    let value: Vec<u8> = (0..256 * 1024).map(|i| (i % 251) as u8).collect();
+    let key = vec![b'k'; aredis::command::LARGE_ARG];
+    client
+        .set(key.clone(), value.clone(), SetOption::default())
+        .await?;
+    client.append(key.clone(), value.clone()).await?;
+    client.set_range(key.clone(), 1, "large").await?;
+    let got: Vec<u8> = client.get(key).await?.unwrap();
+    let mut expected = [value.clone(), value].concat();
+    expected[1..6].copy_from_slice(b"large");
+    assert_eq!(got, expected);
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_streaming() -> Result<()> {
+    let mut client = crate::client().await?;
+    let value: Vec<u8> = (0..1024 * 1024).map(|i| (i % 251) as u8).collect();
+    let mut reader = futures::io::Cursor::new(&value);
+    let written = client
+        .set_from(
+            "blob",
+            &mut reader,
+            value.len() as u64,
+            SetOption::default(),
+        )
+        .await?;
+    assert!(written);
+    let len = client.append_from("blob", &mut &b"tail"[..], 4).await?;
+    assert_eq!(len, value.len() as u64 + 4);
+
+    let mut got = vec![];
+    let len = client.get_to("blob", &mut got).await?;
+    assert_eq!(len, Some(value.len() as u64 + 4));
+    assert_eq!(got, [&value[..], b"tail"].concat());
+    let len = client.get_to("nonexisting", &mut vec![]).await?;
+    assert_eq!(len, None);
+    let mut dumped = vec![];
+    let len = client.dump_to("blob", &mut dumped).await?;
+    assert_eq!(len, Some(dumped.len() as u64));
+    client.del(vec!["blob"]).await?;
+    client
+        .restore("blob", dumped, RestoreOption::default())
+        .await?;
+    let got: Option<Vec<u8>> = client.get("blob").await?;
+    assert_eq!(got.map(|got| got.len()), Some(value.len() + 4));
+
+    client.set_limits(Limits::default().max_buffered(64 * 1024));
+    let mut got = vec![];
+    assert!(client.get_to("blob", &mut got).await?.is_some());
+    assert_eq!(got.len(), value.len() + 4);
+    assert!(client.get::<_, Vec<u8>>("blob").await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_streaming_short_source() -> Result<()> {
+    let mut client = crate::client().await?;
+    let got = client
+        .set_from("blob", &mut &b"abc"[..], 40, SetOption::default())
+        .await;
+    assert!(matches!(got, Err(Error::Internal(_))));
+
+    // part of the SET went out, so the connection must not be used again
+    let got = client.set("key", "value", SetOption::default()).await;
+    match got {
+        Err(Error::Internal(e)) => assert!(e.contains("broken"), "{}", e),
+        got => panic!("unexpected: {:?}", got),
+    }
+    Ok(())
+}
@@ -0,0 +1,57 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use aredis::{command::SetOption, testing::MockServer, Client, Error, Model};
+use bytes::Bytes;
+
+#[tokio::test]
+async fn test_mock_scripted() -> Result<()> {
+    let server = MockServer::scripted(vec![
+        Model::Status("OK".to_string()),
+        Model::String(Bytes::from("value")),
+        Model::Nil,
+    ])
+    .await?;
+    let mut client = Client::connect(server.addr()).await?;
+    assert!(client.set("key", "value", SetOption::default()).await?);
+    let got: Option<Vec<u8>> = client.get("key").await?;
+    assert_eq!(got, Some(b"value".to_vec()));
+    let got: Option<Vec<u8>> = client.get("missing").await?;
+    assert_eq!(got, None);
+    assert!(matches!(client.ping().await, Err(Error::Server(_))));
+
+    let commands = server.commands();
+    assert_eq!(commands.len(), 4);
+    assert_eq!(commands[0], vec!["SET", "key", "value"]);
+    assert_eq!(commands[1], vec!["GET", "key"]);
+    assert_eq!(commands[3], vec!["PING"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mock_handler() -> Result<()> {
+    let server = MockServer::handler(|args| match &args[0][..] {
+        b"INCR" => Model::Integer(args[1].len() as i64),
+        _ => Model::Error("ERR unknown command".to_string()),
+    })
+    .await?;
+    let mut client = Client::connect(server.addr()).await?;
+    assert_eq!(client.incr("four").await?, 4);
+    match client.decr("four").await {
+        Err(Error::Server(e)) => assert_eq!(e, "ERR unknown command"),
+        got => panic!("unexpected: {:?}", got),
+    }
+    Ok(())
+}
@@ -0,0 +1,141 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Result;
+use aredis::{command::SetOption, testing::MockServer, Client, Model, TraceKeys};
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Event, Metadata, Subscriber,
+};
+
+type Fields = HashMap<String, String>;
+
+/// Keeps the fields of every span, and those of events as spans of their own.
+#[derive(Clone, Default)]
+struct Recorder {
+    spans: Arc<Mutex<Vec<Fields>>>,
+}
+
+struct Collect<'a>(&'a mut Fields);
+
+impl Visit for Collect<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = Fields::new();
+        span.record(&mut Collect(&mut fields));
+        let mut spans = self.spans.lock().unwrap();
+        spans.push(fields);
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = self.spans.lock().unwrap();
+        values.record(&mut Collect(&mut spans[span.into_u64() as usize - 1]));
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::new();
+        event.record(&mut Collect(&mut fields));
+        self.spans.lock().unwrap().push(fields);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+impl Recorder {
+    fn commands(&self) -> Vec<Fields> {
+        let spans = self.spans.lock().unwrap();
+        spans
+            .iter()
+            .filter(|fields| fields.contains_key("db.operation"))
+            .cloned()
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn test_trace_commands() -> Result<()> {
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+    let server = MockServer::handler(|args| match &args[0][..] {
+        b"GET" => Model::Nil,
+        b"SET" | b"SELECT" => Model::Status("OK".to_string()),
+        _ => Model::Error("ERR unknown command".to_string()),
+    })
+    .await?;
+    let mut client = Client::connect(server.addr()).await?;
+    let addr = server.addr().to_string();
+    assert!(recorder.spans.lock().unwrap()[0]
+        .get("message")
+        .is_some_and(|message| message == "connected"));
+
+    client.set("key", "value", SetOption::default()).await?;
+    client.set_trace_keys(TraceKeys::Record);
+    client.call(vec!["SELECT", "1"]).await?;
+    client.get::<_, Vec<u8>>("key").await?;
+    client.set_trace_keys(TraceKeys::Redact);
+    client.get::<_, Vec<u8>>("key").await?;
+    client.get::<_, Vec<u8>>("key").await?;
+    client.call(vec!["NOSUCH"]).await?;
+
+    let commands = recorder.commands();
+    let [_, _, set, select, get, redacted, again, incr] = commands.as_slice() else {
+        panic!("unexpected spans: {:?}", commands);
+    };
+    let field = |span: &Fields, name: &str| span.get(name).cloned().unwrap_or_default();
+    assert_eq!(field(set, "db.operation"), "SET");
+    assert_eq!(field(set, "server.address"), addr);
+    assert_eq!(field(set, "reply.type"), "simple");
+    assert!(!set.contains_key("db.redis.key"));
+    assert_eq!(field(select, "db.redis.database_index"), "0");
+    assert!(!select.contains_key("db.redis.key"));
+    assert_eq!(field(get, "db.redis.database_index"), "1");
+    assert_eq!(field(get, "db.redis.key"), "key");
+    assert_eq!(field(get, "reply.type"), "null");
+    assert_eq!(field(redacted, "db.redis.key").len(), 16);
+    assert_ne!(field(redacted, "db.redis.key"), "key");
+    assert_eq!(
+        field(redacted, "db.redis.key"),
+        field(again, "db.redis.key")
+    );
+    assert_eq!(field(incr, "db.operation"), "UNKNOWN");
+    assert_eq!(field(incr, "error.kind"), "ERR");
+    assert_eq!(field(incr, "otel.status_code"), "ERROR");
+    Ok(())
+}
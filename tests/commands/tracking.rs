@@ -0,0 +1,79 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use aredis::{command::SetOption, CachingClient, Client, Protocol};
+
+/// Counts the GET commands the server has seen since the slow log was reset.
+async fn count_gets(client: &mut Client) -> Result<usize> {
+    let got = client.slowlog_get(Some(-1)).await?;
+    Ok(got
+        .iter()
+        .filter(|e| e.args.first().map(|arg| arg.as_slice()) == Some(b"GET"))
+        .count())
+}
+
+async fn check_cache(mut cached: Client, key: &str) -> Result<()> {
+    let mut client = crate::client().await?;
+    client.flush_all(true).await?;
+    client.set(key, "v1", SetOption::default()).await?;
+    client
+        .config_set(vec![("slowlog-log-slower-than", "0")])
+        .await?;
+    client.slowlog_reset().await?;
+    // let the invalidations of the flush and the write above arrive first
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    for _ in 0..3 {
+        let got: Option<Vec<u8>> = cached.get(key).await?;
+        assert_eq!(got.as_deref(), Some(&b"v1"[..]));
+    }
+    let gets = count_gets(&mut client).await?;
+
+    client.set(key, "v2", SetOption::default()).await?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let got: Option<Vec<u8>> = cached.get(key).await?;
+    let got_all: Vec<Option<Vec<u8>>> = cached.mget(vec![key, "missing"]).await?;
+    let gets_after = count_gets(&mut client).await?;
+    client
+        .config_set(vec![("slowlog-log-slower-than", "10000")])
+        .await?;
+
+    assert_eq!(gets, 1);
+    assert_eq!(got.as_deref(), Some(&b"v2"[..]));
+    assert_eq!(gets_after, 2);
+    assert_eq!(got_all, vec![Some(b"v2".to_vec()), None]);
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_tracking_resp3() -> Result<()> {
+    let cached = CachingClient::new(crate::addr()).connect().await?;
+    check_cache(cached, "tracked").await
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_tracking_resp2_prefix() -> Result<()> {
+    let cached = CachingClient::new(crate::addr())
+        .protocol(Protocol::Resp2)
+        .prefix("tracked:")
+        .ttl(Duration::from_secs(60))
+        .connect()
+        .await?;
+    check_cache(cached, "tracked:key").await
+}
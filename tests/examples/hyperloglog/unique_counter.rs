@@ -0,0 +1,87 @@
+// Copyright 2022 tison <wander4096@gmail.com>.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use aredis::Client;
+use time::{macros::date, Date, Duration};
+
+pub struct UniqueCounter {
+    client: Client,
+    key: String,
+}
+
+impl UniqueCounter {
+    pub fn new<K: Into<String>>(client: Client, key: K) -> Self {
+        UniqueCounter {
+            client,
+            key: key.into(),
+        }
+    }
+
+    pub async fn include<In>(&mut self, day: Date, member: In) -> Result<bool>
+    where
+        In: Into<Vec<u8>>,
+    {
+        let result = self.client.pf_add(self.day_key(day), vec![member]).await?;
+        Ok(result)
+    }
+
+    pub async fn count(&mut self, day: Date) -> Result<u64> {
+        let result = self.client.pf_count(vec![self.day_key(day)]).await?;
+        Ok(result)
+    }
+
+    /// Counts the unique members of the seven days ending with `last_day`.
+    pub async fn count_week(&mut self, last_day: Date) -> Result<u64> {
+        let days = (0..7)
+            .map(|i| self.day_key(last_day - Duration::days(i)))
+            .collect();
+        let week_key = format!("{}::week::{}", self.key, last_day);
+        self.client.pf_merge(week_key.clone(), days).await?;
+        let result = self.client.pf_count(vec![week_key]).await?;
+        Ok(result)
+    }
+
+    fn day_key(&self, day: Date) -> String {
+        format!("{}::day::{}", self.key, day)
+    }
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_unique_counter() -> Result<()> {
+    let client = crate::client().await?;
+    let mut counter = UniqueCounter::new(client, "unique::page_view");
+    let monday = date!(2022 - 02 - 07);
+    let tuesday = date!(2022 - 02 - 08);
+
+    let got = counter.include(monday, "alice").await?;
+    assert!(got);
+    let got = counter.include(monday, "alice").await?;
+    assert!(!got);
+    counter.include(monday, "bob").await?;
+    counter.include(tuesday, "bob").await?;
+    counter.include(tuesday, "carol").await?;
+
+    let got = counter.count(monday).await?;
+    assert_eq!(2, got);
+    let got = counter.count(tuesday).await?;
+    assert_eq!(2, got);
+    let got = counter.count_week(tuesday).await?;
+    assert_eq!(3, got);
+    let got = counter.count_week(monday).await?;
+    assert_eq!(2, got);
+
+    Ok(())
+}
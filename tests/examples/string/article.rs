@@ -16,6 +16,7 @@ use std::collections::HashMap;
 
 use anyhow::Result;
 use aredis::Client;
+use bytes::Bytes;
 
 use crate::Utf8String;
 
@@ -31,10 +32,10 @@ struct Article {
 impl Article {
     pub fn new(client: Client, article_id: u64) -> Self {
         let id = article_id.to_string();
-        let title_key = format!("article::{}::title", &id);
-        let content_key = format!("article::{}::content", &id);
-        let author_key = format!("article::{}::author", &id);
-        let created_at_key = format!("article::{}::created_at", &id);
+        let title_key = format!("article::{}::title", id);
+        let content_key = format!("article::{}::content", id);
+        let author_key = format!("article::{}::author", id);
+        let created_at_key = format!("article::{}::created_at", id);
         Article {
             client,
             id,
@@ -123,7 +124,7 @@ impl Article {
 
     pub async fn get_content_preview<Out>(&mut self, preview_len: i64) -> Result<Out>
     where
-        Out: From<Vec<u8>>,
+        Out: From<Bytes>,
     {
         let key = self.content_key.as_str();
         let start = 0;
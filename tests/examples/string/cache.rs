@@ -12,8 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Duration;
+
 use anyhow::Result;
-use aredis::{command::SetOption, Client};
+use aredis::{
+    command::{Expiry, SetOption},
+    Client, KeyspaceEvent, KeyspaceEvents,
+};
+use bytes::Bytes;
+use futures::{future, Stream, StreamExt};
 
 use crate::Utf8String;
 
@@ -35,10 +42,34 @@ impl Cache {
         Ok(())
     }
 
+    pub async fn set_with_ttl<In0, In1>(
+        &mut self,
+        key: In0,
+        value: In1,
+        ttl: Duration,
+    ) -> Result<()>
+    where
+        In0: Into<Vec<u8>>,
+        In1: Into<Vec<u8>>,
+    {
+        self.client.pset_ex(key, ttl, value).await?;
+        Ok(())
+    }
+
+    pub async fn ttl<In>(&mut self, key: In) -> Result<Option<Duration>>
+    where
+        In: Into<Vec<u8>>,
+    {
+        match self.client.pttl(key).await? {
+            Expiry::Expire(ttl) => Ok(Some(ttl)),
+            Expiry::Missing | Expiry::Persistent => Ok(None),
+        }
+    }
+
     pub async fn get<In, Out>(&mut self, key: In) -> Result<Option<Out>>
     where
         In: Into<Vec<u8>>,
-        Out: From<Vec<u8>>,
+        Out: From<Bytes>,
     {
         Ok(self.client.get(key).await?)
     }
@@ -47,11 +78,22 @@ impl Cache {
     where
         In0: Into<Vec<u8>>,
         In1: Into<Vec<u8>>,
-        Out: From<Vec<u8>>,
+        Out: From<Bytes>,
     {
         let option = SetOption::default();
         Ok(self.client.get_set(key, value, option).await?)
     }
+
+    /// Keys of this cache as they expire, so that they can be refreshed.
+    pub async fn expirations(addr: String) -> Result<impl Stream<Item = Vec<u8>>> {
+        let events = KeyspaceEvents::new(addr).notify("Ex").subscribe().await?;
+        Ok(events.filter_map(|event| {
+            future::ready(match event {
+                Ok(KeyspaceEvent::Expired(key)) => Some(key),
+                _ => None,
+            })
+        }))
+    }
 }
 
 #[tokio::test]
@@ -81,5 +123,45 @@ async fn test_cache() -> Result<()> {
     assert!(got.is_some());
     assert_eq!(got.unwrap(), second.into());
 
+    let got = cache.ttl(key).await?;
+    assert!(got.is_none());
+
+    cache
+        .set_with_ttl(key, first, Duration::from_secs(60))
+        .await?;
+    let got = cache.ttl(key).await?;
+    assert!(got.unwrap() <= Duration::from_secs(60));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_cache_refresh() -> Result<()> {
+    crate::restoring_config("notify-keyspace-events", cache_refresh).await
+}
+
+async fn cache_refresh() -> Result<()> {
+    let client = crate::client().await?;
+    let mut cache = Cache::new(client);
+    let expirations = Cache::expirations(crate::addr()).await?;
+    futures::pin_mut!(expirations);
+
+    let key = "greeting-page";
+    let page = "<html><p>hello world</p></html>";
+    cache
+        .set_with_ttl(key, page, Duration::from_millis(50))
+        .await?;
+
+    let expired = expirations.next().await.unwrap();
+    assert_eq!(expired, key.as_bytes());
+    let got: Option<Utf8String> = cache.get(key).await?;
+    assert!(got.is_none());
+
+    cache
+        .set_with_ttl(expired, page, Duration::from_secs(60))
+        .await?;
+    let got: Option<Utf8String> = cache.get(key).await?;
+    assert_eq!(got.unwrap(), page.into());
     Ok(())
 }
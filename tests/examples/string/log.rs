@@ -14,6 +14,7 @@
 
 use anyhow::Result;
 use aredis::Client;
+use bytes::Bytes;
 
 use crate::Utf8String;
 
@@ -42,14 +43,14 @@ impl<K: Into<Vec<u8>> + Copy> Log<K> {
 
     pub async fn get_all<Out>(&mut self) -> Result<Vec<Out>>
     where
-        Out: From<Vec<u8>>,
+        Out: From<Bytes>,
     {
-        match self.client.get::<K, Vec<u8>>(self.key).await? {
+        match self.client.get::<K, Bytes>(self.key).await? {
             None => Ok(vec![]),
             Some(logs) => {
                 let mut result = vec![];
                 for log in logs.split(|sep| *sep == Self::LOG_SEPARATOR) {
-                    result.push(log.to_vec().into());
+                    result.push(logs.slice_ref(log).into());
                 }
                 result.pop(); // drop the tailing empty string
                 Ok(result)
@@ -12,21 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-#![feature(once_cell)]
-
 extern crate core;
 
+use std::{future::Future, panic::AssertUnwindSafe};
+
 use aredis::Client;
+use bytes::Bytes;
+use futures::FutureExt;
 
 mod commands;
 mod examples;
 
-#[derive(Debug, PartialOrd, PartialEq, Eq)]
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq)]
 pub struct Utf8String(String);
 
-impl From<Vec<u8>> for Utf8String {
-    fn from(bytes: Vec<u8>) -> Self {
-        let inner = String::from_utf8(bytes).unwrap();
+impl From<Bytes> for Utf8String {
+    fn from(bytes: Bytes) -> Self {
+        let inner = String::from_utf8(bytes.into()).unwrap();
         Self(inner)
     }
 }
@@ -37,10 +39,29 @@ impl From<&'static str> for Utf8String {
     }
 }
 
-pub async fn client() -> anyhow::Result<Client> {
+pub fn addr() -> String {
     let host = option_env!("REDIS_HOST").unwrap_or_else(|| "localhost");
     let port = option_env!("REDIS_PORT").unwrap_or_else(|| "6379");
-    let mut client = Client::connect(format!("{}:{}", host, port)).await?;
+    format!("{}:{}", host, port)
+}
+
+pub async fn client() -> anyhow::Result<Client> {
+    let mut client = Client::connect(addr()).await?;
     client.flush_all(true).await?;
     Ok(client)
 }
+
+/// Runs `test`, then restores the server's `parameter` to its value before,
+/// even if `test` fails or panics.
+pub async fn restoring_config<F, Fut>(parameter: &str, test: F) -> anyhow::Result<()>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = anyhow::Result<()>>,
+{
+    let mut client = Client::connect(addr()).await?;
+    let old = client.config_get(vec![parameter]).await?;
+    let old = old.get(parameter).map(String::as_str).unwrap_or_default();
+    let result = AssertUnwindSafe(test()).catch_unwind().await;
+    client.config_set(vec![(parameter, old)]).await?;
+    result.unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+}